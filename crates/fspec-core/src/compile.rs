@@ -1,23 +1,42 @@
 // Compilation module: converts ComponentAst to CompiledComponent with pre-compiled regexes
 
 use crate::error::Error;
-use crate::spec::CompiledComponent;
+use crate::spec::{CompiledComponent, MatchSettings};
 use fspec_placeholder::ast::{Choice, ComponentAst, Part, PlaceholderNode};
-use regex::Regex;
+use regex::bytes::Regex;
+
+/// Any bytes (not just UTF-8 characters), zero or more / one or more.
+const ANY_BYTES_STAR: &str = "(?s-u:.)*";
+const ANY_BYTES_PLUS: &str = "(?s-u:.)+";
 
 /// Compile a ComponentAst into a CompiledComponent with a pre-compiled regex.
 ///
 /// This extracts the regex pattern from the AST and compiles it once,
 /// storing both the compiled regex and the placeholder indices for efficient matching.
-pub fn compile_component(ast: &ComponentAst) -> Result<CompiledComponent, Error> {
+///
+/// `settings.case_insensitive` makes literals and one-of choices ignore case. Placeholders with
+/// the `i:` flag (`{ext:i:jpg|jpeg}`) ignore case either way. Limiters such as `snake_case`
+/// keep checking case unless the placeholder itself is flagged.
+pub fn compile_component(
+    ast: &ComponentAst,
+    settings: &MatchSettings,
+) -> Result<CompiledComponent, Error> {
+    let fold = |re: String, flagged: bool| {
+        if flagged { format!("(?i:{})", re) } else { re }
+    };
     let mut pat = String::from("^");
     let mut placeholder_indices: Vec<(String, usize)> = Vec::new(); // name -> capture group index
+    let mut value_checks = Vec::new(); // capture group index -> post-regex check
     let mut capture_group = 1;
 
     for part in &ast.parts {
         match part {
-            Part::Literal(lit) => pat.push_str(&regex::escape(&lit.value)),
-            Part::Star(_) => pat.push_str(".*"),
+            Part::Literal(lit) => {
+                let value = settings.normalization.apply(&lit.value);
+                pat.push_str(&fold(regex::escape(&value), settings.case_insensitive))
+            }
+            // `*` and bare captures match any bytes, including names that aren't valid UTF-8
+            Part::Star(_) => pat.push_str(ANY_BYTES_STAR),
             Part::Placeholder(ph) => match &ph.node {
                 PlaceholderNode::OneOf(oneof) => {
                     // Named one-of: extract the matched choice
@@ -28,10 +47,13 @@ pub fn compile_component(ast: &ComponentAst) -> Result<CompiledComponent, Error>
                                 Choice::Ident { value, .. } => value,
                                 Choice::Str { value, .. } => value,
                             };
-                            alts.push(regex::escape(s));
+                            alts.push(regex::escape(&settings.normalization.apply(s)));
                         }
                         pat.push('('); // capture group for named one-of
-                        pat.push_str(&alts.join("|"));
+                        pat.push_str(&fold(
+                            alts.join("|"),
+                            ph.case_insensitive || settings.case_insensitive,
+                        ));
                         pat.push(')');
                         placeholder_indices.push((named.name.clone(), capture_group));
                         capture_group += 1;
@@ -43,23 +65,29 @@ pub fn compile_component(ast: &ComponentAst) -> Result<CompiledComponent, Error>
                                 Choice::Ident { value, .. } => value,
                                 Choice::Str { value, .. } => value,
                             };
-                            alts.push(regex::escape(s));
+                            alts.push(regex::escape(&settings.normalization.apply(s)));
                         }
                         pat.push_str("(?:");
-                        pat.push_str(&alts.join("|"));
+                        pat.push_str(&fold(
+                            alts.join("|"),
+                            ph.case_insensitive || settings.case_insensitive,
+                        ));
                         pat.push(')');
                     }
                 }
                 PlaceholderNode::Capture(cap) => {
                     // Capture with name: extract the matched value
-                    let mut cap_re = String::from(".+");
+                    let mut cap_re = String::from(ANY_BYTES_PLUS);
 
                     if let Some(lim) = &cap.limiter {
                         cap_re = lim.to_regex_fragment();
+                        if let Some(check) = lim.value_check() {
+                            value_checks.push((capture_group, check));
+                        }
                     }
 
                     pat.push('('); // capture group for named capture
-                    pat.push_str(&cap_re);
+                    pat.push_str(&fold(cap_re, ph.case_insensitive));
                     pat.push(')');
                     placeholder_indices.push((cap.name.clone(), capture_group));
                     capture_group += 1;
@@ -79,6 +107,7 @@ pub fn compile_component(ast: &ComponentAst) -> Result<CompiledComponent, Error>
         ast: ast.clone(),
         regex,
         placeholder_indices,
+        value_checks,
     })
 }
 
@@ -91,23 +120,83 @@ mod tests {
     fn test_oneof_case_sensitivity() {
         // Test that one-of choices match both cases when explicitly provided
         let ast = parse_component("{snake|SNAKE}").unwrap();
-        let compiled = compile_component(&ast).unwrap();
+        let compiled = compile_component(&ast, &MatchSettings::default()).unwrap();
 
         // The regex should be ^(?:snake|SNAKE)$
-        assert!(compiled.regex.is_match("snake"));
-        assert!(compiled.regex.is_match("SNAKE"));
-        assert!(!compiled.regex.is_match("Snake")); // Mixed case shouldn't match
+        assert!(compiled.regex.is_match(b"snake"));
+        assert!(compiled.regex.is_match(b"SNAKE"));
+        assert!(!compiled.regex.is_match(b"Snake")); // Mixed case shouldn't match
     }
 
     #[test]
     fn test_oneof_with_literal_prefix() {
         // Test pattern like "test.{snake|SNAKE}"
         let ast = parse_component("test.{snake|SNAKE}").unwrap();
-        let compiled = compile_component(&ast).unwrap();
+        let compiled = compile_component(&ast, &MatchSettings::default()).unwrap();
+
+        assert!(compiled.regex.is_match(b"test.snake"));
+        assert!(compiled.regex.is_match(b"test.SNAKE"));
+        assert!(!compiled.regex.is_match(b"test.Snake"));
+    }
+
+    #[test]
+    fn test_case_insensitive_flag_and_setting() {
+        // `i:` flag: only the flagged placeholder ignores case
+        let compiled = compile_component(
+            &parse_component("img.{ext:i:jpg|jpeg}").unwrap(),
+            &MatchSettings::default(),
+        )
+        .unwrap();
+        assert!(compiled.regex.is_match(b"img.JPG"));
+        assert!(compiled.regex.is_match(b"img.Jpeg"));
+        assert!(!compiled.regex.is_match(b"IMG.jpg"));
+
+        // setting: literals and one-of choices ignore case, limiters still check case
+        let settings = MatchSettings {
+            case_insensitive: true,
+            ..MatchSettings::default()
+        };
+        let ast = parse_component("img_{n:snake_case}.{jpg|png}").unwrap();
+        let compiled = compile_component(&ast, &settings).unwrap();
+        assert!(compiled.regex.is_match(b"IMG_foo_bar.PNG"));
+        assert!(!compiled.regex.is_match(b"IMG_Foo_Bar.PNG"));
+    }
+
+    #[test]
+    fn test_int_digit_widths() {
+        let compiled = compile_component(
+            &parse_component("s{n:int(2+)}").unwrap(),
+            &MatchSettings::default(),
+        )
+        .unwrap();
+        assert!(!compiled.regex.is_match(b"s1"));
+        assert!(compiled.regex.is_match(b"s01"));
+        assert!(compiled.regex.is_match(b"s0123"));
+
+        let compiled = compile_component(
+            &parse_component("{n:int(2..3)}").unwrap(),
+            &MatchSettings::default(),
+        )
+        .unwrap();
+        assert!(!compiled.regex.is_match(b"1"));
+        assert!(compiled.regex.is_match(b"12"));
+        assert!(compiled.regex.is_match(b"123"));
+        assert!(!compiled.regex.is_match(b"1234"));
+    }
 
-        assert!(compiled.regex.is_match("test.snake"));
-        assert!(compiled.regex.is_match("test.SNAKE"));
-        assert!(!compiled.regex.is_match("test.Snake"));
+    #[test]
+    fn test_range_records_value_check() {
+        let compiled = compile_component(
+            &parse_component("{a:int(2)}_{y:range(1900,2100)}").unwrap(),
+            &MatchSettings::default(),
+        )
+        .unwrap();
+        assert!(compiled.regex.is_match(b"01_1999"));
+        assert_eq!(compiled.value_checks.len(), 1);
+        let (group, check) = &compiled.value_checks[0];
+        assert_eq!(*group, 2);
+        assert!(check.accepts("2100"));
+        assert!(!check.accepts("1899"));
     }
 
     #[test]
@@ -115,12 +204,18 @@ mod tests {
         // Test the actual pattern from golden_limiters.rs
         // Pattern: {name:snake_case}_{name}_{year:int(4)}.{snake|SNAKE}
         let ast = parse_component("{name:snake_case}_{name}_{year:int(4)}.{snake|SNAKE}").unwrap();
-        let compiled = compile_component(&ast).unwrap();
+        let compiled = compile_component(&ast, &MatchSettings::default()).unwrap();
 
         let test1 = "snaked_name_snaked_name_1999.snake";
         let test2 = "snaked_name_snaked_name_1999.SNAKE";
 
-        assert!(compiled.regex.is_match(test1), "Should match .snake");
-        assert!(compiled.regex.is_match(test2), "Should match .SNAKE");
+        assert!(
+            compiled.regex.is_match(test1.as_bytes()),
+            "Should match .snake"
+        );
+        assert!(
+            compiled.regex.is_match(test2.as_bytes()),
+            "Should match .SNAKE"
+        );
     }
 }
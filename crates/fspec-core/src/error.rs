@@ -1,6 +1,9 @@
 use fspec_placeholder::parser::ParseError;
 use std::path::PathBuf;
 
+use crate::report::Diagnostic;
+use crate::spec::Severity;
+
 #[derive(Debug)]
 pub enum Error {
     Io {
@@ -9,12 +12,22 @@ pub enum Error {
     },
     Parse {
         line: usize,
+        /// 1-based byte column where the problem starts.
         col: usize,
+        /// Width of the offending text in bytes (at least 1).
+        len: usize,
         msg: String,
+        /// The offending line of the `.fspec` file, when available.
+        source_line: Option<String>,
     },
     Semantic {
         msg: String,
     },
+    /// Everything wrong with an `.fspec` file, in line order.
+    Spec {
+        path: PathBuf,
+        errors: Vec<Error>,
+    },
 }
 
 impl From<std::io::Error> for Error {
@@ -26,45 +39,132 @@ impl From<std::io::Error> for Error {
     }
 }
 
-impl From<ParseError> for Error {
-    fn from(e: ParseError) -> Self {
-        // In component strings we don't expect newlines, so treat offset as "column".
-        let line = 1;
-        let col = e.at.saturating_add(1);
-
-        let span_str = e
-            .span
-            .as_ref()
-            .map(|s| format!("{}..{}", s.start, s.end))
-            .unwrap_or_else(|| "none".to_string());
+impl Error {
+    /// A parse error at `line`/`col` (1-based), underlining a single column.
+    pub(crate) fn parse(line: usize, col: usize, msg: impl Into<String>) -> Error {
+        Error::Parse {
+            line,
+            col,
+            len: 1,
+            msg: msg.into(),
+            source_line: None,
+        }
+    }
 
-        let tok_str = e
-            .source_tokenize
-            .as_ref()
-            .map(|s| format!(", tokenize={}", s))
-            .unwrap_or_default();
+    /// A placeholder parse error in a pattern component that starts at `line`/`col`. The
+    /// error's offsets are relative to the component.
+    pub(crate) fn from_component(e: ParseError, line: usize, col: usize) -> Error {
+        let (start, len) = match e.span {
+            Some(s) => (s.start, s.end.saturating_sub(s.start).max(1)),
+            None => (e.at, 1),
+        };
+        Error::Parse {
+            line,
+            col: col + start,
+            len,
+            msg: e.message,
+            source_line: None,
+        }
+    }
 
-        let msg = format!(
-            "{} (kind={:?}, at={}, span={}{} )",
-            e.message, e.kind, e.at, span_str, tok_str
-        );
+    /// The error as diagnostics, one per problem (an [`Error::Spec`] yields one for each of its
+    /// errors). Parse errors carry their line in `rule_lines` and their column in the message.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let diag = |code, path: String, message, rule_lines| Diagnostic {
+            code,
+            severity: Severity::Error,
+            path,
+            message,
+            rule_lines,
+        };
+        match self {
+            Error::Spec { path, errors } => errors
+                .iter()
+                .flat_map(|e| e.diagnostics())
+                .map(|mut d| {
+                    if d.path.is_empty() {
+                        d.path = path.display().to_string();
+                    }
+                    d
+                })
+                .collect(),
+            Error::Parse { line, col, msg, .. } => vec![diag(
+                "parse_error",
+                String::new(),
+                format!("column {}: {}", col, msg),
+                vec![*line],
+            )],
+            Error::Semantic { msg } => vec![diag("spec_error", String::new(), msg.clone(), vec![])],
+            Error::Io { path, source } => vec![diag(
+                "io_error",
+                path.display().to_string(),
+                source.to_string(),
+                vec![],
+            )],
+        }
+    }
 
-        Error::Parse { line, col, msg }
+    /// Attach the text of the offending line, so the error renders with a snippet.
+    pub(crate) fn with_source_line(mut self, text: &str) -> Error {
+        if let Error::Parse { source_line, .. } = &mut self {
+            source_line.get_or_insert_with(|| text.to_string());
+        }
+        self
     }
 }
 
+/// Render `text` with a caret underline from byte column `col` (1-based), `len` bytes wide.
+fn write_snippet(
+    f: &mut std::fmt::Formatter<'_>,
+    line: usize,
+    col: usize,
+    len: usize,
+    text: &str,
+) -> std::fmt::Result {
+    let start = (col - 1).min(text.len());
+    let end = (start + len).min(text.len());
+    let (Some(before), Some(under)) = (text.get(..start), text.get(start..end)) else {
+        return Ok(());
+    };
+    // Keep tabs so the caret lines up with the text above it.
+    let pad: String = before
+        .chars()
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let carets = "^".repeat(under.chars().count().max(1));
+    let gutter = " ".repeat(line.to_string().len());
+    write!(f, "\n{gutter} |\n{line} | {text}\n{gutter} | {pad}{carets}")
+}
+
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Error::Io { path, source } => {
                 write!(f, "I/O error reading {}: {}", path.display(), source)
             }
-            Error::Parse { line, col, msg } => {
-                write!(f, "Parse error at line {}, column {}: {}", line, col, msg)
+            Error::Parse {
+                line,
+                col,
+                len,
+                msg,
+                source_line,
+            } => {
+                write!(f, "Parse error at line {}, column {}: {}", line, col, msg)?;
+                match source_line {
+                    Some(text) => write_snippet(f, *line, *col, *len, text),
+                    None => Ok(()),
+                }
             }
             Error::Semantic { msg } => {
                 write!(f, "{}", msg)
             }
+            Error::Spec { path, errors } => {
+                for e in errors {
+                    writeln!(f, "{}\n", e)?;
+                }
+                let plural = if errors.len() == 1 { "" } else { "s" };
+                write!(f, "{} error{} in {}", errors.len(), plural, path.display())
+            }
         }
     }
 }
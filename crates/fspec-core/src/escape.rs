@@ -0,0 +1,87 @@
+// Escaped text form of OS paths.
+//
+// Report keys, captures and diagnostics are strings. `to_string_lossy()` would map every
+// invalid byte to U+FFFD, so two different names could collapse into one key. Instead,
+// invalid bytes are written as `\xNN` and `\` as `\\` in every name, so a name that
+// really contains `\xE9` can't be mistaken for one with an invalid byte. Other valid UTF-8
+// is unchanged.
+
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::path::Path;
+
+/// `bytes` as text, with `\` escaped as `\\` and invalid UTF-8 bytes as `\xNN`.
+pub(crate) fn escape_bytes(bytes: &[u8]) -> Cow<'_, str> {
+    if let Ok(s) = std::str::from_utf8(bytes)
+        && !s.contains('\\')
+    {
+        return Cow::Borrowed(s);
+    }
+    let mut out = String::with_capacity(bytes.len() + 8);
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            if c == '\\' {
+                out.push_str("\\\\");
+            } else {
+                out.push(c);
+            }
+        }
+        for b in chunk.invalid() {
+            out.push_str(&format!("\\x{:02X}", b));
+        }
+    }
+    Cow::Owned(out)
+}
+
+/// A single name (path component) as text, see [`escape_bytes`].
+pub(crate) fn escape_os_str(s: &OsStr) -> Cow<'_, str> {
+    match s.to_str() {
+        Some(s) if !s.contains('\\') => Cow::Borrowed(s),
+        _ => Cow::Owned(escape_bytes(s.as_encoded_bytes()).into_owned()),
+    }
+}
+
+/// A relative path as `/`-separated text, escaping each component, see [`escape_bytes`].
+/// On Windows `\` is a separator, so it never appears in a name.
+pub(crate) fn escape_path(p: &Path) -> String {
+    if let Some(s) = p.to_str() {
+        if cfg!(windows) {
+            return s.replace('\\', "/");
+        }
+        if !s.contains('\\') {
+            return s.to_string();
+        }
+    }
+    p.iter().map(escape_os_str).collect::<Vec<_>>().join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_utf8_is_unchanged_except_backslashes() {
+        assert_eq!(escape_bytes("café".as_bytes()), "café");
+        assert_eq!(escape_bytes(br"a\b"), r"a\\b");
+    }
+
+    #[test]
+    fn invalid_bytes_are_escaped_distinctly() {
+        // Latin-1 "café" and "cafè"
+        assert_eq!(escape_bytes(b"caf\xe9"), r"caf\xE9");
+        assert_eq!(escape_bytes(b"caf\xe8"), r"caf\xE8");
+        // a literal backslash in an invalid name can't be mistaken for an escape
+        assert_eq!(escape_bytes(b"a\\xE9\xff"), r"a\\xE9\xFF");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn paths_escape_per_component() {
+        use std::os::unix::ffi::OsStrExt;
+        let p = Path::new(OsStr::from_bytes(b"docs/r\xe9sum\xe9.txt"));
+        assert_eq!(escape_path(p), r"docs/r\xE9sum\xE9.txt");
+        assert_eq!(escape_path(Path::new("docs/a.txt")), "docs/a.txt");
+        // a real `\xE9` in a valid name differs from an invalid byte
+        assert_eq!(escape_path(Path::new(r"docs/caf\xE9")), r"docs/caf\\xE9");
+    }
+}
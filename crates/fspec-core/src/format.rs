@@ -0,0 +1,284 @@
+// `fspec fmt`: rewrite an `.fspec` file in canonical form.
+//
+// Each line is parsed on its own, with the byte range of every token: keyword, anchor, path
+// components (and within them the spans `ComponentAst` records) and `where` predicates. The
+// line is then rebuilt token by token, copying text that is already canonical from the source
+// and rewriting only the rest: an implied `allow` is written out, `/` anchoring becomes `./`,
+// placeholders take their canonical form (see `fspec_placeholder`'s `Display` impls), quotes
+// a literal doesn't need are dropped, and separators become single spaces. A canonical spec
+// therefore comes out byte for byte as it went in. Comments and rule order are kept; runs of
+// blank lines become one.
+
+use std::fmt;
+
+use fspec_placeholder::ast::{ComponentAst, Part};
+
+use crate::error::Error;
+use crate::parse::{ParsedSpec, RuleSpans, parse_line};
+use crate::predicate::spaced;
+use crate::spec::{
+    DirType, FSEntry, FSPattern, FileOrDirType, FileType, MatchSettings, Rule, RuleKind,
+};
+
+/// Format `.fspec` source. Returns every parse error if any line doesn't parse.
+pub(crate) fn format_source(src: &str, settings: &MatchSettings) -> Result<String, Vec<Error>> {
+    let mut out = String::new();
+    let mut errors = Vec::new();
+    let mut pending_blank = false;
+
+    for (idx, raw_line) in src.lines().enumerate() {
+        let line = raw_line.strip_suffix('\r').unwrap_or(raw_line);
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            pending_blank = !out.is_empty();
+            continue;
+        }
+        if pending_blank {
+            out.push('\n');
+            pending_blank = false;
+        }
+        if trimmed.starts_with('#') {
+            out.push_str(trimmed);
+            out.push('\n');
+            continue;
+        }
+
+        let mut spec = ParsedSpec::default();
+        match parse_line(line, idx + 1, settings, &mut spec) {
+            Ok(Some(spans)) => {
+                format_rule(&mut out, line, &spec.rules[0], &spans);
+                out.push('\n');
+            }
+            Ok(None) => {
+                out.push_str(&directive_line(&spec));
+                out.push('\n');
+            }
+            Err(e) => errors.push(e.with_source_line(line)),
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(out)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Write the canonical form of rule `line`, parsed as `rule` with its tokens at `spans`.
+fn format_rule(out: &mut String, line: &str, rule: &Rule, spans: &RuleSpans) {
+    out.push_str(match &spans.keyword {
+        Some(keyword) => &line[keyword.clone()],
+        None => "allow",
+    });
+    out.push(' ');
+
+    let anchor = &line[spans.pattern.anchor.clone()];
+    out.push_str(if anchor == "/" { "./" } else { anchor });
+    let entries = match &rule.pattern {
+        FSPattern::Anchored(e) | FSPattern::Unanchored(e) => e,
+    };
+    for (i, (entry, range)) in entries.iter().zip(&spans.pattern.components).enumerate() {
+        if i > 0 {
+            out.push('/');
+        }
+        let src = &line[range.clone()];
+        match entry {
+            FSEntry::Dir(DirType::Component(c))
+            | FSEntry::File(FileType::Component(c))
+            | FSEntry::Either(FileOrDirType::Component(c)) => format_component(out, src, &c.ast),
+            // `*` and `**` are only ever spelled one way
+            _ => out.push_str(src),
+        }
+    }
+    // A directory leaf is written with a trailing slash.
+    if let Some(FSEntry::Dir(_)) = entries.last() {
+        out.push('/');
+    }
+
+    for (i, range) in spans.predicates.iter().enumerate() {
+        out.push_str(if i == 0 { " where " } else { " and " });
+        out.push_str(&spaced(&line[range.clone()]));
+    }
+}
+
+/// Write the canonical form of component `src`, parsed as `ast`.
+///
+/// Placeholders that aren't already canonical are reprinted. A run of literal text that
+/// needs quoting keeps the quotes as written (`"a where b".txt` and `"a where b.txt"` are
+/// both fine); one that doesn't is written bare.
+fn format_component(out: &mut String, src: &str, ast: &ComponentAst) {
+    let mut parts = ast.parts.iter().peekable();
+    while let Some(part) = parts.next() {
+        match part {
+            Part::Star(_) => out.push('*'),
+            Part::Placeholder(ph) => {
+                let canonical = ph.to_string();
+                let written = &src[ph.span.start..ph.span.end];
+                out.push_str(if written == canonical {
+                    written
+                } else {
+                    &canonical
+                });
+            }
+            Part::Literal(first) => {
+                // Adjacent literals (`ab"*"`) are one run of text.
+                let mut run = first.clone();
+                while let Some(Part::Literal(next)) = parts.peek() {
+                    run.value.push_str(&next.value);
+                    run.span.end = next.span.end;
+                    parts.next();
+                }
+                let written = &src[run.span.start..run.span.end];
+                let canonical = ComponentAst {
+                    parts: vec![Part::Literal(run)],
+                }
+                .to_string();
+                let quoted = canonical.starts_with('"');
+                out.push_str(if quoted { written } else { &canonical });
+            }
+        }
+    }
+}
+
+/// The canonical text of a parsed directive line.
+fn directive_line(spec: &ParsedSpec) -> String {
+    if let Some(m) = spec.manifests.first() {
+        let path: Vec<_> = m.path.iter().map(|c| c.to_string_lossy()).collect();
+        return format!("manifest ./{}", path.join("/"));
+    }
+    let limits = spec.limits;
+    let (name, limit) = [
+        ("max-depth", limits.max_depth),
+        ("max-path-length", limits.max_path_length),
+        ("max-name-length", limits.max_name_length),
+        ("max-entries", limits.max_entries),
+    ]
+    .into_iter()
+    .find_map(|(name, l)| Some((name, l?)))
+    .expect("a parsed non-comment line is a rule or a directive");
+    format!("{} {}", name, limit.value)
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kw = match self.kind {
+            RuleKind::Allow => "allow",
+            RuleKind::Ignore => "ignore",
+            RuleKind::Deny => "deny",
+        };
+        write!(f, "{} {}", kw, self.pattern)?;
+        for (i, p) in self.predicates.iter().enumerate() {
+            let sep = if i == 0 { " where " } else { " and " };
+            write!(f, "{}{}", sep, p)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for FSPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = match self {
+            FSPattern::Anchored(e) => {
+                f.write_str("./")?;
+                e
+            }
+            FSPattern::Unanchored(e) => e,
+        };
+        for (i, entry) in entries.iter().enumerate() {
+            if i > 0 {
+                f.write_str("/")?;
+            }
+            match entry {
+                FSEntry::Dir(DirType::DoubleStar) => f.write_str("**")?,
+                FSEntry::Dir(DirType::Star)
+                | FSEntry::File(FileType::Star)
+                | FSEntry::Either(FileOrDirType::Star) => f.write_str("*")?,
+                FSEntry::Dir(DirType::Component(c))
+                | FSEntry::File(FileType::Component(c))
+                | FSEntry::Either(FileOrDirType::Component(c)) => write!(f, "{}", c.ast)?,
+            }
+        }
+        // A directory leaf is written with a trailing slash.
+        if let Some(FSEntry::Dir(_)) = entries.last() {
+            f.write_str("/")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(src: &str) -> String {
+        format_source(src, &MatchSettings::default()).unwrap()
+    }
+
+    #[test]
+    fn normalizes_keywords_anchoring_and_braces() {
+        let src = "\n\n  # media\n/movies/{ year : int(4) }/*.mkv\n\n\n\
+                   ignore   **/*.tmp   \n\
+                   deny ./x/{e: \"mp4\"|mkv} where size<1024 and not  executable\n\
+                   max-depth   6\nmanifest /data/SHA256SUMS\n\n";
+        assert_eq!(
+            fmt(src),
+            "# media\nallow ./movies/{year:int(4)}/*.mkv\n\n\
+             ignore **/*.tmp\n\
+             deny ./x/{e:mp4|mkv} where size < 1024 and not executable\n\
+             max-depth 6\nmanifest ./data/SHA256SUMS\n"
+        );
+    }
+
+    #[test]
+    fn keeps_directory_leaves_and_quoting() {
+        let src = "./target/\nassets/\"a where b\".txt\n\"#notes\".md\n\"plain\"*.txt\n";
+        assert_eq!(
+            fmt(src),
+            "allow ./target/\nallow assets/\"a where b\".txt\nallow #notes.md\nallow plain*.txt\n"
+        );
+    }
+
+    #[test]
+    fn canonical_spec_is_unchanged_byte_for_byte() {
+        // quotes placed anywhere that covers what needs them, and predicate values as written
+        let src = "# media\n\
+                   allow ./movies/\"a where b\".txt\n\
+                   allow ./{year:int(4)}/ab\"*\"c_{ext:mp4|\"m 4v\"}\n\
+                   ignore \" lead\"ing/*.\"{x}\"\n\
+                   deny **/*.bin where size >= 1024 and mode == 644 and not executable\n\
+                   \n\
+                   max-depth 6\n\
+                   manifest ./data/SHA256SUMS\n";
+        assert_eq!(fmt(src), src);
+    }
+
+    #[test]
+    fn formatted_spec_parses_to_the_same_rules() {
+        let src = "# keep\n/a/{ n:re(\"x\"\"y\") }_{:i: a|b}/**/*\n\n\n\"#x\".md where  size>1KB\n";
+        let rules = |s: &str| -> Vec<String> {
+            let spec = crate::parse::parse_fspec(s, &MatchSettings::default()).unwrap();
+            spec.rules.iter().map(|r| r.to_string()).collect()
+        };
+        let out = fmt(src);
+        assert_eq!(rules(&out), rules(src));
+        assert!(out.starts_with("# keep\n"));
+    }
+
+    #[test]
+    fn formatting_is_idempotent() {
+        let src = "/a/{ n:re(\"x\"\"y\") }_{:i: a|b}/**/*\nignore ./.git/\n# end";
+        let once = fmt(src);
+        assert_eq!(fmt(&once), once);
+    }
+
+    #[test]
+    fn reports_every_bad_line() {
+        let errors = format_source(
+            "allow ./{x\nallow ok\nmax-depth {x\n",
+            &MatchSettings::default(),
+        )
+        .unwrap_err();
+        assert_eq!(errors.len(), 2);
+    }
+}
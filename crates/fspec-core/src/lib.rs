@@ -1,20 +1,33 @@
 mod compile;
 mod error;
+mod escape;
+mod format;
+mod lint;
+mod magic;
+mod manifest;
 mod matcher;
 mod parse;
 mod pattern;
+mod predicate;
 mod report;
 mod spec;
 mod walk;
 
 use parse::parse_fspec;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
 
 pub use error::Error;
-pub use report::Report;
-pub use spec::{DirType, FSEntry, FSPattern, FileType, MatchSettings, Rule, RuleKind, Severity};
-pub use walk::{WalkCtx, WalkOutput};
+pub use fspec_placeholder::Conformance;
+pub use lint::lint_rules;
+pub use predicate::{CmpOp, Predicate};
+pub use report::{Diagnostic, Report, ReportEntry, Status, Summary};
+pub use spec::{
+    Captures, DirType, EntryKind, FSEntry, FSPattern, FileType, Limit, Limits, MatchSettings,
+    NormalizationForm, Rule, RuleKind, Severity,
+};
+pub use walk::{RuleMatch, WalkCtx, WalkEntry, WalkOutput, WalkVisitor};
 
 /// Check a directory tree against an `.fspec` file located at `{root}/.fspec`.
 ///
@@ -57,12 +70,199 @@ pub fn check_tree_with_spec(
     spec_path: Option<&Path>,
     settings: &MatchSettings,
 ) -> Result<Report, Error> {
-    // --- parse .fspec ---
     let fspec_path: PathBuf = match spec_path {
         Some(p) => p.to_path_buf(),
         None => root.join(".fspec"),
     };
 
+    Spec::from_file(&fspec_path, settings)?.check(root)
+}
+
+/// A parsed `.fspec`, ready to check any number of trees or individual paths without
+/// re-reading it.
+///
+/// ```
+/// use fspec_core::{EntryKind, MatchSettings, Spec, Status};
+///
+/// let spec = Spec::parse("allow ./incoming/*.mkv\n", &MatchSettings::default()).unwrap();
+/// let entry = spec.classify("incoming/a.mkv", EntryKind::File);
+/// assert_eq!(entry.status, Status::Allowed);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Spec {
+    parsed: parse::ParsedSpec,
+    settings: MatchSettings,
+    skipped: Vec<PathBuf>,
+}
+
+impl Spec {
+    /// Parse `.fspec` source text. Returns every parse error (each an [`Error::Parse`] with
+    /// its line, column and width) if the text doesn't parse.
+    pub fn parse(src: &str, settings: &MatchSettings) -> Result<Spec, Vec<Error>> {
+        Ok(Spec {
+            parsed: parse_fspec(src, settings)?,
+            settings: *settings,
+            skipped: Vec::new(),
+        })
+    }
+
+    /// Read and parse the `.fspec` file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is missing or can't be read, or [`Error::Spec`] with every
+    /// line that doesn't parse.
+    pub fn from_file(path: &Path, settings: &MatchSettings) -> Result<Spec, Error> {
+        Ok(Spec {
+            parsed: load_spec(path, settings)?,
+            settings: *settings,
+            skipped: Vec::new(),
+        })
+    }
+
+    /// Parse `src` as if it followed the spec's last line, adding its rules and directives.
+    /// Its rules therefore win over the spec's. Used for rules that come from somewhere other
+    /// than the spec file, such as presets: they're tagged with `source`, and entries they
+    /// decide have no [`ReportEntry::rule_line`].
+    ///
+    /// On error, returns every line of `src` that doesn't parse; the spec keeps the lines
+    /// that did.
+    pub fn extend(&mut self, source: &str, src: &str) -> Result<(), Vec<Error>> {
+        let first = self.parsed.rules.len();
+        let result = parse::parse_more(src, &self.settings, &mut self.parsed);
+        let source: Arc<str> = source.into();
+        for rule in &mut self.parsed.rules[first..] {
+            rule.source = Some(source.clone());
+        }
+        result
+    }
+
+    /// Leave the file at `path` out of every walk, as `.fspec` in the root is. Used for files
+    /// that configure the check rather than belong to the tree, such as `fspec.toml`. A path
+    /// outside the walked tree has no effect.
+    pub fn skip_file(&mut self, path: impl Into<PathBuf>) {
+        self.skipped.push(path.into());
+    }
+
+    /// The spec's rules, in file order.
+    pub fn rules(&self) -> &[Rule] {
+        &self.parsed.rules
+    }
+
+    /// The limits set by the spec's directive lines.
+    pub fn limits(&self) -> &Limits {
+        &self.parsed.limits
+    }
+
+    /// The settings the spec was parsed with, which [`Spec::check`] and [`Spec::classify`]
+    /// also match with.
+    pub fn settings(&self) -> &MatchSettings {
+        &self.settings
+    }
+
+    /// Walk the tree at `root` and check it against the spec, as [`check_tree_with_spec`] does.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an I/O error occurs while walking the tree.
+    pub fn check(&self, root: &Path) -> Result<Report, Error> {
+        let mut walk_output = WalkOutput::default();
+        self.walk(root, &mut walk_output)?;
+        Ok(Report::from_walk_output(&walk_output, &self.settings))
+    }
+
+    /// Walk the tree at `root`, handing each entry and diagnostic to `visitor` as soon as it's
+    /// decided rather than collecting them. Memory use is bounded by the depth of the tree and
+    /// the size of the largest directory, not the number of entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an I/O error occurs while walking the tree. Anything visited before
+    /// then has already been handed over.
+    pub fn walk(&self, root: &Path, visitor: &mut dyn WalkVisitor) -> Result<(), Error> {
+        walk::walk_tree(root, &self.parsed, &self.settings, &self.skipped, visitor)
+    }
+
+    /// Decide a single path, relative to the spec's directory, without touching the
+    /// filesystem. A leading `./` or `/` is ignored. Ancestors are decided first, so a path
+    /// under an ignored directory is ignored unless a rule re-allows it.
+    ///
+    /// Only names are available, so rules with a `where` clause never match, and a directory
+    /// is only allowed by a rule of its own rather than because something below it is. Limits
+    /// and manifests aren't checked; see [`Spec::denied_by`] for `deny` rules.
+    pub fn classify(&self, path: impl AsRef<Path>, kind: EntryKind) -> ReportEntry {
+        let rel = relative(path.as_ref());
+        let (status, by) = walk::classify_path(&self.parsed, &self.settings, &rel, kind);
+        let (rule_line, captures) = match by {
+            Some(m) => (m.spec_line(), m.captures),
+            None => (None, Captures::new()),
+        };
+        ReportEntry {
+            status,
+            kind: Some(kind),
+            rule_line,
+            captures,
+        }
+    }
+
+    /// Lines of the `deny` rules that match a path, with the same caveats as
+    /// [`Spec::classify`]: rules with a `where` clause are left out.
+    pub fn denied_by(&self, path: impl AsRef<Path>, kind: EntryKind) -> Vec<usize> {
+        let rel = relative(path.as_ref());
+        walk::denied_lines(&self.parsed, &self.settings, &rel, kind)
+    }
+}
+
+/// `path` with any leading `./` or `/` (and other non-name components) dropped.
+fn relative(path: &Path) -> PathBuf {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Lint an `.fspec` file without walking any tree: rules shadowed by later ones, duplicated
+/// rules, patterns that can never match, unknown limiters (when lenient) and single-use
+/// placeholder names. See [`lint_rules`].
+///
+/// # Errors
+///
+/// Returns an error if the file is missing, can't be read, or doesn't parse.
+pub fn lint_spec(spec_path: &Path, settings: &MatchSettings) -> Result<Vec<Diagnostic>, Error> {
+    let spec = Spec::from_file(spec_path, settings)?;
+    Ok(lint_rules(spec.rules()))
+}
+
+/// Parse and lint `.fspec` source text, such as an unsaved editor buffer. Returns every parse
+/// error (each an [`Error::Parse`] with its line, column and width) if the text doesn't parse.
+pub fn lint_source(src: &str, settings: &MatchSettings) -> Result<Vec<Diagnostic>, Vec<Error>> {
+    let spec = Spec::parse(src, settings)?;
+    Ok(lint_rules(spec.rules()))
+}
+
+/// The canonical form of the `.fspec` file at `spec_path`: explicit `allow` keywords, `./`
+/// anchoring, no whitespace inside braces and minimal quoting, with comments and rule order
+/// kept. Formatting canonical text returns it unchanged.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read, or [`Error::Spec`] with every line that
+/// doesn't parse.
+pub fn format_spec(spec_path: &Path, settings: &MatchSettings) -> Result<String, Error> {
+    let contents = fs::read_to_string(spec_path).map_err(|e| Error::Io {
+        path: spec_path.to_path_buf(),
+        source: e,
+    })?;
+    format::format_source(&contents, settings).map_err(|errors| Error::Spec {
+        path: spec_path.to_path_buf(),
+        errors,
+    })
+}
+
+/// Read and parse the `.fspec` file at `fspec_path`.
+fn load_spec(fspec_path: &Path, settings: &MatchSettings) -> Result<parse::ParsedSpec, Error> {
     if !fspec_path.exists() {
         return Err(Error::Semantic {
             msg: format!(
@@ -82,14 +282,13 @@ pub fn check_tree_with_spec(
         });
     }
 
-    let contents = fs::read_to_string(&fspec_path).map_err(|e| Error::Io {
-        path: fspec_path.clone(),
+    let contents = fs::read_to_string(fspec_path).map_err(|e| Error::Io {
+        path: fspec_path.to_path_buf(),
         source: e,
     })?;
 
-    let spec_rules = parse_fspec(&contents, settings)?;
-    let walk_output = walk::walk_tree(root, &spec_rules)?;
-    let report = Report::from_walk_output(&walk_output);
-
-    Ok(report)
+    parse_fspec(&contents, settings).map_err(|errors| Error::Spec {
+        path: fspec_path.to_path_buf(),
+        errors,
+    })
 }
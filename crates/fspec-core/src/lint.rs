@@ -0,0 +1,434 @@
+// Static checks on a parsed spec, without walking a tree.
+//
+// Rule coverage is decided structurally and conservatively: a rule is only called shadowed
+// when a later rule provably matches everything it does. Components are compared by their
+// AST, except that a literal component is also covered by any component that matches it.
+// A component sharing a placeholder name with another one must capture an equal value, so it
+// only covers an identical component, whose own rule already makes the values equal.
+
+use std::collections::BTreeMap;
+
+use fspec_placeholder::parser::ParseErrorKind;
+use fspec_placeholder::{Conformance, Part, validate_component};
+
+use crate::matcher::matches_compiled_component;
+use crate::report::Diagnostic;
+use crate::spec::{
+    CompiledComponent, DirType, FSEntry, FSPattern, FileOrDirType, FileType, Rule, RuleKind,
+    Severity,
+};
+
+/// Lint a spec's rules (in file order). Diagnostics have an empty `path`, and name the rules
+/// involved in `rule_lines`.
+pub fn lint_rules(rules: &[Rule]) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    let shapes: Vec<Option<Shape>> = rules.iter().map(|r| Shape::of(&r.pattern)).collect();
+
+    for (i, rule) in rules.iter().enumerate() {
+        let Some(shape) = &shapes[i] else {
+            diags.push(diag(
+                "unmatchable_rule",
+                Severity::Warning,
+                "rule can never match: '**/' as the last segment matches nothing".to_string(),
+                vec![rule.line],
+            ));
+            continue;
+        };
+        if ends_in_double_star_name(&rule.pattern) {
+            diags.push(diag(
+                "double_star_leaf",
+                Severity::Warning,
+                "rule ends in '**', which matches a single name here (like '*'); \
+                 use '**/*' for everything below"
+                    .to_string(),
+                vec![rule.line],
+            ));
+        }
+        if let Some(d) = overriding_rule(rules, &shapes, i, shape) {
+            diags.push(d);
+        }
+    }
+
+    diags.extend(unknown_limiters(rules));
+    diags.extend(single_use_placeholders(rules));
+    diags.sort_by_key(|d| d.rule_lines.first().copied());
+    diags
+}
+
+fn diag(
+    code: &'static str,
+    severity: Severity,
+    message: String,
+    rule_lines: Vec<usize>,
+) -> Diagnostic {
+    Diagnostic {
+        code,
+        severity,
+        path: String::new(),
+        message,
+        rule_lines,
+    }
+}
+
+/// The first later rule that makes rule `i` pointless: an identical rule, or (for rules that
+/// take part in last-match-wins) one that matches everything it does and always applies.
+fn overriding_rule(
+    rules: &[Rule],
+    shapes: &[Option<Shape>],
+    i: usize,
+    shape: &Shape,
+) -> Option<Diagnostic> {
+    let rule = &rules[i];
+    for (later, later_shape) in rules.iter().zip(shapes).skip(i + 1) {
+        if later.kind == rule.kind
+            && later.predicates == rule.predicates
+            && same_pattern(&later.pattern, &rule.pattern)
+        {
+            return Some(diag(
+                "duplicate_rule",
+                Severity::Warning,
+                format!(
+                    "rule on line {} is repeated on line {}",
+                    rule.line, later.line
+                ),
+                vec![rule.line, later.line],
+            ));
+        }
+        let decides = |r: &Rule| r.kind != RuleKind::Deny;
+        if !decides(rule) || !decides(later) || !later.predicates.is_empty() {
+            continue;
+        }
+        let Some(later_shape) = later_shape else {
+            continue;
+        };
+        if !later_shape.covers(shape) {
+            continue;
+        }
+        let (code, what) = match (rule.kind, later.kind) {
+            (RuleKind::Ignore, RuleKind::Allow) => ("ignore_overridden", "re-allowed"),
+            _ => ("shadowed_rule", "shadowed"),
+        };
+        return Some(diag(
+            code,
+            Severity::Warning,
+            format!(
+                "rule on line {} never decides anything: everything it matches is {} by line {}",
+                rule.line, what, later.line
+            ),
+            vec![rule.line, later.line],
+        ));
+    }
+    None
+}
+
+/// Limiter names that aren't known. Only possible with lenient conformance; strict parsing
+/// rejects them.
+fn unknown_limiters(rules: &[Rule]) -> Vec<Diagnostic> {
+    let mut diags = Vec::new();
+    for rule in rules {
+        for c in components(&rule.pattern) {
+            if let Err(e) = validate_component(&c.ast, Conformance::Strict)
+                && e.kind == ParseErrorKind::UnknownLimiter
+            {
+                diags.push(diag(
+                    "unknown_limiter",
+                    Severity::Warning,
+                    e.message,
+                    vec![rule.line],
+                ));
+            }
+        }
+    }
+    diags
+}
+
+/// Placeholder names that appear once in their rule and in no other rule, so nothing is
+/// compared against them.
+fn single_use_placeholders(rules: &[Rule]) -> Vec<Diagnostic> {
+    let per_rule: Vec<BTreeMap<&str, usize>> = rules
+        .iter()
+        .map(|rule| {
+            let mut counts = BTreeMap::new();
+            for c in components(&rule.pattern) {
+                // anonymous placeholders (`{:int(4)}`) have an empty name
+                for (name, _) in c.placeholder_indices.iter().filter(|(n, _)| !n.is_empty()) {
+                    *counts.entry(name.as_str()).or_insert(0) += 1;
+                }
+            }
+            counts
+        })
+        .collect();
+
+    let mut diags = Vec::new();
+    for (i, (rule, counts)) in rules.iter().zip(&per_rule).enumerate() {
+        for (&name, &n) in counts {
+            let elsewhere = per_rule
+                .iter()
+                .enumerate()
+                .any(|(j, other)| j != i && other.contains_key(name));
+            if n == 1 && !elsewhere {
+                diags.push(diag(
+                    "single_use_placeholder",
+                    Severity::Info,
+                    format!(
+                        "placeholder '{}' is used once and referenced nowhere else",
+                        name
+                    ),
+                    vec![rule.line],
+                ));
+            }
+        }
+    }
+    diags
+}
+
+fn entries(pattern: &FSPattern) -> &[FSEntry] {
+    match pattern {
+        FSPattern::Anchored(e) | FSPattern::Unanchored(e) => e,
+    }
+}
+
+fn components(pattern: &FSPattern) -> impl Iterator<Item = &CompiledComponent> {
+    entries(pattern).iter().filter_map(|e| match e {
+        FSEntry::Dir(DirType::Component(c))
+        | FSEntry::File(FileType::Component(c))
+        | FSEntry::Either(FileOrDirType::Component(c)) => Some(c),
+        _ => None,
+    })
+}
+
+/// A leaf written as `**` without a trailing slash is an ordinary component.
+fn ends_in_double_star_name(pattern: &FSPattern) -> bool {
+    let leaf = match entries(pattern).last() {
+        Some(FSEntry::File(FileType::Component(c)))
+        | Some(FSEntry::Either(FileOrDirType::Component(c))) => c,
+        _ => return false,
+    };
+    matches!(leaf.ast.parts[..], [Part::Star(_), Part::Star(_)])
+}
+
+fn same_pattern(a: &FSPattern, b: &FSPattern) -> bool {
+    let same_entry = |x: &FSEntry, y: &FSEntry| match (x, y) {
+        (FSEntry::Dir(DirType::Component(p)), FSEntry::Dir(DirType::Component(q)))
+        | (FSEntry::File(FileType::Component(p)), FSEntry::File(FileType::Component(q)))
+        | (
+            FSEntry::Either(FileOrDirType::Component(p)),
+            FSEntry::Either(FileOrDirType::Component(q)),
+        ) => p.ast == q.ast,
+        (FSEntry::Dir(DirType::Star), FSEntry::Dir(DirType::Star))
+        | (FSEntry::Dir(DirType::DoubleStar), FSEntry::Dir(DirType::DoubleStar))
+        | (FSEntry::File(FileType::Star), FSEntry::File(FileType::Star))
+        | (FSEntry::Either(FileOrDirType::Star), FSEntry::Either(FileOrDirType::Star)) => true,
+        _ => false,
+    };
+    let anchored = |p: &FSPattern| matches!(p, FSPattern::Anchored(_));
+    let (x, y) = (entries(a), entries(b));
+    anchored(a) == anchored(b)
+        && x.len() == y.len()
+        && x.iter().zip(y).all(|(p, q)| same_entry(p, q))
+}
+
+#[derive(Clone, Copy)]
+enum Seg<'a> {
+    /// `*`: any one name.
+    Any,
+    /// `**` in directory position: zero or more directories.
+    Deep,
+    Comp(&'a CompiledComponent),
+    /// A component with a placeholder name that's repeated in the pattern.
+    Bound(&'a CompiledComponent),
+}
+
+impl Seg<'_> {
+    /// Does this single-name segment match every name `other` matches?
+    fn covers(self, other: Seg) -> bool {
+        match (self, other) {
+            (Seg::Any, Seg::Any | Seg::Comp(_) | Seg::Bound(_)) => true,
+            (Seg::Comp(c), Seg::Comp(o) | Seg::Bound(o)) => {
+                c.ast == o.ast || literal(o).is_some_and(|l| matches_compiled_component(c, l))
+            }
+            (Seg::Bound(c), Seg::Comp(o) | Seg::Bound(o)) => c.ast == o.ast,
+            _ => false,
+        }
+    }
+}
+
+/// The text a component matches, if it's a plain literal.
+fn literal(c: &CompiledComponent) -> Option<&[u8]> {
+    match &c.ast.parts[..] {
+        [Part::Literal(l)] => Some(l.value.as_bytes()),
+        _ => None,
+    }
+}
+
+/// A pattern as anchored segments (an unanchored pattern starts with `**`), plus what kind of
+/// entry its leaf matches.
+struct Shape<'a> {
+    segs: Vec<Seg<'a>>,
+    files: bool,
+    dirs: bool,
+}
+
+impl<'a> Shape<'a> {
+    /// `None` if the pattern can never match.
+    fn of(pattern: &'a FSPattern) -> Option<Shape<'a>> {
+        let entries = entries(pattern);
+        let mut segs = Vec::with_capacity(entries.len() + 1);
+        if matches!(pattern, FSPattern::Unanchored(_))
+            && !matches!(entries.first(), Some(FSEntry::Dir(DirType::DoubleStar)))
+        {
+            segs.push(Seg::Deep);
+        }
+        for e in entries {
+            segs.push(match e {
+                FSEntry::Dir(DirType::DoubleStar) => Seg::Deep,
+                FSEntry::Dir(DirType::Star)
+                | FSEntry::File(FileType::Star)
+                | FSEntry::Either(FileOrDirType::Star) => Seg::Any,
+                FSEntry::Dir(DirType::Component(c))
+                | FSEntry::File(FileType::Component(c))
+                | FSEntry::Either(FileOrDirType::Component(c)) => Seg::Comp(c),
+            });
+        }
+
+        let mut uses: BTreeMap<&str, usize> = BTreeMap::new();
+        for c in components(pattern) {
+            for (name, _) in c.placeholder_indices.iter().filter(|(n, _)| !n.is_empty()) {
+                *uses.entry(name.as_str()).or_insert(0) += 1;
+            }
+        }
+        for seg in &mut segs {
+            if let Seg::Comp(c) = *seg
+                && c.placeholder_indices
+                    .iter()
+                    .any(|(n, _)| uses.get(n.as_str()) > Some(&1))
+            {
+                *seg = Seg::Bound(c);
+            }
+        }
+
+        let (files, dirs) = match entries.last()? {
+            // `**/` as the leaf: the matcher never accepts it.
+            FSEntry::Dir(DirType::DoubleStar) => return None,
+            FSEntry::Dir(_) => (false, true),
+            FSEntry::File(_) => (true, false),
+            FSEntry::Either(_) => (true, true),
+        };
+        Some(Shape { segs, files, dirs })
+    }
+
+    /// Does this pattern match every path `other` matches?
+    fn covers(&self, other: &Shape) -> bool {
+        (self.files || !other.files)
+            && (self.dirs || !other.dirs)
+            && covers_from(&self.segs, &other.segs, 0, 0)
+    }
+}
+
+/// Do `b[j..]` match every path `a[i..]` does? The last segment of each is the leaf.
+fn covers_from(b: &[Seg], a: &[Seg], j: usize, i: usize) -> bool {
+    let (a_leaf, b_leaf) = (i + 1 == a.len(), j + 1 == b.len());
+    if a_leaf && b_leaf {
+        return b[j].covers(a[i]);
+    }
+    if !b_leaf && matches!(b[j], Seg::Deep) {
+        // `**` in `b` takes no directories, or takes `a[i]` (whatever it is) and stays.
+        return covers_from(b, a, j + 1, i) || (!a_leaf && covers_from(b, a, j, i + 1));
+    }
+    if a_leaf || b_leaf || matches!(a[i], Seg::Deep) {
+        return false;
+    }
+    b[j].covers(a[i]) && covers_from(b, a, j + 1, i + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MatchSettings;
+    use crate::parse::parse_fspec;
+
+    fn lint(src: &str) -> Vec<(&'static str, Vec<usize>)> {
+        let rules = parse_fspec(src, &MatchSettings::default()).unwrap().rules;
+        lint_rules(&rules)
+            .into_iter()
+            .filter(|d| d.severity > Severity::Info)
+            .map(|d| (d.code, d.rule_lines))
+            .collect()
+    }
+
+    #[test]
+    fn later_broader_rules_shadow_earlier_ones() {
+        let src = "allow ./a/b.txt\nallow ./a/*.txt\nallow **/*.txt\nignore ./x/y\nallow x/*\n";
+        assert_eq!(
+            lint(src),
+            [
+                ("shadowed_rule", vec![1, 2]),
+                ("shadowed_rule", vec![2, 3]),
+                ("ignore_overridden", vec![4, 5]),
+            ]
+        );
+    }
+
+    #[test]
+    fn narrower_or_conditional_later_rules_do_not_shadow() {
+        let src = "allow ./a/**/*.txt\nallow ./a/*.txt\n\
+                   allow ./b/*\nallow ./b/*.txt\n\
+                   allow ./c/*.mp4\nignore ./c/*.mp4 where size < 1KB\n\
+                   allow ./d/\nallow ./d\n";
+        assert_eq!(lint(src), [("shadowed_rule", vec![7, 8])]);
+    }
+
+    #[test]
+    fn literal_components_are_covered_by_matching_placeholders() {
+        let src = "allow ./2024/x.txt\nallow ./{year:int(4)}/{name}.txt\n";
+        assert_eq!(lint(src), [("shadowed_rule", vec![1, 2])]);
+        let src = "allow ./202/x.txt\nallow ./{year:int(4)}/{name}.txt\n";
+        assert!(lint(src).is_empty());
+    }
+
+    #[test]
+    fn repeated_placeholders_only_cover_what_makes_them_equal() {
+        // `{y}/{y}.txt` can't match `2024/2023.txt`
+        let src = "allow ./2024/2023.txt\nallow ./{y}/{y}.txt\n";
+        assert!(lint(src).is_empty());
+        let src = "allow ./{y:int(4)}/{y}.txt\nallow ./{y}/{y}.txt\n";
+        assert!(lint(src).is_empty());
+        // the earlier rule repeats the same components, so its values are equal too
+        let src = "allow ./{y}/raw/{y}.txt\nallow ./{y}/*/{y}.txt\n";
+        assert_eq!(lint(src), [("shadowed_rule", vec![1, 2])]);
+    }
+
+    #[test]
+    fn duplicates_are_reported_once() {
+        let src = "deny **/*.tmp\nallow ./a\ndeny **/*.tmp\n";
+        assert_eq!(lint(src), [("duplicate_rule", vec![1, 3])]);
+    }
+
+    #[test]
+    fn flags_unmatchable_and_single_name_double_star_leaves() {
+        assert_eq!(
+            lint("allow ./a/**/\nallow ./b/**\n"),
+            [("unmatchable_rule", vec![1]), ("double_star_leaf", vec![2]),]
+        );
+    }
+
+    #[test]
+    fn unknown_limiters_are_reported_when_lenient() {
+        let settings = MatchSettings {
+            conformance: Conformance::Lenient,
+            ..MatchSettings::default()
+        };
+        let rules = parse_fspec("allow ./{n:snakecase}.txt\n", &settings)
+            .unwrap()
+            .rules;
+        let codes: Vec<_> = lint_rules(&rules).iter().map(|d| d.code).collect();
+        assert_eq!(codes, ["unknown_limiter", "single_use_placeholder"]);
+    }
+
+    #[test]
+    fn repeated_placeholders_are_not_single_use() {
+        let src = "allow ./{year:int(4)}/{year}-{n}.txt\nallow ./x/{n}.md\nallow ./{:int(2)}\n";
+        let rules = parse_fspec(src, &MatchSettings::default()).unwrap().rules;
+        assert!(lint_rules(&rules).is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+// Content sniffing for the `magic` predicate: does a file's leading bytes agree with its
+// extension?
+//
+// The table covers common media, archive and document formats. Only the first few hundred
+// bytes are read. Extensions not in the table have nothing to check against.
+
+/// How many leading bytes a signature may look at (`tar` has its marker at 257).
+pub(crate) const HEAD_LEN: usize = 512;
+
+pub(crate) struct ContentType {
+    /// Display name (`PNG`).
+    pub name: &'static str,
+    /// Lowercase extensions that declare this type.
+    exts: &'static [&'static str],
+    sig: fn(&[u8]) -> bool,
+}
+
+fn at(head: &[u8], offset: usize, magic: &[u8]) -> bool {
+    head.get(offset..offset + magic.len()) == Some(magic)
+}
+
+fn riff(head: &[u8], form: &[u8]) -> bool {
+    at(head, 0, b"RIFF") && at(head, 8, form)
+}
+
+fn html(head: &[u8]) -> bool {
+    let start = head
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(head.len());
+    // skip a UTF-8 BOM
+    let rest = head[start..]
+        .strip_prefix(b"\xEF\xBB\xBF")
+        .unwrap_or(&head[start..]);
+    let lower: Vec<u8> = rest.iter().take(15).map(u8::to_ascii_lowercase).collect();
+    lower.starts_with(b"<!doctype html") || lower.starts_with(b"<html")
+}
+
+fn mp3(head: &[u8]) -> bool {
+    at(head, 0, b"ID3") || (head.len() >= 2 && head[0] == 0xFF && head[1] & 0xE0 == 0xE0)
+}
+
+/// Known types. Order matters for [`sniff`]: more specific signatures come first.
+const TYPES: &[ContentType] = &[
+    // images
+    ContentType {
+        name: "PNG",
+        exts: &["png"],
+        sig: |h| at(h, 0, b"\x89PNG\r\n\x1a\n"),
+    },
+    ContentType {
+        name: "JPEG",
+        exts: &["jpg", "jpeg", "jpe"],
+        sig: |h| at(h, 0, b"\xFF\xD8\xFF"),
+    },
+    ContentType {
+        name: "GIF",
+        exts: &["gif"],
+        sig: |h| at(h, 0, b"GIF87a") || at(h, 0, b"GIF89a"),
+    },
+    ContentType {
+        name: "WebP",
+        exts: &["webp"],
+        sig: |h| riff(h, b"WEBP"),
+    },
+    ContentType {
+        name: "TIFF",
+        exts: &["tif", "tiff"],
+        sig: |h| at(h, 0, b"II*\0") || at(h, 0, b"MM\0*"),
+    },
+    ContentType {
+        name: "BMP",
+        exts: &["bmp"],
+        sig: |h| at(h, 0, b"BM"),
+    },
+    ContentType {
+        name: "ICO",
+        exts: &["ico"],
+        sig: |h| at(h, 0, b"\0\0\x01\0"),
+    },
+    // audio / video
+    ContentType {
+        name: "WAV",
+        exts: &["wav"],
+        sig: |h| riff(h, b"WAVE"),
+    },
+    ContentType {
+        name: "AVI",
+        exts: &["avi"],
+        sig: |h| riff(h, b"AVI "),
+    },
+    ContentType {
+        name: "FLAC",
+        exts: &["flac"],
+        sig: |h| at(h, 0, b"fLaC"),
+    },
+    ContentType {
+        name: "Ogg",
+        exts: &["ogg", "oga", "ogv", "opus"],
+        sig: |h| at(h, 0, b"OggS"),
+    },
+    ContentType {
+        name: "Matroska",
+        exts: &["mkv", "mka", "webm"],
+        sig: |h| at(h, 0, b"\x1A\x45\xDF\xA3"),
+    },
+    ContentType {
+        name: "MP4",
+        exts: &["mp4", "m4v", "m4a", "mov", "heic", "3gp"],
+        sig: |h| at(h, 4, b"ftyp"),
+    },
+    // documents
+    ContentType {
+        name: "PDF",
+        exts: &["pdf"],
+        sig: |h| at(h, 0, b"%PDF-"),
+    },
+    ContentType {
+        name: "RTF",
+        exts: &["rtf"],
+        sig: |h| at(h, 0, b"{\\rtf"),
+    },
+    ContentType {
+        name: "OLE2 (legacy Office)",
+        exts: &["doc", "xls", "ppt", "msi"],
+        sig: |h| at(h, 0, b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1"),
+    },
+    ContentType {
+        name: "HTML",
+        exts: &["html", "htm"],
+        sig: html,
+    },
+    // archives (Office Open XML, OpenDocument, EPUB and JAR files are ZIP containers)
+    ContentType {
+        name: "ZIP",
+        exts: &[
+            "zip", "jar", "apk", "docx", "xlsx", "pptx", "odt", "ods", "odp", "epub",
+        ],
+        sig: |h| at(h, 0, b"PK\x03\x04") || at(h, 0, b"PK\x05\x06"),
+    },
+    ContentType {
+        name: "gzip",
+        exts: &["gz", "tgz"],
+        sig: |h| at(h, 0, b"\x1F\x8B"),
+    },
+    ContentType {
+        name: "bzip2",
+        exts: &["bz2", "tbz2"],
+        sig: |h| at(h, 0, b"BZh"),
+    },
+    ContentType {
+        name: "xz",
+        exts: &["xz", "txz"],
+        sig: |h| at(h, 0, b"\xFD7zXZ\0"),
+    },
+    ContentType {
+        name: "Zstandard",
+        exts: &["zst"],
+        sig: |h| at(h, 0, b"\x28\xB5\x2F\xFD"),
+    },
+    ContentType {
+        name: "7z",
+        exts: &["7z"],
+        sig: |h| at(h, 0, b"7z\xBC\xAF\x27\x1C"),
+    },
+    ContentType {
+        name: "RAR",
+        exts: &["rar"],
+        sig: |h| at(h, 0, b"Rar!\x1A\x07"),
+    },
+    ContentType {
+        name: "tar",
+        exts: &["tar"],
+        sig: |h| at(h, 257, b"ustar"),
+    },
+    // last: its frame-sync signature is the loosest
+    ContentType {
+        name: "MP3",
+        exts: &["mp3"],
+        sig: mp3,
+    },
+];
+
+/// The type a file name's extension declares, if it's one we know the signature of.
+pub(crate) fn declared_type(name: &str) -> Option<&'static ContentType> {
+    let (_, ext) = name.rsplit_once('.')?;
+    let ext = ext.to_ascii_lowercase();
+    TYPES.iter().find(|t| t.exts.contains(&ext.as_str()))
+}
+
+/// The first known type whose signature matches `head`.
+pub(crate) fn sniff(head: &[u8]) -> Option<&'static ContentType> {
+    TYPES.iter().find(|t| (t.sig)(head))
+}
+
+impl ContentType {
+    pub(crate) fn matches(&self, head: &[u8]) -> bool {
+        (self.sig)(head)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extensions_map_to_types() {
+        assert_eq!(declared_type("photo.PNG").unwrap().name, "PNG");
+        assert_eq!(declared_type("a.tar.gz").unwrap().name, "gzip");
+        assert_eq!(declared_type("report.docx").unwrap().name, "ZIP");
+        assert!(declared_type("notes.txt").is_none());
+        assert!(declared_type("Makefile").is_none());
+    }
+
+    #[test]
+    fn sniffs_common_signatures() {
+        assert_eq!(sniff(b"\xFF\xD8\xFF\xE0\0\x10JFIF").unwrap().name, "JPEG");
+        assert_eq!(sniff(b"\0\0\0\x18ftypmp42").unwrap().name, "MP4");
+        assert_eq!(sniff(b"RIFF\0\0\0\0WEBPVP8 ").unwrap().name, "WebP");
+        assert_eq!(
+            sniff(b"\n  <!DOCTYPE HTML><html><body>404</body>")
+                .unwrap()
+                .name,
+            "HTML"
+        );
+        let mut tar = vec![0u8; 300];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(sniff(&tar).unwrap().name, "tar");
+        assert!(sniff(b"just some text").is_none());
+    }
+}
@@ -0,0 +1,278 @@
+// Checksum manifests: `manifest ./data/SHA256SUMS`.
+//
+// A manifest lists files relative to its own directory with their SHA-256 hashes, in the
+// format written by `sha256sum` (`<hex>  <path>`, or `<hex> *<path>`) or in BSD tagged
+// form (`SHA256 (<path>) = <hex>`, from BSD `sha256` or `sha256sum --tag`). While walking, every
+// file under the manifest's directory is hashed if listed, and reported if it's allowed
+// but not listed. Listed paths the walk never reached are reported at the end.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+use crate::error::Error;
+use crate::escape::{escape_bytes, escape_path};
+use crate::report::Diagnostic;
+use crate::spec::Severity;
+
+/// A `manifest <path>` directive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ManifestDecl {
+    /// Manifest file, relative to the spec root.
+    pub path: PathBuf,
+    /// Line of the directive in the `.fspec` file.
+    pub line: usize,
+}
+
+/// Parse the argument of a `manifest` directive: a relative path, optionally `./` or `/` prefixed.
+pub(crate) fn parse_manifest_decl(
+    arg: &str,
+    line: usize,
+    col: usize,
+) -> Result<ManifestDecl, Error> {
+    let err = |msg: &str| Error::parse(line, col, msg);
+    let rel = arg.trim_start_matches("./").trim_start_matches('/');
+    if rel.is_empty() {
+        return Err(err("expected a manifest path after manifest"));
+    }
+    if rel.ends_with('/') {
+        return Err(err("manifest path must name a file, not a directory"));
+    }
+    if rel.split('/').any(|c| c == "..") {
+        return Err(err(
+            "manifest path must stay inside the spec root ('..' is not allowed)",
+        ));
+    }
+    Ok(ManifestDecl {
+        path: rel
+            .split('/')
+            .filter(|c| !c.is_empty() && *c != ".")
+            .collect(),
+        line,
+    })
+}
+
+#[derive(Debug, Clone)]
+struct Listed {
+    hash: [u8; 32],
+    /// Line in the manifest file.
+    line: usize,
+    seen: bool,
+}
+
+/// A loaded manifest and what the walk has matched against it so far.
+#[derive(Debug, Clone)]
+pub(crate) struct Manifest {
+    decl: ManifestDecl,
+    /// Directory the listed paths are relative to.
+    dir: PathBuf,
+    /// Listed paths, `/`-separated and relative to `dir`.
+    listed: BTreeMap<String, Listed>,
+}
+
+impl Manifest {
+    /// Read and parse the manifest at `root/decl.path`. Problems with the file itself are
+    /// returned as diagnostics; a manifest that can't be read lists nothing.
+    pub(crate) fn load(root: &Path, decl: &ManifestDecl) -> (Manifest, Vec<Diagnostic>) {
+        let mut manifest = Manifest {
+            decl: decl.clone(),
+            dir: decl
+                .path
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+            listed: BTreeMap::new(),
+        };
+        let mut diags = Vec::new();
+        let shown = escape_path(&decl.path);
+
+        let text = match fs::read_to_string(root.join(&decl.path)) {
+            Ok(t) => t,
+            Err(e) => {
+                diags.push(manifest.diag(
+                    "manifest_unreadable",
+                    shown.clone(),
+                    format!("cannot read manifest: {}: {}", e, shown),
+                ));
+                return (manifest, diags);
+            }
+        };
+
+        for (idx, raw) in text.lines().enumerate() {
+            let line = raw.strip_suffix('\r').unwrap_or(raw);
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse_line(line) {
+                Some((hash, path)) => {
+                    // Keyed like walked paths, so names containing `\` compare equal.
+                    let key = escape_bytes(path.trim_start_matches("./").as_bytes()).into_owned();
+                    manifest.listed.insert(
+                        key,
+                        Listed {
+                            hash,
+                            line: idx + 1,
+                            seen: false,
+                        },
+                    );
+                }
+                None => diags.push(manifest.diag(
+                    "manifest_invalid",
+                    shown.clone(),
+                    format!("line {}: expected '<sha256>  <path>': {}", idx + 1, shown),
+                )),
+            }
+        }
+        (manifest, diags)
+    }
+
+    /// Check a walked file (`rel_path`, relative to the spec root) against this manifest:
+    /// listed files are hashed, and allowed files that aren't listed are reported.
+    pub(crate) fn check_file(
+        &mut self,
+        root: &Path,
+        rel_path: &Path,
+        allowed: bool,
+    ) -> Option<Diagnostic> {
+        let inner = rel_path.strip_prefix(&self.dir).ok()?;
+        if rel_path == self.decl.path {
+            return None;
+        }
+        let key = escape_path(inner);
+        let shown = escape_path(rel_path);
+
+        let Some(listed) = self.listed.get_mut(&key) else {
+            return allowed.then(|| {
+                self.diag(
+                    "manifest_unlisted",
+                    shown.clone(),
+                    format!("not listed in {}: {}", escape_path(&self.decl.path), shown),
+                )
+            });
+        };
+        listed.seen = true;
+        let (want, line) = (listed.hash, listed.line);
+
+        let message = match sha256_file(&root.join(rel_path)) {
+            Ok(got) if got == want => return None,
+            Ok(got) => format!(
+                "hash mismatch with {} line {} (expected {}, got {}): {}",
+                escape_path(&self.decl.path),
+                line,
+                to_hex(&want),
+                to_hex(&got),
+                shown
+            ),
+            Err(e) => format!("cannot hash file: {}: {}", e, shown),
+        };
+        Some(self.diag("manifest_hash_mismatch", shown, message))
+    }
+
+    /// Listed paths the walk never found as files.
+    pub(crate) fn finish(self) -> Vec<Diagnostic> {
+        self.listed
+            .iter()
+            .filter(|(_, l)| !l.seen)
+            .map(|(key, l)| {
+                let shown = if self.dir.as_os_str().is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}/{}", escape_path(&self.dir), key)
+                };
+                self.diag(
+                    "manifest_missing",
+                    shown.clone(),
+                    format!(
+                        "listed in {} line {} but not found: {}",
+                        escape_path(&self.decl.path),
+                        l.line,
+                        shown
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    fn diag(&self, code: &'static str, path: String, message: String) -> Diagnostic {
+        Diagnostic {
+            code,
+            severity: Severity::Error,
+            path,
+            message,
+            rule_lines: vec![self.decl.line],
+        }
+    }
+}
+
+/// One manifest line: GNU (`<hex>  <path>`, `<hex> *<path>`) or BSD tagged
+/// (`SHA256 (<path>) = <hex>`) format.
+fn parse_line(line: &str) -> Option<([u8; 32], &str)> {
+    if let Some(rest) = line.strip_prefix("SHA256 (") {
+        let (path, hex) = rest.rsplit_once(") = ")?;
+        return Some((from_hex(hex.trim())?, path));
+    }
+    let (hex, rest) = line.split_at_checked(64)?;
+    let path = rest
+        .strip_prefix("  ")
+        .or_else(|| rest.strip_prefix(" *"))?;
+    if path.is_empty() {
+        return None;
+    }
+    Some((from_hex(hex)?, path))
+}
+
+fn from_hex(s: &str) -> Option<[u8; 32]> {
+    if s.len() != 64 || !s.is_ascii() {
+        return None;
+    }
+    let mut out = [0u8; 32];
+    for (i, byte) in out.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&s[2 * i..2 * i + 2], 16).ok()?;
+    }
+    Some(out)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn sha256_file(path: &Path) -> io::Result<[u8; 32]> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    #[test]
+    fn parses_gnu_and_bsd_lines() {
+        let line = format!("{HASH}  data/a.csv");
+        let (h, p) = parse_line(&line).unwrap();
+        assert_eq!(to_hex(&h), HASH);
+        assert_eq!(p, "data/a.csv");
+        assert_eq!(parse_line(&format!("{HASH} *b.bin")).unwrap().1, "b.bin");
+        assert_eq!(
+            parse_line(&format!("SHA256 (c (1).txt) = {HASH}"))
+                .unwrap()
+                .1,
+            "c (1).txt"
+        );
+        assert!(parse_line("not a checksum line").is_none());
+        assert!(parse_line(&format!("{HASH} a.csv")).is_none());
+    }
+
+    #[test]
+    fn decl_paths_are_relative_to_the_root() {
+        let d = parse_manifest_decl("./data/SHA256SUMS", 3, 10).unwrap();
+        assert_eq!(d.path, Path::new("data").join("SHA256SUMS"));
+        assert!(parse_manifest_decl("../SHA256SUMS", 3, 10).is_err());
+        assert!(parse_manifest_decl("data/", 3, 10).is_err());
+    }
+}
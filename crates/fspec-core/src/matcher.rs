@@ -1,8 +1,10 @@
 use std::collections::HashMap;
 use std::path::Path;
 
+use crate::escape::escape_bytes;
 use crate::spec::{
-    CompiledComponent, DirType, FSEntry, FSPattern, FileOrDirType, FileType, Rule, RuleKind,
+    Captures, CompiledComponent, DirType, FSEntry, FSPattern, FileOrDirType, FileType, Rule,
+    RuleKind,
 };
 
 /// Result of matching a compiled component against a string
@@ -14,18 +16,22 @@ struct ComponentMatch {
     placeholders: HashMap<String, String>,
 }
 
-fn matches_compiled_component(compiled: &CompiledComponent, actual: &str) -> bool {
+pub(crate) fn matches_compiled_component(compiled: &CompiledComponent, actual: &[u8]) -> bool {
     extract_component_values(compiled, actual).matched
 }
 
 /// Extract placeholder values from a matched component using the pre-compiled regex.
-fn extract_component_values(compiled: &CompiledComponent, actual: &str) -> ComponentMatch {
+fn extract_component_values(compiled: &CompiledComponent, actual: &[u8]) -> ComponentMatch {
     // Use the pre-compiled regex directly - no compilation needed!
-    if let Some(caps) = compiled.regex.captures(actual) {
+    if let Some(caps) = compiled.regex.captures(actual)
+        && let Some(checked) = run_value_checks(compiled, &caps)
+    {
         let mut placeholders = HashMap::new();
         for (name, idx) in &compiled.placeholder_indices {
-            if let Some(mat) = caps.get(*idx) {
-                placeholders.insert(name.clone(), mat.as_str().to_string());
+            if let Some(value) = checked.get(idx) {
+                placeholders.insert(name.clone(), value.clone());
+            } else if let Some(mat) = caps.get(*idx) {
+                placeholders.insert(name.clone(), escape_bytes(mat.as_bytes()).into_owned());
             }
         }
         ComponentMatch {
@@ -40,13 +46,33 @@ fn extract_component_values(compiled: &CompiledComponent, actual: &str) -> Compo
     }
 }
 
+/// Post-regex validation hook: run the component's value checks (e.g. `range(..)`, `date(..)`)
+/// against the captured text.
+///
+/// Returns the normalized value for each checked capture group (dates become ISO 8601, so
+/// same-name placeholders in different formats compare equal), or `None` if any check fails.
+fn run_value_checks(
+    compiled: &CompiledComponent,
+    caps: &regex::bytes::Captures<'_>,
+) -> Option<HashMap<usize, String>> {
+    compiled
+        .value_checks
+        .iter()
+        .map(|(idx, check)| {
+            let text = std::str::from_utf8(caps.get(*idx)?.as_bytes()).ok()?;
+            let value = check.normalize(text)?;
+            Some((*idx, value))
+        })
+        .collect()
+}
+
 #[derive(Clone, Copy, PartialEq)]
 enum Terminal {
     File,
     Dir,
 }
 
-fn matches_terminal_pat(terminal: Terminal, pat: &FSEntry, actual: &str) -> bool {
+fn matches_terminal_pat(terminal: Terminal, pat: &FSEntry, actual: &[u8]) -> bool {
     match (terminal, pat) {
         // FILE
         (Terminal::File, FSEntry::File(FileType::Component(c)))
@@ -71,7 +97,7 @@ fn matches_terminal_pat(terminal: Terminal, pat: &FSEntry, actual: &str) -> bool
     }
 }
 
-fn matches_dir_pat(pat: &FSEntry, actual: &str) -> bool {
+fn matches_dir_pat(pat: &FSEntry, actual: &[u8]) -> bool {
     match pat {
         FSEntry::Dir(DirType::Component(component)) => {
             matches_compiled_component(component, actual)
@@ -86,7 +112,7 @@ fn dp(
     pi: usize,
     si: usize,
     parts: &[FSEntry],
-    path_parts: &[std::borrow::Cow<'_, str>],
+    path_parts: &[&[u8]],
     terminal: Terminal,
     memo: &mut [Vec<Option<bool>>],
 ) -> bool {
@@ -118,7 +144,7 @@ fn dp(
                 pat => {
                     if si >= path_parts.len() {
                         false
-                    } else if matches_dir_pat(pat, &path_parts[si]) {
+                    } else if matches_dir_pat(pat, path_parts[si]) {
                         dp(pi + 1, si + 1, parts, path_parts, terminal, memo)
                     } else {
                         false
@@ -129,7 +155,7 @@ fn dp(
             // Last pattern entry: must match the terminal form (file/dir) against exactly one segment
             if si >= path_parts.len() {
                 false
-            } else if matches_terminal_pat(terminal, &parts[pi], &path_parts[si]) {
+            } else if matches_terminal_pat(terminal, &parts[pi], path_parts[si]) {
                 dp(pi + 1, si + 1, parts, path_parts, terminal, memo)
             } else {
                 false
@@ -141,20 +167,24 @@ fn dp(
     res
 }
 
-fn matches_anchored_literal(rule: &Rule, path: &Path, kind: RuleKind, terminal: Terminal) -> bool {
+fn matches_anchored_literal(
+    rule: &Rule,
+    path: &Path,
+    kind: RuleKind,
+    terminal: Terminal,
+) -> Option<Captures> {
     if rule.kind != kind {
-        return false;
+        return None;
     }
 
     let parts = match &rule.pattern {
         FSPattern::Anchored(parts) => parts,
-        _ => return false,
+        _ => return None,
     };
 
     // Materialize path components once (this avoids repeated iter().count() and allows DP).
-    // Using Cow<str> keeps this cheap for normal UTF-8 paths.
-    let path_parts: Vec<std::borrow::Cow<'_, str>> =
-        path.iter().map(|c| c.to_string_lossy()).collect();
+    // Components are matched as raw bytes, so names that aren't valid UTF-8 still match.
+    let path_parts: Vec<&[u8]> = path.iter().map(|c| c.as_encoded_bytes()).collect();
 
     // Memo table: None = unknown, Some(true/false) = computed.
     // Dimensions: (parts.len()+1) x (path_parts.len()+1)
@@ -162,44 +192,50 @@ fn matches_anchored_literal(rule: &Rule, path: &Path, kind: RuleKind, terminal:
 
     // Phase 1: Check if pattern matches (existing logic)
     if !dp(0, 0, parts, &path_parts, terminal, &mut memo) {
-        return false;
+        return None;
     }
 
     // Phase 2: Extract placeholder values and validate same-name constraints
-    validate_placeholder_consistency(parts, &path_parts, terminal)
+    collect_consistent_captures(parts, &path_parts, terminal)
 }
 
-/// Extract placeholder values from a matched pattern and validate same-name consistency
-fn validate_placeholder_consistency(
+/// Extract placeholder values from a matched pattern and validate same-name consistency.
+///
+/// Returns the named captures (one value per name) if every repeated name agrees,
+/// or `None` if the same name captured different values.
+/// Anonymous placeholders (`{:int(4)}`) have an empty name and are never compared.
+fn collect_consistent_captures(
     parts: &[FSEntry],
-    path_parts: &[std::borrow::Cow<'_, str>],
+    path_parts: &[&[u8]],
     terminal: Terminal,
-) -> bool {
+) -> Option<Captures> {
     // Extract all placeholder values by matching components
     let mut all_placeholders: HashMap<String, Vec<String>> = HashMap::new(); // name -> list of values
 
     extract_placeholders_recursive(parts, path_parts, 0, 0, terminal, &mut all_placeholders);
 
+    let mut captures = Captures::new();
+
     // Validate: all placeholders with the same name must have the same value
-    for (_name, values) in all_placeholders {
-        if values.len() > 1 {
-            // All values must be the same
-            let first = &values[0];
-            for value in values.iter().skip(1) {
-                if value != first {
-                    return false;
-                }
-            }
+    for (name, mut values) in all_placeholders {
+        if name.is_empty() || values.is_empty() {
+            continue;
+        }
+        // All values must be the same
+        let first = values.swap_remove(0);
+        if values.iter().any(|v| *v != first) {
+            return None;
         }
+        captures.insert(name, first);
     }
 
-    true
+    Some(captures)
 }
 
 /// Recursively extract placeholder values by matching pattern parts to path segments
 fn extract_placeholders_recursive(
     parts: &[FSEntry],
-    path_parts: &[std::borrow::Cow<'_, str>],
+    path_parts: &[&[u8]],
     pat_idx: usize,
     path_idx: usize,
     terminal: Terminal,
@@ -248,7 +284,7 @@ fn extract_placeholders_recursive(
         }
         FSEntry::Dir(DirType::Component(component)) => {
             if !is_last {
-                let match_result = extract_component_values(component, &path_parts[path_idx]);
+                let match_result = extract_component_values(component, path_parts[path_idx]);
                 if match_result.matched {
                     for (name, value) in match_result.placeholders {
                         placeholders.entry(name).or_default().push(value);
@@ -284,7 +320,7 @@ fn extract_placeholders_recursive(
         }
         FSEntry::File(FileType::Component(component)) => {
             if is_last && terminal == Terminal::File {
-                let match_result = extract_component_values(component, &path_parts[path_idx]);
+                let match_result = extract_component_values(component, path_parts[path_idx]);
                 if match_result.matched {
                     for (name, value) in match_result.placeholders {
                         placeholders.entry(name).or_default().push(value);
@@ -312,7 +348,7 @@ fn extract_placeholders_recursive(
                         FileOrDirType::Component(component) => {
                             if is_last {
                                 let match_result =
-                                    extract_component_values(component, &path_parts[path_idx]);
+                                    extract_component_values(component, path_parts[path_idx]);
                                 if match_result.matched {
                                     for (name, value) in match_result.placeholders {
                                         placeholders.entry(name).or_default().push(value);
@@ -334,7 +370,7 @@ fn extract_placeholders_recursive(
                         FileOrDirType::Component(component) => {
                             if !is_last {
                                 let match_result =
-                                    extract_component_values(component, &path_parts[path_idx]);
+                                    extract_component_values(component, path_parts[path_idx]);
                                 if match_result.matched {
                                     for (name, value) in match_result.placeholders {
                                         placeholders.entry(name).or_default().push(value);
@@ -377,23 +413,22 @@ fn matches_unanchored_literal(
     path: &Path,
     kind: RuleKind,
     terminal: Terminal,
-) -> bool {
+) -> Option<Captures> {
     if rule.kind != kind {
-        return false;
+        return None;
     }
 
     let parts = match &rule.pattern {
         FSPattern::Unanchored(parts) => parts,
-        _ => return false,
+        _ => return None,
     };
 
     if parts.is_empty() {
-        return false;
+        return None;
     }
 
     // Materialize path components once (like in anchored).
-    let path_parts: Vec<std::borrow::Cow<'_, str>> =
-        path.iter().map(|c| c.to_string_lossy()).collect();
+    let path_parts: Vec<&[u8]> = path.iter().map(|c| c.as_encoded_bytes()).collect();
 
     // Unanchored semantics (your current code): align pattern to the *end* of the path.
     // Equivalent: anchored match on the full path with an implicit leading '**' that can
@@ -414,34 +449,46 @@ fn matches_unanchored_literal(
 
     // Phase 1: Check if pattern matches
     if !dp(0, 0, &effective_parts, &path_parts, terminal, &mut memo) {
-        return false;
+        return None;
     }
 
     // Phase 2: Extract placeholder values and validate same-name constraints
-    validate_placeholder_consistency(&effective_parts, &path_parts, terminal)
+    collect_consistent_captures(&effective_parts, &path_parts, terminal)
 }
 
-pub(crate) fn matches_allowed_anchored_file(rule: &Rule, path: &Path) -> bool {
+pub(crate) fn matches_allowed_anchored_file(rule: &Rule, path: &Path) -> Option<Captures> {
     matches_anchored_literal(rule, path, RuleKind::Allow, Terminal::File)
 }
-pub(crate) fn matches_allowed_anchored_dir(rule: &Rule, path: &Path) -> bool {
+pub(crate) fn matches_allowed_anchored_dir(rule: &Rule, path: &Path) -> Option<Captures> {
     matches_anchored_literal(rule, path, RuleKind::Allow, Terminal::Dir)
 }
-pub(crate) fn matches_ignored_anchored_file(rule: &Rule, path: &Path) -> bool {
+pub(crate) fn matches_ignored_anchored_file(rule: &Rule, path: &Path) -> Option<Captures> {
     matches_anchored_literal(rule, path, RuleKind::Ignore, Terminal::File)
 }
-pub(crate) fn matches_ignored_anchored_dir(rule: &Rule, path: &Path) -> bool {
+pub(crate) fn matches_ignored_anchored_dir(rule: &Rule, path: &Path) -> Option<Captures> {
     matches_anchored_literal(rule, path, RuleKind::Ignore, Terminal::Dir)
 }
-pub(crate) fn matches_allowed_unanchored_file(rule: &Rule, path: &Path) -> bool {
+pub(crate) fn matches_allowed_unanchored_file(rule: &Rule, path: &Path) -> Option<Captures> {
     matches_unanchored_literal(rule, path, RuleKind::Allow, Terminal::File)
 }
-pub(crate) fn matches_allowed_unanchored_dir(rule: &Rule, path: &Path) -> bool {
+pub(crate) fn matches_allowed_unanchored_dir(rule: &Rule, path: &Path) -> Option<Captures> {
     matches_unanchored_literal(rule, path, RuleKind::Allow, Terminal::Dir)
 }
-pub(crate) fn matches_ignored_unanchored_file(rule: &Rule, path: &Path) -> bool {
+pub(crate) fn matches_ignored_unanchored_file(rule: &Rule, path: &Path) -> Option<Captures> {
     matches_unanchored_literal(rule, path, RuleKind::Ignore, Terminal::File)
 }
-pub(crate) fn matches_ignored_unanchored_dir(rule: &Rule, path: &Path) -> bool {
+pub(crate) fn matches_ignored_unanchored_dir(rule: &Rule, path: &Path) -> Option<Captures> {
     matches_unanchored_literal(rule, path, RuleKind::Ignore, Terminal::Dir)
 }
+pub(crate) fn matches_denied_anchored_file(rule: &Rule, path: &Path) -> Option<Captures> {
+    matches_anchored_literal(rule, path, RuleKind::Deny, Terminal::File)
+}
+pub(crate) fn matches_denied_anchored_dir(rule: &Rule, path: &Path) -> Option<Captures> {
+    matches_anchored_literal(rule, path, RuleKind::Deny, Terminal::Dir)
+}
+pub(crate) fn matches_denied_unanchored_file(rule: &Rule, path: &Path) -> Option<Captures> {
+    matches_unanchored_literal(rule, path, RuleKind::Deny, Terminal::File)
+}
+pub(crate) fn matches_denied_unanchored_dir(rule: &Rule, path: &Path) -> Option<Captures> {
+    matches_unanchored_literal(rule, path, RuleKind::Deny, Terminal::Dir)
+}
@@ -1,72 +1,321 @@
 use crate::error::Error;
-use crate::spec::{MatchSettings, Rule, RuleKind};
+use crate::manifest::{ManifestDecl, parse_manifest_decl};
+use crate::spec::{Limit, Limits, MatchSettings, Rule, RuleKind};
 
-use crate::pattern::parse_pattern_str;
+use crate::pattern::{PatternSpans, parse_pattern_str};
+use crate::predicate::parse_predicates;
+use std::ops::Range;
 
-pub(crate) fn parse_fspec(src: &str, settings: &MatchSettings) -> Result<Vec<Rule>, Error> {
-    let mut rules = Vec::new();
+/// The contents of an `.fspec` file: its rules, in order, and its directives.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ParsedSpec {
+    pub rules: Vec<Rule>,
+    pub limits: Limits,
+    pub manifests: Vec<ManifestDecl>,
+    /// Number of source lines parsed so far.
+    pub lines: usize,
+}
 
-    for (idx, raw_line) in src.lines().enumerate() {
-        let line_no = idx + 1;
+/// Byte ranges of a rule line's tokens in the line, so `fspec fmt` can copy what's already
+/// canonical and rewrite only the rest. Component spans (`ComponentAst`) are relative to
+/// their component's range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RuleSpans {
+    /// `allow`, `ignore` or `deny`; `None` where `allow` is implied.
+    pub keyword: Option<Range<usize>>,
+    pub pattern: PatternSpans,
+    /// Each `where` predicate, without the `and`s between them.
+    pub predicates: Vec<Range<usize>>,
+}
+
+/// Parse an `.fspec` file. A bad line is skipped and parsing carries on, so every error in the
+/// file is returned (in line order), not just the first.
+pub(crate) fn parse_fspec(src: &str, settings: &MatchSettings) -> Result<ParsedSpec, Vec<Error>> {
+    let mut spec = ParsedSpec::default();
+    parse_more(src, settings, &mut spec)?;
+    Ok(spec)
+}
+
+/// Parse `src` as further lines of `spec`, numbered after the lines already parsed.
+pub(crate) fn parse_more(
+    src: &str,
+    settings: &MatchSettings,
+    spec: &mut ParsedSpec,
+) -> Result<(), Vec<Error>> {
+    let mut errors = Vec::new();
+    let first_line = spec.lines + 1;
 
+    for (idx, raw_line) in src.lines().enumerate() {
         // Handle Windows CRLF files.
         let line = raw_line.strip_suffix('\r').unwrap_or(raw_line);
 
-        // We only trim for control flow; the pattern itself will be handled below.
-        let trimmed = line.trim_start();
-
-        // Comments only at start of line (after optional leading whitespace).
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            continue;
+        if let Err(e) = parse_line(line, first_line + idx, settings, spec) {
+            errors.push(e.with_source_line(line));
         }
+        spec.lines += 1;
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
 
-        // Parse keyword and the rest of the line.
-        // If no keyword is found, default to 'allow' (for find output compatibility).
-        let (kind, raw_pattern) = if let Some((k, rest_owned)) = split_kw_owned(trimmed) {
-            // Found a keyword (allow or ignore)
-            let rest = rest_owned.trim_start();
-            if rest.is_empty() {
-                return Err(Error::Parse {
-                    line: line_no,
-                    col: 1,
-                    msg: "expected a pattern after keyword".into(),
-                });
+/// Parse one line of an `.fspec` file into `spec`. For a rule, returns where its tokens are.
+pub(crate) fn parse_line(
+    line: &str,
+    line_no: usize,
+    settings: &MatchSettings,
+    spec: &mut ParsedSpec,
+) -> Result<Option<RuleSpans>, Error> {
+    // We only trim for control flow; the pattern itself will be handled below.
+    let trimmed = line.trim_start();
+
+    // Comments only at start of line (after optional leading whitespace).
+    if trimmed.is_empty() || trimmed.starts_with('#') {
+        return Ok(None);
+    }
+
+    // Directive lines (`max-depth 6`, `manifest ./SHA256SUMS`) set limits or declare
+    // manifests rather than adding a rule. A limit with a bad argument is an error, so a typo
+    // can't quietly drop the limit; quote the name or use `allow` for a path of that name. A
+    // bare `manifest` is what `find` prints for a path of that name, so if its argument doesn't
+    // parse the line is a pattern, unless it doesn't parse as one either; then the directive's
+    // error is the more useful one.
+    let directive_err = match split_directive(trimmed) {
+        Some((name, arg)) => {
+            let col = line.len() - arg.len() + 1;
+            let parsed = if name == "manifest" {
+                parse_manifest_decl(arg.trim_end(), line_no, col).map(Directive::Manifest)
+            } else {
+                let value = parse_limit_value(name, arg.trim_end(), line_no, col)?;
+                Ok(Directive::Limit(value))
+            };
+            match parsed {
+                Ok(directive) => {
+                    add_directive(spec, name, directive, line_no, line, trimmed)?;
+                    return Ok(None);
+                }
+                Err(e) => Some(e),
             }
-            (k, rest.trim_end().to_string())
-        } else {
-            // No keyword found - treat entire line as pattern, default to 'allow'
-            (RuleKind::Allow, trimmed.trim_end().to_string())
-        };
-        let pattern = parse_pattern_str(&raw_pattern, line_no, settings)?;
+        }
+        None => None,
+    };
+
+    match parse_rule(line, trimmed, line_no, settings) {
+        Ok((rule, spans)) => {
+            spec.rules.push(rule);
+            Ok(Some(spans))
+        }
+        Err(e) => Err(directive_err.unwrap_or(e)),
+    }
+}
+
+enum Directive {
+    Manifest(ManifestDecl),
+    Limit(usize),
+}
 
-        rules.push(Rule {
-            line: line_no,
-            kind,
-            pattern,
-        });
+fn add_directive(
+    spec: &mut ParsedSpec,
+    name: &str,
+    directive: Directive,
+    line_no: usize,
+    line: &str,
+    trimmed: &str,
+) -> Result<(), Error> {
+    let value = match directive {
+        Directive::Manifest(decl) => {
+            spec.manifests.push(decl);
+            return Ok(());
+        }
+        Directive::Limit(value) => value,
+    };
+    let slot = match name {
+        "max-depth" => &mut spec.limits.max_depth,
+        "max-path-length" => &mut spec.limits.max_path_length,
+        "max-name-length" => &mut spec.limits.max_name_length,
+        _ => &mut spec.limits.max_entries,
+    };
+    if let Some(prev) = slot {
+        return Err(Error::parse(
+            line_no,
+            line.len() - trimmed.len() + 1,
+            format!(
+                "duplicate {} directive (already set on line {})",
+                name, prev.line
+            ),
+        ));
     }
+    *slot = Some(Limit {
+        value,
+        line: line_no,
+    });
+    Ok(())
+}
+
+/// Parse a rule line (`trimmed` is `line` without leading whitespace).
+fn parse_rule(
+    line: &str,
+    trimmed: &str,
+    line_no: usize,
+    settings: &MatchSettings,
+) -> Result<(Rule, RuleSpans), Error> {
+    let indent = line.len() - trimmed.len();
+    let mut keyword = None;
+    // Parse keyword and the rest of the line.
+    // If no keyword is found, default to 'allow' (for find output compatibility).
+    let (kind, pattern_src) = if let Some((k, rest)) = split_kw(trimmed) {
+        // Found a keyword (allow or ignore)
+        keyword = Some(indent..line.len() - rest.len());
+        let rest = rest.trim_start();
+        if rest.is_empty() {
+            return Err(Error::parse(
+                line_no,
+                line.len() - trimmed.len() + 1,
+                "expected a pattern after keyword",
+            ));
+        }
+        (k, rest)
+    } else {
+        // No keyword found - treat entire line as pattern, default to 'allow'
+        (RuleKind::Allow, trimmed)
+    };
+    // Column of the pattern's first byte, so errors inside it point at the right place.
+    let pattern_col = line.len() - pattern_src.len() + 1;
+    let raw_pattern = pattern_src.trim_end();
+    // An optional `where` clause follows the pattern.
+    let (raw_pattern, predicates) = match split_where(raw_pattern) {
+        Some((pat, clause)) => {
+            let col = line.trim_end().len() - clause.len() + 1;
+            let predicates = parse_predicates(clause)
+                .map_err(|(at, msg)| Error::parse(line_no, col + at, msg))?;
+            // relative to the line rather than the clause
+            let predicates = predicates
+                .into_iter()
+                .map(|(p, r)| (p, col - 1 + r.start..col - 1 + r.end))
+                .collect::<Vec<_>>();
+            (pat, predicates)
+        }
+        None => (raw_pattern, Vec::new()),
+    };
+
+    // Compiling a component can fail without a position (e.g. a regex the engine rejects);
+    // point those at the pattern.
+    let (pattern, pattern_spans) = parse_pattern_str(raw_pattern, line_no, pattern_col, settings)
+        .map_err(|e| match e {
+        Error::Semantic { msg } => Error::parse(line_no, pattern_col, msg),
+        e => e,
+    })?;
 
-    Ok(rules)
+    let spans = RuleSpans {
+        keyword,
+        pattern: pattern_spans,
+        predicates: predicates.iter().map(|(_, r)| r.clone()).collect(),
+    };
+    let rule = Rule {
+        line: line_no,
+        kind,
+        pattern,
+        predicates: predicates.into_iter().map(|(p, _)| p).collect(),
+        source: None,
+    };
+    Ok((rule, spans))
 }
 
-fn split_kw_owned(s: &str) -> Option<(RuleKind, String)> {
-    fn kw(s: &str, word: &str, kind: RuleKind) -> Option<(RuleKind, String)> {
+const DIRECTIVES: &[&str] = &[
+    "max-depth",
+    "max-path-length",
+    "max-name-length",
+    "max-entries",
+    "manifest",
+];
+
+/// Split a directive line into its name and the (left-trimmed) argument text.
+fn split_directive(s: &str) -> Option<(&'static str, &str)> {
+    DIRECTIVES.iter().find_map(|&name| {
+        let rest = s.strip_prefix(name)?;
+        if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+            Some((name, rest.trim_start()))
+        } else {
+            None
+        }
+    })
+}
+
+fn parse_limit_value(name: &str, arg: &str, line: usize, col: usize) -> Result<usize, Error> {
+    if arg.is_empty() {
+        return Err(Error::parse(
+            line,
+            col,
+            format!("expected a number after {}", name),
+        ));
+    }
+    arg.parse::<usize>().map_err(|_| {
+        Error::parse(
+            line,
+            col,
+            format!("{} expects a non-negative integer, found '{}'", name, arg),
+        )
+    })
+}
+
+fn split_kw(s: &str) -> Option<(RuleKind, &str)> {
+    fn kw<'a>(s: &'a str, word: &str, kind: RuleKind) -> Option<(RuleKind, &'a str)> {
         let rest = s.strip_prefix(word)?;
         // Require a boundary so "allowance" doesn't match "allow".
         if rest.is_empty() || rest.starts_with(char::is_whitespace) {
-            Some((kind, rest.to_string()))
+            Some((kind, rest))
         } else {
             None
         }
     }
 
-    kw(s, "allow", RuleKind::Allow).or_else(|| kw(s, "ignore", RuleKind::Ignore))
+    kw(s, "allow", RuleKind::Allow)
+        .or_else(|| kw(s, "ignore", RuleKind::Ignore))
+        .or_else(|| kw(s, "deny", RuleKind::Deny))
+}
+
+/// Split `pattern where predicates` at the first ` where ` outside quotes and `{...}`.
+/// Returns the pattern (right-trimmed) and the clause text after `where`.
+fn split_where(s: &str) -> Option<(&str, &str)> {
+    let bytes = s.as_bytes();
+    let mut in_quote = false;
+    let mut depth = 0usize;
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'"' => in_quote = !in_quote,
+            b'{' if !in_quote => depth += 1,
+            b'}' if !in_quote => depth = depth.saturating_sub(1),
+            b' ' | b'\t' if !in_quote && depth == 0 => {
+                let rest = &s[i + 1..];
+                let Some(after) = rest.strip_prefix("where") else {
+                    continue;
+                };
+                if after.is_empty() || after.starts_with([' ', '\t']) {
+                    let pattern = s[..i].trim_end();
+                    if !pattern.is_empty() {
+                        return Some((pattern, after.trim_start()));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    None
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn first_error(src: &str) -> Error {
+        parse_fspec(src, &MatchSettings::default())
+            .unwrap_err()
+            .remove(0)
+    }
+
     #[test]
     fn parses_basic_rules_smoke() {
         let src = r#"
@@ -75,7 +324,7 @@ mod tests {
             ignore **/*.tmp
         "#;
 
-        let rules = parse_fspec(src, &MatchSettings::default()).unwrap();
+        let rules = parse_fspec(src, &MatchSettings::default()).unwrap().rules;
         assert_eq!(rules.len(), 2);
         assert_eq!(rules[0].line, 3);
         assert_eq!(rules[0].kind, RuleKind::Allow);
@@ -92,7 +341,7 @@ mod tests {
             /src/utils.rs
         "#;
 
-        let rules = parse_fspec(src, &MatchSettings::default()).unwrap();
+        let rules = parse_fspec(src, &MatchSettings::default()).unwrap().rules;
         assert_eq!(rules.len(), 4);
         // Line without keyword defaults to Allow
         assert_eq!(rules[0].kind, RuleKind::Allow);
@@ -117,9 +366,155 @@ mod tests {
             ./target/
         "#;
 
-        let rules = parse_fspec(src, &MatchSettings::default()).unwrap();
+        let rules = parse_fspec(src, &MatchSettings::default()).unwrap().rules;
         assert_eq!(rules.len(), 3);
         // All should default to Allow
         assert!(rules.iter().all(|r| r.kind == RuleKind::Allow));
     }
+
+    #[test]
+    fn parses_where_clauses_and_deny() {
+        let src = r#"
+            deny **/*.mp4 where size < 1KB
+            allow ./published/** where mode == 0644 and not executable
+            allow "a where b".txt
+            allow {n:re("x where y")}.txt
+        "#;
+
+        let rules = parse_fspec(src, &MatchSettings::default()).unwrap().rules;
+        assert_eq!(rules[0].kind, RuleKind::Deny);
+        assert_eq!(rules[0].predicates.len(), 1);
+        assert_eq!(rules[1].predicates.len(), 2);
+        // `where` inside quotes or placeholders is part of the pattern
+        assert!(rules[2].predicates.is_empty());
+        assert!(rules[3].predicates.is_empty());
+    }
+
+    #[test]
+    fn bad_where_clause_reports_column() {
+        match first_error("deny *.mp4 where size < 1XB") {
+            Error::Parse { line, col, msg, .. } => {
+                assert_eq!((line, col), (1, 25));
+                assert!(msg.contains("invalid size"), "{msg}");
+            }
+            other => panic!("expected parse error, got {other:?}"),
+        }
+        assert!(parse_fspec("deny *.mp4 where", &MatchSettings::default()).is_err());
+    }
+
+    #[test]
+    fn placeholder_errors_point_into_the_line() {
+        let src = "allow ./a.txt\n\n  ignore ./media/{year:int(}/*.mkv\n";
+        let err = first_error(src);
+        match &err {
+            Error::Parse { line, col, .. } => assert_eq!((*line, *col), (3, 28)),
+            other => panic!("expected parse error, got {other:?}"),
+        }
+        let shown = err.to_string();
+        assert!(
+            shown.ends_with(
+                "3 |   ignore ./media/{year:int(}/*.mkv\n  |                            ^"
+            ),
+            "{shown}"
+        );
+    }
+
+    #[test]
+    fn limiter_errors_underline_the_limiter() {
+        match first_error("allow ./x/{name:snakecase}.txt") {
+            Error::Parse { col, len, .. } => assert_eq!((col, len), (17, 9)),
+            other => panic!("expected parse error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn collects_every_bad_line() {
+        let src = r#"
+            allow ./{year:int(}/x
+            allow ./ok.txt
+            deny *.mp4 where size < 1XB
+            max-depth {six
+            allow ./{n:re("(")}.txt
+        "#;
+
+        let errors = parse_fspec(src, &MatchSettings::default()).unwrap_err();
+        let lines: Vec<usize> = errors
+            .iter()
+            .map(|e| match e {
+                Error::Parse { line, .. } => *line,
+                other => panic!("expected parse error, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(lines, [2, 4, 5, 6]);
+    }
+
+    #[test]
+    fn parses_limit_directives() {
+        let src = r#"
+            max-depth 6
+            max-path-length 255
+            allow src/**
+            max-name-length 100
+            max-entries 1000
+        "#;
+
+        let spec = parse_fspec(src, &MatchSettings::default()).unwrap();
+        assert_eq!(spec.rules.len(), 1);
+        assert_eq!(spec.limits.max_depth, Some(Limit { value: 6, line: 2 }));
+        assert_eq!(
+            spec.limits.max_path_length,
+            Some(Limit {
+                value: 255,
+                line: 3
+            })
+        );
+        assert_eq!(spec.limits.max_name_length.unwrap().value, 100);
+        assert_eq!(spec.limits.max_entries.unwrap().line, 6);
+    }
+
+    #[test]
+    fn parses_manifest_directives() {
+        let src = "manifest ./data/SHA256SUMS\nmanifest SHA256SUMS\nallow data/**\n";
+        let spec = parse_fspec(src, &MatchSettings::default()).unwrap();
+        assert_eq!(spec.rules.len(), 1);
+        assert_eq!(spec.manifests.len(), 2);
+        assert_eq!(spec.manifests[0].line, 1);
+        assert_eq!(spec.manifests[1].path, std::path::Path::new("SHA256SUMS"));
+    }
+
+    #[test]
+    fn directive_needs_word_boundary() {
+        // a file literally named "max-depths" is still a pattern
+        let spec = parse_fspec("max-depths", &MatchSettings::default()).unwrap();
+        assert_eq!(spec.rules.len(), 1);
+        assert_eq!(spec.limits, Limits::default());
+    }
+
+    #[test]
+    fn manifest_without_a_valid_argument_is_a_pattern() {
+        // `find` output for paths named like directives
+        let src = "./max-depth\n./manifest\nmanifest\n\"max-entries\"\nallow max-depth\n";
+        let spec = parse_fspec(src, &MatchSettings::default()).unwrap();
+        assert_eq!(spec.rules.len(), 5);
+        assert_eq!(spec.limits, Limits::default());
+        assert!(spec.manifests.is_empty());
+    }
+
+    #[test]
+    fn bad_directives_are_errors() {
+        for (src, want) in [
+            ("max-depth {", "non-negative integer"),
+            ("manifest ../{", "'..' is not allowed"),
+            // a limit's argument must be a number, even if the line would be a pattern
+            ("max-depth two", "non-negative integer, found 'two'"),
+            ("max-entries -1", "non-negative integer, found '-1'"),
+            ("max-depth", "expected a number after max-depth"),
+            ("max-depth 3\nmax-depth 4", "already set on line 1"),
+        ] {
+            match first_error(src) {
+                Error::Parse { msg, .. } => assert!(msg.contains(want), "{src}: {msg}"),
+                other => panic!("{src}: expected parse error, got {other:?}"),
+            }
+        }
+    }
 }
@@ -1,27 +1,42 @@
 use crate::MatchSettings;
 use crate::compile::compile_component;
 use crate::error::Error;
-use crate::spec::{DirType, FSEntry, FSPattern, FileOrDirType, FileType};
-use fspec_placeholder::parse_component;
+use crate::spec::{CompiledComponent, DirType, FSEntry, FSPattern, FileOrDirType, FileType};
+use fspec_placeholder::{parse_component, validate_component};
+use std::ops::Range;
 
+/// Byte ranges of a pattern's tokens in its `.fspec` line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PatternSpans {
+    /// `./` or `/`; empty for an unanchored pattern.
+    pub anchor: Range<usize>,
+    /// Each path component, without the `/`s around it.
+    pub components: Vec<Range<usize>>,
+}
+
+/// Parse a pattern that starts at column `col` of `.fspec` line `line`, returning it with
+/// where each of its tokens is in the line. Error positions are reported relative to the
+/// whole line.
 pub(crate) fn parse_pattern_str(
     raw: &str,
     line: usize,
+    col: usize,
     settings: &MatchSettings,
-) -> Result<FSPattern, Error> {
+) -> Result<(FSPattern, PatternSpans), Error> {
     let s0 = raw.trim();
     if s0.is_empty() {
-        return Err(parse_err(line, 1, "empty pattern"));
+        return Err(Error::parse(line, col, "empty pattern"));
     }
+    let col = col + (raw.len() - raw.trim_start().len());
 
     // Anchored vs unanchored.
     // Support both '/' and './' as anchored prefixes
     let (anchored, mut s, base_col) = if let Some(rest) = s0.strip_prefix("./") {
-        (true, rest, 3) // we consumed './'
+        (true, rest, col + 2) // we consumed './'
     } else if let Some(rest) = s0.strip_prefix('/') {
-        (true, rest, 2) // we consumed '/'
+        (true, rest, col + 1) // we consumed '/'
     } else {
-        (false, s0, 1)
+        (false, s0, col)
     };
 
     // Directory vs file is determined by trailing slash.
@@ -32,7 +47,7 @@ pub(crate) fn parse_pattern_str(
     }
 
     if s.is_empty() {
-        return Err(parse_err(
+        return Err(Error::parse(
             line,
             base_col,
             "pattern must not be just '/' (no path components)",
@@ -43,81 +58,98 @@ pub(crate) fn parse_pattern_str(
     let parts: Vec<&str> = s.split('/').collect();
     for (i, part) in parts.iter().enumerate() {
         if part.is_empty() {
-            // Column: approximate start of this empty segment.
-            // (Good enough; you can refine later if you want exact columns.)
             let col = base_col + parts[..i].iter().map(|p| p.len() + 1).sum::<usize>();
-            return Err(parse_err(line, col, "empty path segment (// not allowed)"));
+            return Err(Error::parse(
+                line,
+                col,
+                "empty path segment (// not allowed)",
+            ));
         }
     }
 
     let last_idx = parts.len() - 1;
     let mut entries = Vec::with_capacity(parts.len());
+    let mut spans = PatternSpans {
+        anchor: col - 1..base_col - 1,
+        components: Vec::with_capacity(parts.len()),
+    };
 
+    let mut col = base_col;
     for (i, part) in parts.iter().enumerate() {
         let is_last = i == last_idx;
+        let at = (line, col);
+        spans.components.push(col - 1..col - 1 + part.len());
+        col += part.len() + 1;
 
         if !is_last {
-            entries.push(FSEntry::Dir(parse_dir(part)?));
+            entries.push(FSEntry::Dir(parse_dir(part, at, settings)?));
             continue;
         }
 
         // Final component depends on trailing slash.
         if ends_with_slash {
-            entries.push(FSEntry::Dir(parse_dir(part)?));
+            entries.push(FSEntry::Dir(parse_dir(part, at, settings)?));
         } else if settings.allow_file_or_dir_leaf {
-            entries.push(FSEntry::Either(parse_file_or_dir(part)?));
+            entries.push(FSEntry::Either(parse_file_or_dir(part, at, settings)?));
         } else {
-            entries.push(FSEntry::File(parse_file(part)?));
+            entries.push(FSEntry::File(parse_file(part, at, settings)?));
         }
     }
 
-    Ok(if anchored {
+    let pattern = if anchored {
         FSPattern::Anchored(entries)
     } else {
         FSPattern::Unanchored(entries)
-    })
+    };
+    Ok((pattern, spans))
 }
 
-fn parse_dir(s: &str) -> Result<DirType, Error> {
+fn parse_dir(s: &str, at: (usize, usize), settings: &MatchSettings) -> Result<DirType, Error> {
     match s {
         "*" => Ok(DirType::Star),
         "**" => Ok(DirType::DoubleStar),
         _ => {
-            let component = parse_component(s)?;
-            let compiled = compile_component(&component)?;
+            let compiled = parse_and_compile(s, at, settings)?;
             Ok(DirType::Component(compiled))
         }
     }
 }
 
-fn parse_file(s: &str) -> Result<FileType, Error> {
+fn parse_file(s: &str, at: (usize, usize), settings: &MatchSettings) -> Result<FileType, Error> {
     match s {
         "*" => Ok(FileType::Star),
         _ => {
-            let component = parse_component(s)?;
-            let compiled = compile_component(&component)?;
+            let compiled = parse_and_compile(s, at, settings)?;
             Ok(FileType::Component(compiled))
         }
     }
 }
 
-fn parse_file_or_dir(s: &str) -> Result<FileOrDirType, Error> {
+fn parse_file_or_dir(
+    s: &str,
+    at: (usize, usize),
+    settings: &MatchSettings,
+) -> Result<FileOrDirType, Error> {
     match s {
         "*" => Ok(FileOrDirType::Star),
         _ => {
-            let component = parse_component(s)?;
-            let compiled = compile_component(&component)?;
+            let compiled = parse_and_compile(s, at, settings)?;
             Ok(FileOrDirType::Component(compiled))
         }
     }
 }
 
-fn parse_err(line: usize, col: usize, msg: impl Into<String>) -> Error {
-    Error::Parse {
-        line,
-        col,
-        msg: msg.into(),
-    }
+/// Parse a component, validate its limiters at the configured conformance level, and compile it.
+/// `at` is the `(line, column)` where the component starts in the `.fspec` file.
+fn parse_and_compile(
+    s: &str,
+    (line, col): (usize, usize),
+    settings: &MatchSettings,
+) -> Result<CompiledComponent, Error> {
+    let component = parse_component(s).map_err(|e| Error::from_component(e, line, col))?;
+    validate_component(&component, settings.conformance)
+        .map_err(|e| Error::from_component(e, line, col))?;
+    compile_component(&component, settings)
 }
 
 #[cfg(test)]
@@ -127,7 +159,7 @@ mod tests {
 
     #[test]
     fn unanchored_dir_then_entry() {
-        let p = parse_pattern_str("assets/*/*.png", 1, &MatchSettings::default()).unwrap();
+        let (p, _) = parse_pattern_str("assets/*/*.png", 1, 1, &MatchSettings::default()).unwrap();
         match p {
             Unanchored(entries) => {
                 assert_eq!(entries.len(), 3);
@@ -137,9 +169,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn spans_are_line_offsets_of_anchor_and_components() {
+        // as in `allow ./a/{ x }/`, where the pattern starts at column 7
+        let (_, spans) = parse_pattern_str("./a/{ x }/", 1, 7, &MatchSettings::default()).unwrap();
+        assert_eq!(spans.anchor, 6..8);
+        assert_eq!(spans.components, vec![8..9, 10..15]);
+
+        let (_, spans) = parse_pattern_str("a/b", 1, 1, &MatchSettings::default()).unwrap();
+        assert_eq!(spans.anchor, 0..0);
+        assert_eq!(spans.components, vec![0..1, 2..3]);
+    }
+
     #[test]
     fn trailing_slash_makes_last_component_dir() {
-        let p = parse_pattern_str("assets/*/", 1, &MatchSettings::default()).unwrap();
+        let (p, _) = parse_pattern_str("assets/*/", 1, 1, &MatchSettings::default()).unwrap();
         match p {
             Unanchored(entries) => {
                 assert_eq!(entries.len(), 2);
@@ -150,7 +194,7 @@ mod tests {
 
     #[test]
     fn anchored_pattern() {
-        let p = parse_pattern_str("/assets/**/x", 1, &MatchSettings::default()).unwrap();
+        let (p, _) = parse_pattern_str("/assets/**/x", 1, 1, &MatchSettings::default()).unwrap();
         match p {
             Anchored(entries) => {
                 assert_eq!(entries.len(), 3);
@@ -161,7 +205,7 @@ mod tests {
 
     #[test]
     fn anchored_pattern_with_dot_slash() {
-        let p = parse_pattern_str("./assets/**/x", 1, &MatchSettings::default()).unwrap();
+        let (p, _) = parse_pattern_str("./assets/**/x", 1, 1, &MatchSettings::default()).unwrap();
         match p {
             Anchored(entries) => {
                 assert_eq!(entries.len(), 3);
@@ -172,7 +216,7 @@ mod tests {
 
     #[test]
     fn anchored_dir_with_dot_slash() {
-        let p = parse_pattern_str("./bin/", 1, &MatchSettings::default()).unwrap();
+        let (p, _) = parse_pattern_str("./bin/", 1, 1, &MatchSettings::default()).unwrap();
         match p {
             Anchored(entries) => {
                 assert_eq!(entries.len(), 1);
@@ -183,14 +227,15 @@ mod tests {
 
     #[test]
     fn rejects_double_slash() {
-        assert!(parse_pattern_str("a//b", 1, &MatchSettings::default()).is_err());
+        assert!(parse_pattern_str("a//b", 1, 1, &MatchSettings::default()).is_err());
     }
 
     #[test]
     fn spaces_in_dir_literal() {
-        let p = parse_pattern_str(
+        let (p, _) = parse_pattern_str(
             "/assets/this dir has spaces /x",
             1,
+            1,
             &MatchSettings::default(),
         )
         .unwrap();
@@ -204,9 +249,10 @@ mod tests {
 
     #[test]
     fn spaces_in_file_literal() {
-        let p = parse_pattern_str(
+        let (p, _) = parse_pattern_str(
             "/assets/approved/My mom named this file.png",
             1,
+            1,
             &MatchSettings::default(),
         )
         .unwrap();
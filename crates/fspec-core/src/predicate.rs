@@ -0,0 +1,498 @@
+// Predicate clauses on rules: `deny **/*.mp4 where size < 1KB`.
+//
+// A predicate tests an entry's metadata rather than its name. Metadata is only read
+// when a rule with predicates has already matched the path, so specs without `where`
+// clauses cost nothing extra.
+
+use std::cell::OnceCell;
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::magic::{HEAD_LEN, declared_type, sniff};
+use crate::spec::EntryKind;
+
+/// Comparison operator in a `size` or `mode` predicate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl CmpOp {
+    fn parse(s: &str) -> Option<CmpOp> {
+        Some(match s {
+            "<" => CmpOp::Lt,
+            "<=" => CmpOp::Le,
+            ">" => CmpOp::Gt,
+            ">=" => CmpOp::Ge,
+            "==" => CmpOp::Eq,
+            "!=" => CmpOp::Ne,
+            _ => return None,
+        })
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            CmpOp::Lt => "<",
+            CmpOp::Le => "<=",
+            CmpOp::Gt => ">",
+            CmpOp::Ge => ">=",
+            CmpOp::Eq => "==",
+            CmpOp::Ne => "!=",
+        }
+    }
+
+    fn test<T: Ord>(self, a: T, b: T) -> bool {
+        match self {
+            CmpOp::Lt => a < b,
+            CmpOp::Le => a <= b,
+            CmpOp::Gt => a > b,
+            CmpOp::Ge => a >= b,
+            CmpOp::Eq => a == b,
+            CmpOp::Ne => a != b,
+        }
+    }
+}
+
+/// One test in a rule's `where` clause. A rule's predicates must all hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Predicate {
+    /// `size <op> N[B|KB|MB|GB|TB]`: file length in bytes (units are powers of 1024).
+    /// Never holds for directories.
+    Size { op: CmpOp, bytes: u64 },
+    /// `mode == 0644` / `mode != 0644`: permission bits, in octal. Unix only.
+    Mode { op: CmpOp, mode: u32 },
+    /// `empty` / `not empty`: a zero-length file, or a directory with no entries.
+    Empty { negated: bool },
+    /// `executable` / `not executable`: any execute bit set. Never holds off Unix.
+    Executable { negated: bool },
+    /// `magic` / `not magic`: the file's leading bytes match the type its extension
+    /// declares. Holds for directories and for extensions with no known signature.
+    Magic { negated: bool },
+}
+
+const UNITS: &[(&str, u64)] = &[
+    ("TB", 1 << 40),
+    ("GB", 1 << 30),
+    ("MB", 1 << 20),
+    ("KB", 1 << 10),
+    ("B", 1),
+];
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Predicate::Size { op, bytes } => write!(f, "size {} {}", op.as_str(), fmt_size(bytes)),
+            Predicate::Mode { op, mode } => write!(f, "mode {} {:04o}", op.as_str(), mode),
+            Predicate::Empty { negated } => write!(f, "{}empty", if negated { "not " } else { "" }),
+            Predicate::Executable { negated } => {
+                write!(f, "{}executable", if negated { "not " } else { "" })
+            }
+            Predicate::Magic { negated } => write!(f, "{}magic", if negated { "not " } else { "" }),
+        }
+    }
+}
+
+fn fmt_size(bytes: u64) -> String {
+    for &(unit, scale) in UNITS {
+        if bytes >= scale && bytes.is_multiple_of(scale) {
+            return format!("{}{}", bytes / scale, unit);
+        }
+    }
+    format!("{}B", bytes)
+}
+
+/// A predicate with the byte range of its source text.
+pub(crate) type SpannedPredicate = (Predicate, Range<usize>);
+
+/// Parse the text after `where`: predicates joined by `and`, each with its byte range in `src`.
+/// Errors carry the byte offset into `src` they refer to.
+pub(crate) fn parse_predicates(src: &str) -> Result<Vec<SpannedPredicate>, (usize, String)> {
+    let toks = tokenize(src);
+    let mut preds = Vec::new();
+    let mut i = 0;
+    loop {
+        let (pred, next) = parse_one(&toks, i, src.len())?;
+        let (last_at, last) = toks[next - 1];
+        preds.push((pred, toks[i].0..last_at + last.len()));
+        i = next;
+        match toks.get(i) {
+            None => return Ok(preds),
+            Some(&(_, "and")) if i + 1 < toks.len() => i += 1,
+            Some(&(at, "and")) => return Err((at, "expected a predicate after 'and'".into())),
+            Some(&(at, t)) => return Err((at, format!("expected 'and', found '{}'", t))),
+        }
+    }
+}
+
+fn parse_one(
+    toks: &[(usize, &str)],
+    i: usize,
+    end: usize,
+) -> Result<(Predicate, usize), (usize, String)> {
+    let Some(&(at, word)) = toks.get(i) else {
+        return Err((end, "expected a predicate".into()));
+    };
+    match word {
+        "not" => match toks.get(i + 1) {
+            Some(&(_, "empty")) => Ok((Predicate::Empty { negated: true }, i + 2)),
+            Some(&(_, "executable")) => Ok((Predicate::Executable { negated: true }, i + 2)),
+            Some(&(_, "magic")) => Ok((Predicate::Magic { negated: true }, i + 2)),
+            Some(&(at, _)) => Err((
+                at,
+                "expected 'empty', 'executable' or 'magic' after 'not'".into(),
+            )),
+            None => Err((
+                end,
+                "expected 'empty', 'executable' or 'magic' after 'not'".into(),
+            )),
+        },
+        "empty" => Ok((Predicate::Empty { negated: false }, i + 1)),
+        "executable" => Ok((Predicate::Executable { negated: false }, i + 1)),
+        "magic" => Ok((Predicate::Magic { negated: false }, i + 1)),
+        "size" | "mode" => {
+            let op = match toks.get(i + 1) {
+                Some(&(at, t)) => CmpOp::parse(t)
+                    .ok_or_else(|| (at, format!("expected a comparison after '{}'", word)))?,
+                None => return Err((end, format!("expected a comparison after '{}'", word))),
+            };
+            let Some(&(vat, value)) = toks.get(i + 2) else {
+                return Err((
+                    end,
+                    format!("expected a value after '{} {}'", word, op.as_str()),
+                ));
+            };
+            let pred = if word == "size" {
+                Predicate::Size {
+                    op,
+                    bytes: parse_size(value).ok_or_else(|| {
+                        (
+                            vat,
+                            format!("invalid size '{}' (expected e.g. 512, 1KB, 10MB)", value),
+                        )
+                    })?,
+                }
+            } else {
+                if !matches!(op, CmpOp::Eq | CmpOp::Ne) {
+                    return Err((toks[i + 1].0, "mode only supports == and !=".into()));
+                }
+                Predicate::Mode {
+                    op,
+                    mode: parse_mode(value).ok_or_else(|| {
+                        (
+                            vat,
+                            format!("invalid mode '{}' (expected octal, e.g. 0644)", value),
+                        )
+                    })?,
+                }
+            };
+            Ok((pred, i + 3))
+        }
+        _ => Err((
+            at,
+            format!(
+                "unknown predicate '{}' (expected size, mode, empty, executable or magic)",
+                word
+            ),
+        )),
+    }
+}
+
+/// A predicate as written, with its words and operators separated by single spaces.
+pub(crate) fn spaced(src: &str) -> String {
+    let words: Vec<&str> = tokenize(src).into_iter().map(|(_, t)| t).collect();
+    words.join(" ")
+}
+
+/// Split into words (`size`, `1KB`) and operators (`<=`), with their byte offsets.
+fn tokenize(src: &str) -> Vec<(usize, &str)> {
+    let is_op = |c: char| matches!(c, '<' | '>' | '=' | '!');
+    let mut toks = Vec::new();
+    let mut chars = src.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let op = is_op(c);
+        let mut end = start;
+        while let Some(&(j, d)) = chars.peek() {
+            if d.is_whitespace() || is_op(d) != op {
+                break;
+            }
+            end = j + d.len_utf8();
+            chars.next();
+        }
+        toks.push((start, &src[start..end]));
+    }
+    toks
+}
+
+fn parse_size(s: &str) -> Option<u64> {
+    let digits = s.bytes().take_while(u8::is_ascii_digit).count();
+    if digits == 0 {
+        return None;
+    }
+    let (n, unit) = s.split_at(digits);
+    let scale = if unit.is_empty() {
+        1
+    } else {
+        UNITS.iter().find(|(u, _)| u.eq_ignore_ascii_case(unit))?.1
+    };
+    n.parse::<u64>().ok()?.checked_mul(scale)
+}
+
+fn parse_mode(s: &str) -> Option<u32> {
+    if s.is_empty() || s.len() > 4 || !s.bytes().all(|b| (b'0'..=b'7').contains(&b)) {
+        return None;
+    }
+    u32::from_str_radix(s, 8).ok()
+}
+
+/// What predicates can be evaluated against: an entry found during the walk.
+/// Metadata and directory contents are read on first use.
+pub(crate) struct EntryFacts<'a> {
+    path: &'a Path,
+    kind: EntryKind,
+    meta: OnceCell<Option<fs::Metadata>>,
+    dir_empty: OnceCell<bool>,
+    head: OnceCell<Vec<u8>>,
+}
+
+impl<'a> EntryFacts<'a> {
+    pub(crate) fn new(path: &'a Path, kind: EntryKind) -> Self {
+        EntryFacts {
+            path,
+            kind,
+            meta: OnceCell::new(),
+            dir_empty: OnceCell::new(),
+            head: OnceCell::new(),
+        }
+    }
+
+    fn meta(&self) -> Option<&fs::Metadata> {
+        self.meta
+            .get_or_init(|| fs::symlink_metadata(self.path).ok())
+            .as_ref()
+    }
+
+    fn is_empty(&self) -> bool {
+        match self.kind {
+            EntryKind::File => self.meta().is_some_and(|m| m.len() == 0),
+            EntryKind::Dir => *self
+                .dir_empty
+                .get_or_init(|| fs::read_dir(self.path).is_ok_and(|mut rd| rd.next().is_none())),
+        }
+    }
+
+    /// The first [`HEAD_LEN`] bytes of the file (fewer if it's shorter or unreadable).
+    fn head(&self) -> &[u8] {
+        self.head.get_or_init(|| {
+            let mut buf = Vec::with_capacity(HEAD_LEN);
+            if let Ok(f) = fs::File::open(self.path) {
+                let _ = f.take(HEAD_LEN as u64).read_to_end(&mut buf);
+            }
+            buf
+        })
+    }
+
+    fn mode(&self) -> Option<u32> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            self.meta().map(|m| m.permissions().mode() & 0o7777)
+        }
+        #[cfg(not(unix))]
+        {
+            None
+        }
+    }
+}
+
+impl EntryFacts<'_> {
+    /// Whether the file's content agrees with its extension, and what it looks like.
+    fn check_magic(&self) -> (bool, String) {
+        if self.kind == EntryKind::Dir {
+            return (true, "a directory".to_string());
+        }
+        let name = self
+            .path
+            .file_name()
+            .map(|n| n.to_string_lossy())
+            .unwrap_or_default();
+        let Some(declared) = declared_type(&name) else {
+            return (true, "no known signature for this extension".to_string());
+        };
+        let head = self.head();
+        if declared.matches(head) {
+            return (true, format!("content is {}", declared.name));
+        }
+        let found = if head.is_empty() {
+            "empty"
+        } else {
+            sniff(head).map_or("unrecognized", |t| t.name)
+        };
+        (
+            false,
+            format!("content is {}, not {}", found, declared.name),
+        )
+    }
+}
+
+impl Predicate {
+    /// Whether the predicate holds for `facts`, and what was observed
+    /// (`size 512B < 1KB`, `mode 0755 not == 0644`), for attaching to findings.
+    pub(crate) fn eval(&self, facts: &EntryFacts) -> (bool, String) {
+        let not = |holds: bool| if holds { "" } else { "not " };
+        match *self {
+            Predicate::Size { op, bytes } => {
+                if facts.kind == EntryKind::Dir {
+                    return (false, "size n/a for a directory".to_string());
+                }
+                let Some(len) = facts.meta().map(fs::Metadata::len) else {
+                    return (false, "size unavailable".to_string());
+                };
+                let holds = op.test(len, bytes);
+                let detail = format!(
+                    "size {}B {}{} {}",
+                    len,
+                    not(holds),
+                    op.as_str(),
+                    fmt_size(bytes)
+                );
+                (holds, detail)
+            }
+            Predicate::Mode { op, mode } => {
+                let Some(actual) = facts.mode() else {
+                    return (false, "mode unavailable on this platform".to_string());
+                };
+                let holds = op.test(actual, mode);
+                let detail = format!(
+                    "mode {:04o} {}{} {:04o}",
+                    actual,
+                    not(holds),
+                    op.as_str(),
+                    mode
+                );
+                (holds, detail)
+            }
+            Predicate::Empty { negated } => {
+                let empty = facts.is_empty();
+                (empty != negated, format!("{}empty", not(empty)))
+            }
+            Predicate::Executable { negated } => {
+                let exec = facts.mode().is_some_and(|m| m & 0o111 != 0);
+                (exec != negated, format!("{}executable", not(exec)))
+            }
+            Predicate::Magic { negated } => {
+                let (ok, detail) = facts.check_magic();
+                (ok != negated, detail)
+            }
+        }
+    }
+}
+
+/// Evaluate every predicate; `true` if they all hold. Details are returned for all of them.
+pub(crate) fn eval_all(preds: &[Predicate], facts: &EntryFacts) -> (bool, Vec<String>) {
+    let mut all = true;
+    let mut details = Vec::with_capacity(preds.len());
+    for p in preds {
+        let (holds, detail) = p.eval(facts);
+        all &= holds;
+        details.push(detail);
+    }
+    (all, details)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_predicates(src: &str) -> Result<Vec<Predicate>, (usize, String)> {
+        let preds = super::parse_predicates(src)?;
+        Ok(preds.into_iter().map(|(p, _)| p).collect())
+    }
+
+    #[test]
+    fn parses_predicate_lists() {
+        assert_eq!(
+            parse_predicates("size < 1KB").unwrap(),
+            vec![Predicate::Size {
+                op: CmpOp::Lt,
+                bytes: 1024
+            }]
+        );
+        assert_eq!(
+            parse_predicates("size>=10mb and not executable").unwrap(),
+            vec![
+                Predicate::Size {
+                    op: CmpOp::Ge,
+                    bytes: 10 << 20
+                },
+                Predicate::Executable { negated: true },
+            ]
+        );
+        assert_eq!(
+            parse_predicates("mode != 0644").unwrap(),
+            vec![Predicate::Mode {
+                op: CmpOp::Ne,
+                mode: 0o644
+            }]
+        );
+        assert_eq!(
+            parse_predicates("empty").unwrap(),
+            vec![Predicate::Empty { negated: false }]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_predicates_with_offsets() {
+        for (src, at, want) in [
+            ("colour == red", 0, "unknown predicate"),
+            ("size < 1XB", 7, "invalid size"),
+            ("mode < 0644", 5, "only supports == and !="),
+            ("mode == 0999", 8, "invalid mode"),
+            ("empty and", 6, "after 'and'"),
+            ("empty or executable", 6, "expected 'and'"),
+            ("size", 4, "expected a comparison"),
+        ] {
+            let (got_at, msg) = parse_predicates(src).unwrap_err();
+            assert!(msg.contains(want), "{src}: {msg}");
+            assert_eq!(got_at, at, "{src}");
+        }
+    }
+
+    #[test]
+    fn predicates_have_their_source_ranges() {
+        let src = "size<1024  and not   executable";
+        let ranges: Vec<_> = super::parse_predicates(src)
+            .unwrap()
+            .into_iter()
+            .map(|(_, r)| &src[r])
+            .collect();
+        assert_eq!(ranges, ["size<1024", "not   executable"]);
+        assert_eq!(spaced(ranges[0]), "size < 1024");
+        assert_eq!(spaced(ranges[1]), "not executable");
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for src in [
+            "size < 1KB",
+            "size >= 1536B",
+            "mode == 0644",
+            "not empty",
+            "not magic",
+        ] {
+            let p = parse_predicates(src).unwrap();
+            assert_eq!(p[0].to_string(), src);
+        }
+    }
+}
@@ -1,6 +1,8 @@
-use crate::spec::Severity;
+use crate::escape::escape_path;
+use crate::spec::{Captures, EntryKind, MatchSettings, Severity};
 use crate::walk::WalkOutput;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Status {
@@ -10,7 +12,13 @@ pub enum Status {
 }
 
 fn canon_key(s: &str) -> String {
-    let mut t = s.trim().replace('\\', "/");
+    // `\` is a separator only on Windows. Elsewhere it can be part of a name, and it
+    // introduces the `\xNN` escapes used for names that aren't valid UTF-8.
+    let mut t = if cfg!(windows) {
+        s.trim().replace('\\', "/")
+    } else {
+        s.trim().to_string()
+    };
 
     // strip leading "./" and "/"
     t = t
@@ -23,20 +31,6 @@ fn canon_key(s: &str) -> String {
         t.pop();
     }
 
-    // On case-insensitive file systems (like macOS), normalize to lowercase
-    // so that paths that differ only in case are treated as the same.
-    // This ensures that if a file is matched with one case, it's also
-    // considered matched when queried with a different case.
-    #[cfg(target_os = "macos")]
-    {
-        t = t.to_lowercase();
-    }
-    // On Windows, also normalize to lowercase for case-insensitive matching
-    #[cfg(target_os = "windows")]
-    {
-        t = t.to_lowercase();
-    }
-
     t
 }
 
@@ -55,6 +49,31 @@ pub struct Diagnostic {
     pub rule_lines: Vec<usize>,
 }
 
+/// Everything the report knows about a single path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportEntry {
+    pub status: Status,
+    /// File or directory. `None` for entries added via [`Report::set_status`] without a walk.
+    pub kind: Option<EntryKind>,
+    /// Line of the rule that decided the status. `None` for unaccounted paths, for
+    /// directories that are only allowed because something below them is, and for paths
+    /// decided by a rule from outside the spec file (see [`Rule::source`](crate::Rule)).
+    pub rule_line: Option<usize>,
+    /// Named placeholder values captured by the deciding rule.
+    pub captures: Captures,
+}
+
+/// Per-category path counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub allowed_files: usize,
+    pub allowed_dirs: usize,
+    pub ignored_files: usize,
+    pub ignored_dirs: usize,
+    pub unaccounted_files: usize,
+    pub unaccounted_dirs: usize,
+}
+
 /// A report containing the results of validating a directory tree against an `.fspec` file.
 ///
 /// The report contains:
@@ -63,72 +82,204 @@ pub struct Diagnostic {
 #[derive(Debug, Default)]
 pub struct Report {
     // Key: normalized relative path string ("src/main.rs", "bin", ...)
-    statuses: BTreeMap<String, Status>,
+    entries: BTreeMap<String, ReportEntry>,
     diagnostics: Vec<Diagnostic>,
+    // Case-insensitive lookups: lowercased key -> stored key. `None` when case-sensitive.
+    // Walked paths are stored under their on-disk spelling, so names that differ only in case
+    // each keep an entry; a lookup matching several finds the lexicographically smallest.
+    folded: Option<HashMap<String, String>>,
 }
 
 impl Report {
-    pub fn from_walk_output(walk: &WalkOutput) -> Report {
-        let mut report = Report::default();
-
-        for p in &walk.allowed_files {
-            report.set_status(p.to_string_lossy().as_ref(), Status::Allowed);
-        }
-        for p in &walk.allowed_dirs {
-            report.set_status(p.to_string_lossy().as_ref(), Status::Allowed);
+    /// An empty report whose path lookups ignore case
+    /// (used when [`MatchSettings::case_insensitive`] is set).
+    pub fn case_insensitive() -> Report {
+        Report {
+            folded: Some(HashMap::new()),
+            ..Report::default()
         }
-        for p in &walk.ignored_files {
-            report.set_status(p.to_string_lossy().as_ref(), Status::Ignored);
-        }
-        for p in &walk.ignored_dirs {
-            report.set_status(p.to_string_lossy().as_ref(), Status::Ignored);
-        }
-        for p in &walk.unaccounted_files {
-            report.set_status(p.to_string_lossy().as_ref(), Status::Unaccounted);
+    }
+
+    pub fn from_walk_output(walk: &WalkOutput, settings: &MatchSettings) -> Report {
+        let mut report = if settings.case_insensitive {
+            Report::case_insensitive()
+        } else {
+            Report::default()
+        };
+
+        let sets = [
+            (&walk.allowed_files, Status::Allowed, EntryKind::File),
+            (&walk.allowed_dirs, Status::Allowed, EntryKind::Dir),
+            (&walk.ignored_files, Status::Ignored, EntryKind::File),
+            (&walk.ignored_dirs, Status::Ignored, EntryKind::Dir),
+            (
+                &walk.unaccounted_files,
+                Status::Unaccounted,
+                EntryKind::File,
+            ),
+            (&walk.unaccounted_dirs, Status::Unaccounted, EntryKind::Dir),
+        ];
+
+        for (paths, status, kind) in sets {
+            for p in paths {
+                report.insert_walked(walk, p, status, kind);
+            }
         }
-        for p in &walk.unaccounted_dirs {
-            report.set_status(p.to_string_lossy().as_ref(), Status::Unaccounted);
+        for d in &walk.diagnostics {
+            report.push_diagnostic(d.clone());
         }
 
         report
     }
+
+    fn insert_walked(&mut self, walk: &WalkOutput, p: &PathBuf, status: Status, kind: EntryKind) {
+        let (rule_line, captures) = match walk.decided_by.get(p) {
+            Some(m) if status != Status::Unaccounted => (m.spec_line(), m.captures.clone()),
+            _ => (None, Captures::new()),
+        };
+
+        // Not merged with an entry that differs only in case: both exist on disk.
+        let k = canon_key(&escape_path(p));
+        self.fold(&k);
+        self.entries.insert(
+            k,
+            ReportEntry {
+                status,
+                kind: Some(kind),
+                rule_line,
+                captures,
+            },
+        );
+    }
+
+    /// Canonical key for `path`: an existing entry's key if one matches under the case policy.
+    /// An exact match wins over one that differs only in case.
+    fn key(&self, path: &str) -> String {
+        let k = canon_key(path);
+        match &self.folded {
+            Some(folded) if !self.entries.contains_key(&k) => {
+                folded.get(&k.to_lowercase()).cloned().unwrap_or(k)
+            }
+            _ => k,
+        }
+    }
+
+    fn insert_key(&mut self, path: &str) -> String {
+        let k = self.key(path);
+        self.fold(&k);
+        k
+    }
+
+    /// Make `k` findable regardless of case, unless a smaller key already folds the same way.
+    fn fold(&mut self, k: &str) {
+        if let Some(folded) = &mut self.folded {
+            let slot = folded
+                .entry(k.to_lowercase())
+                .or_insert_with(|| k.to_string());
+            if k < slot.as_str() {
+                *slot = k.to_string();
+            }
+        }
+    }
+
     pub fn set_status(&mut self, path: impl AsRef<str>, status: Status) {
-        let k = canon_key(path.as_ref());
-        self.statuses.insert(k, status);
+        let k = self.insert_key(path.as_ref());
+        self.entries
+            .entry(k)
+            .and_modify(|e| e.status = status)
+            .or_insert(ReportEntry {
+                status,
+                kind: None,
+                rule_line: None,
+                captures: Captures::new(),
+            });
+    }
+
+    pub fn set_entry(&mut self, path: impl AsRef<str>, entry: ReportEntry) {
+        let k = self.insert_key(path.as_ref());
+        self.entries.insert(k, entry);
     }
 
     pub fn status_of(&self, path: impl AsRef<str>) -> Option<Status> {
-        let k = canon_key(path.as_ref());
-        self.statuses.get(&k).copied()
+        self.entry(path).map(|e| e.status)
+    }
+
+    pub fn entry(&self, path: impl AsRef<str>) -> Option<&ReportEntry> {
+        let k = self.key(path.as_ref());
+        self.entries.get(&k)
+    }
+
+    /// All paths in the report, sorted by normalized path.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &ReportEntry)> {
+        self.entries.iter().map(|(p, e)| (p.as_str(), e))
+    }
+
+    /// Counts per status and entry kind. Entries without a known kind are not counted.
+    pub fn summary(&self) -> Summary {
+        let mut s = Summary::default();
+        for e in self.entries.values() {
+            let slot = match (e.status, e.kind) {
+                (Status::Allowed, Some(EntryKind::File)) => &mut s.allowed_files,
+                (Status::Allowed, Some(EntryKind::Dir)) => &mut s.allowed_dirs,
+                (Status::Ignored, Some(EntryKind::File)) => &mut s.ignored_files,
+                (Status::Ignored, Some(EntryKind::Dir)) => &mut s.ignored_dirs,
+                (Status::Unaccounted, Some(EntryKind::File)) => &mut s.unaccounted_files,
+                (Status::Unaccounted, Some(EntryKind::Dir)) => &mut s.unaccounted_dirs,
+                (_, None) => continue,
+            };
+            *slot += 1;
+        }
+        s
     }
 
     pub fn push_diagnostic(&mut self, d: Diagnostic) {
         self.diagnostics.push(d);
     }
 
+    /// Keep only the entries for which `keep(path, entry)` returns `true`.
+    pub fn retain_entries(&mut self, mut keep: impl FnMut(&str, &ReportEntry) -> bool) {
+        self.entries.retain(|p, e| keep(p, e));
+        if let Some(folded) = &mut self.folded {
+            folded.clear();
+            let keys: Vec<String> = self.entries.keys().cloned().collect();
+            for k in keys {
+                self.fold(&k);
+            }
+        }
+    }
+
+    /// Keep only the diagnostics for which `keep` returns `true`.
+    pub fn retain_diagnostics(&mut self, keep: impl FnMut(&Diagnostic) -> bool) {
+        self.diagnostics.retain(keep);
+    }
+
     pub fn is_allowed(&self, path: &str) -> bool {
-        let k = canon_key(path);
-        self.statuses.get(&k).is_some_and(|s| *s == Status::Allowed)
+        self.status_of(path) == Some(Status::Allowed)
     }
     pub fn is_ignored(&self, path: &str) -> bool {
-        let k = canon_key(path);
-        self.statuses.get(&k).is_some_and(|s| *s == Status::Ignored)
+        self.status_of(path) == Some(Status::Ignored)
     }
     pub fn is_unaccounted(&self, path: &str) -> bool {
-        let k = canon_key(path);
-        self.statuses
-            .get(&k)
-            .is_some_and(|s| *s == Status::Unaccounted)
+        self.status_of(path) == Some(Status::Unaccounted)
     }
 
     pub fn unaccounted_paths(&self) -> Vec<&str> {
-        self.statuses
+        self.entries
             .iter()
-            .filter_map(|(p, s)| (*s == Status::Unaccounted).then_some(p.as_str()))
+            .filter_map(|(p, e)| (e.status == Status::Unaccounted).then_some(p.as_str()))
             .collect()
     }
 
     pub fn diagnostics(&self) -> &[Diagnostic] {
         &self.diagnostics
     }
+
+    /// `true` if no path is unaccounted and no diagnostic is an error (such as `denied`).
+    pub fn is_ok(&self) -> bool {
+        self.unaccounted_paths().is_empty()
+            && !self
+                .diagnostics
+                .iter()
+                .any(|d| d.severity == Severity::Error)
+    }
 }
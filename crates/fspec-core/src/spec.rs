@@ -1,5 +1,13 @@
-use fspec_placeholder::ComponentAst;
-use regex::Regex;
+use crate::predicate::Predicate;
+use fspec_placeholder::{ComponentAst, Conformance, ValueCheck};
+use regex::bytes::Regex;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use unicode_normalization::{UnicodeNormalization, is_nfc, is_nfd};
+
+/// Named placeholder values captured while matching a path (name -> value).
+pub type Captures = BTreeMap<String, String>;
 
 /// A compiled component with a pre-compiled regex for efficient matching.
 ///
@@ -10,10 +18,13 @@ use regex::Regex;
 pub struct CompiledComponent {
     /// The original AST (kept for debugging/comparison)
     pub ast: ComponentAst,
-    /// Pre-compiled regex pattern (anchored with ^ and $)
+    /// Pre-compiled regex pattern (anchored with ^ and $), matched against raw name bytes
     pub regex: Regex,
     /// Mapping of placeholder names to capture group indices
     pub placeholder_indices: Vec<(String, usize)>,
+    /// Checks on captured values that the regex can't express (e.g. `range(1900,2100)`),
+    /// keyed by capture group index.
+    pub value_checks: Vec<(usize, ValueCheck)>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
@@ -34,6 +45,19 @@ pub struct MatchSettings {
     pub allow_file_or_dir_leaf: bool,
     /// Default severity level for unaccounted paths in the report.
     pub default_severity: Severity,
+    /// How strictly placeholder limiters are validated when the spec is parsed.
+    /// `Strict` rejects unknown limiter names; `Lenient` lets them match any non-empty value.
+    pub conformance: Conformance,
+    /// If `true`, literals and one-of choices match regardless of case, and report lookups
+    /// ignore case. Off by default on every platform, so a spec behaves the same everywhere.
+    /// Individual placeholders can opt in with the `i:` flag (`{ext:i:jpg|jpeg}`).
+    pub case_insensitive: bool,
+    /// Unicode normalization applied to spec literals and path names before matching.
+    /// With `Nfc`/`Nfd`, names not already in that form are also reported as diagnostics.
+    pub normalization: NormalizationForm,
+    /// If `true`, names that aren't valid UTF-8 are reported as `non_utf8_name` diagnostics.
+    /// They are matched byte-wise and shown with `\xNN` escapes either way.
+    pub report_non_utf8_names: bool,
 }
 
 impl Default for MatchSettings {
@@ -41,14 +65,64 @@ impl Default for MatchSettings {
         Self {
             allow_file_or_dir_leaf: true,
             default_severity: Severity::default(),
+            conformance: Conformance::default(),
+            case_insensitive: false,
+            normalization: NormalizationForm::default(),
+            report_non_utf8_names: false,
+        }
+    }
+}
+
+/// Unicode normalization form that names are compared in.
+///
+/// macOS tends to store names decomposed (NFD) while most editors write composed text (NFC),
+/// so `é` in a spec and `é` on disk may differ byte-wise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NormalizationForm {
+    /// Compare names as they are.
+    #[default]
+    None,
+    /// Canonical composition.
+    Nfc,
+    /// Canonical decomposition.
+    Nfd,
+}
+
+impl NormalizationForm {
+    /// `s` in this form (unchanged for `None`).
+    pub fn apply(self, s: &str) -> Cow<'_, str> {
+        match self {
+            NormalizationForm::None => Cow::Borrowed(s),
+            NormalizationForm::Nfc if is_nfc(s) => Cow::Borrowed(s),
+            NormalizationForm::Nfd if is_nfd(s) => Cow::Borrowed(s),
+            NormalizationForm::Nfc => Cow::Owned(s.nfc().collect()),
+            NormalizationForm::Nfd => Cow::Owned(s.nfd().collect()),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NormalizationForm::None => "none",
+            NormalizationForm::Nfc => "NFC",
+            NormalizationForm::Nfd => "NFD",
         }
     }
 }
 
+/// Whether a filesystem entry is a file or a directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EntryKind {
+    File,
+    Dir,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RuleKind {
     Allow,
     Ignore,
+    /// Reports every matching path as a violation. Checked on its own rather than
+    /// taking part in last-match-wins, so it never changes a path's status.
+    Deny,
 }
 
 // Note: Rule, FSPattern, FSEntry, DirType, FileType, and FileOrDirType
@@ -59,6 +133,31 @@ pub struct Rule {
     pub line: usize,
     pub kind: RuleKind,
     pub pattern: FSPattern,
+    /// The rule's `where` clause; the rule only applies where all of these hold.
+    pub predicates: Vec<Predicate>,
+    /// Where the rule came from if not the spec file, such as the preset that added it.
+    /// `line` then only orders it after the spec's own rules.
+    pub source: Option<Arc<str>>,
+}
+
+/// A numeric limit set by a directive line (`max-depth 6`), and the line it was set on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limit {
+    pub value: usize,
+    pub line: usize,
+}
+
+/// Spec-level limits on the shape of the tree. `None` means no limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// `max-depth N`: entries may be at most `N` components below the root.
+    pub max_depth: Option<Limit>,
+    /// `max-path-length N`: relative paths may be at most `N` bytes.
+    pub max_path_length: Option<Limit>,
+    /// `max-name-length N`: names may be at most `N` bytes.
+    pub max_name_length: Option<Limit>,
+    /// `max-entries N`: a directory may hold at most `N` entries.
+    pub max_entries: Option<Limit>,
 }
 
 #[derive(Debug, Clone)]
@@ -1,18 +1,84 @@
+use crate::escape::escape_path;
+use crate::manifest::Manifest;
 use crate::matcher::matches_allowed_anchored_dir;
 use crate::matcher::matches_allowed_anchored_file;
 use crate::matcher::matches_allowed_unanchored_dir;
 use crate::matcher::matches_allowed_unanchored_file;
+use crate::matcher::matches_denied_anchored_dir;
+use crate::matcher::matches_denied_anchored_file;
+use crate::matcher::matches_denied_unanchored_dir;
+use crate::matcher::matches_denied_unanchored_file;
 use crate::matcher::matches_ignored_anchored_dir;
 use crate::matcher::matches_ignored_anchored_file;
 use crate::matcher::matches_ignored_unanchored_dir;
 use crate::matcher::matches_ignored_unanchored_file;
-use crate::spec::RuleKind;
-use std::collections::HashSet;
+use crate::parse::ParsedSpec;
+use crate::predicate::{EntryFacts, Predicate, eval_all};
+use crate::report::{Diagnostic, Status};
+use crate::spec::{
+    Captures, EntryKind, Limits, MatchSettings, NormalizationForm, RuleKind, Severity,
+};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use crate::{Error, Rule};
 
+/// The rule that decided a path's status, and the placeholder values it captured.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleMatch {
+    /// Line of the deciding rule in the `.fspec` file.
+    pub rule_line: usize,
+    /// Named placeholder values captured by that rule (empty for inherited ignores).
+    pub captures: Captures,
+    /// The deciding rule's [`Rule::source`].
+    pub source: Option<Arc<str>>,
+}
+
+impl RuleMatch {
+    /// The deciding rule's line, or `None` if it isn't one of the spec file's own rules.
+    pub fn spec_line(&self) -> Option<usize> {
+        self.source.is_none().then_some(self.rule_line)
+    }
+}
+
+/// An entry as the walk decides it.
+#[derive(Debug, Clone, Copy)]
+pub struct WalkEntry<'a> {
+    /// Path relative to the root of the walk.
+    pub path: &'a Path,
+    pub kind: EntryKind,
+    /// The entry's final status. A directory is allowed if anything below it is.
+    pub status: Status,
+    /// The rule that decided the status, and what it captured. `None` for unaccounted paths
+    /// and for directories that are only allowed because something below them is.
+    pub decided_by: Option<&'a RuleMatch>,
+}
+
+impl WalkEntry<'_> {
+    /// The path as reports show it: `/`-separated, with `\` escaped as `\\` and bytes that
+    /// aren't valid UTF-8 as `\xNN`.
+    pub fn path_text(&self) -> String {
+        escape_path(self.path)
+    }
+}
+
+/// Receives the results of a walk as it proceeds, so a caller can stream them instead of
+/// holding the whole tree in memory. [`WalkOutput`] is the implementation that collects them.
+///
+/// Each entry is visited exactly once. Directories are visited after everything below them,
+/// once it's known whether anything there was allowed. Diagnostics are delivered in the order
+/// they're raised, before the entry they concern (or, for manifest findings about missing
+/// files, at the end).
+pub trait WalkVisitor {
+    fn entry(&mut self, entry: &WalkEntry<'_>);
+    fn diagnostic(&mut self, diagnostic: Diagnostic);
+}
+
+/// A whole walk, collected: the [`WalkVisitor`] behind [`Report`](crate::Report).
 #[derive(Debug, Clone, Default)]
 pub struct WalkOutput {
     pub allowed_files: HashSet<PathBuf>,
@@ -21,40 +87,17 @@ pub struct WalkOutput {
     pub ignored_dirs: HashSet<PathBuf>,
     pub unaccounted_files: HashSet<PathBuf>,
     pub unaccounted_dirs: HashSet<PathBuf>,
+    /// Deciding rule per path. Paths that are unaccounted, or only allowed
+    /// structurally (as an ancestor of something allowed), have no entry.
+    pub decided_by: HashMap<PathBuf, RuleMatch>,
+    /// Findings raised while walking: about individual names (`unnormalized_name`,
+    /// `non_utf8_name`), spec limits being exceeded (`max_depth_exceeded`, ...) and
+    /// rule predicates (`denied`, `predicate_failed`, `content_mismatch`), and checksum
+    /// manifests (`manifest_hash_mismatch`, `manifest_unlisted`, `manifest_missing`, ...).
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 impl WalkOutput {
-    fn allow_with_ancestors(&mut self, path: PathBuf, is_file: bool) {
-        // 1) allow the path itself
-        if is_file {
-            self.allowed_files.insert(path.clone());
-            self.unaccounted_files.remove(&path);
-            self.ignored_files.remove(&path);
-        } else {
-            self.allowed_dirs.insert(path.clone());
-            self.unaccounted_dirs.remove(&path);
-            self.ignored_dirs.remove(&path);
-        }
-
-        // 2) walk ancestors (dirs only)
-        let mut cur = path.parent();
-
-        while let Some(dir) = cur {
-            // 🔧 Stop before the empty relative root ("")
-            if dir.as_os_str().is_empty() {
-                break;
-            }
-
-            let pb = dir.to_path_buf();
-
-            self.allowed_dirs.insert(pb.clone());
-            self.unaccounted_dirs.remove(&pb);
-            self.ignored_dirs.remove(&pb);
-
-            cur = dir.parent();
-        }
-    }
-
     pub fn mark_unaccounted_dir(&mut self, path: PathBuf) {
         // Don't mark if already justified
         if self.allowed_dirs.contains(&path) || self.ignored_dirs.contains(&path) {
@@ -72,14 +115,38 @@ impl WalkOutput {
         self.unaccounted_files.insert(path);
     }
 
-    pub fn mark_ignored_dir(&mut self, path: PathBuf) {
+    pub fn mark_ignored_dir(&mut self, path: PathBuf, by: RuleMatch) {
         self.ignored_dirs.insert(path.clone());
         self.unaccounted_dirs.remove(&path);
+        self.decided_by.insert(path, by);
     }
 
-    pub fn mark_ignored_file(&mut self, path: PathBuf) {
+    pub fn mark_ignored_file(&mut self, path: PathBuf, by: RuleMatch) {
         self.ignored_files.insert(path.clone());
         self.unaccounted_files.remove(&path);
+        self.decided_by.insert(path, by);
+    }
+}
+
+impl WalkVisitor for WalkOutput {
+    fn entry(&mut self, entry: &WalkEntry<'_>) {
+        let path = entry.path.to_path_buf();
+        if let Some(by) = entry.decided_by {
+            self.decided_by.insert(path.clone(), by.clone());
+        }
+        let set = match (entry.status, entry.kind) {
+            (Status::Allowed, EntryKind::File) => &mut self.allowed_files,
+            (Status::Allowed, EntryKind::Dir) => &mut self.allowed_dirs,
+            (Status::Ignored, EntryKind::File) => &mut self.ignored_files,
+            (Status::Ignored, EntryKind::Dir) => &mut self.ignored_dirs,
+            (Status::Unaccounted, EntryKind::File) => &mut self.unaccounted_files,
+            (Status::Unaccounted, EntryKind::Dir) => &mut self.unaccounted_dirs,
+        };
+        set.insert(path);
+    }
+
+    fn diagnostic(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
     }
 }
 
@@ -100,8 +167,8 @@ pub struct WalkCtx {
     /// Depth from root. Useful for debug indentation.
     pub depth: usize,
 
-    /// The returned list of allowed/ignored/unaccounted for dirs+files
-    pub walk_output: WalkOutput,
+    /// Diagnostics raised but not yet handed to the visitor.
+    pub diagnostics: Vec<Diagnostic>,
 
     /// Placeholder for Option A: the set of rule indices still "in play" in this subtree.
     /// Today we just carry it forward unchanged.
@@ -110,6 +177,20 @@ pub struct WalkCtx {
     /// Placeholder for Option A: an inherited "subtree status".
     /// For example, later you might store "ignored by rule #N unless overridden".
     pub inherited: InheritedState,
+
+    /// Settings the spec was parsed with (normalization, ...).
+    pub settings: MatchSettings,
+
+    /// Limits set by the spec's directive lines (`max-depth`, ...).
+    pub limits: Limits,
+
+    /// Checksum manifests declared by the spec, with what the walk has matched so far.
+    pub(crate) manifests: Vec<Manifest>,
+
+    /// Files left out of the walk, relative to root: `.fspec` and any [`Spec::skip_file`]s.
+    ///
+    /// [`Spec::skip_file`]: crate::Spec::skip_file
+    pub(crate) skipped: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -120,19 +201,155 @@ pub enum InheritedState {
     // etc.
 }
 
-pub fn walk_tree(root: &Path, rules: &[Rule]) -> Result<WalkOutput, Error> {
+pub(crate) fn walk_tree(
+    root: &Path,
+    spec: &ParsedSpec,
+    settings: &MatchSettings,
+    skipped: &[PathBuf],
+    visitor: &mut dyn WalkVisitor,
+) -> Result<(), Error> {
+    let rules = &spec.rules;
+    let mut diagnostics = Vec::new();
+    let mut manifests = Vec::with_capacity(spec.manifests.len());
+    for decl in &spec.manifests {
+        let (m, diags) = Manifest::load(root, decl);
+        diagnostics.extend(diags);
+        manifests.push(m);
+    }
+
     let mut ctx = WalkCtx {
         root: root.to_path_buf(),
         rel: PathBuf::new(),
         depth: 0,
-        walk_output: WalkOutput::default(),
+        diagnostics,
         live_rule_idxs: (0..rules.len()).collect(),
         inherited: InheritedState::None,
+        settings: *settings,
+        limits: spec.limits,
+        manifests,
+        skipped: skipped_below(root, skipped),
     };
 
-    walk_dir(&mut ctx, rules)?;
+    walk_dir(&mut ctx, rules, false, visitor)?;
 
-    Ok(ctx.walk_output)
+    for m in std::mem::take(&mut ctx.manifests) {
+        ctx.diagnostics.extend(m.finish());
+    }
+    flush_diagnostics(&mut ctx, visitor);
+
+    Ok(())
+}
+
+/// `.fspec`, and those of `skipped` that are inside `root`, relative to `root`.
+fn skipped_below(root: &Path, skipped: &[PathBuf]) -> Vec<PathBuf> {
+    let mut rel = vec![PathBuf::from(".fspec")];
+    if let Ok(root) = root.canonicalize() {
+        rel.extend(skipped.iter().filter_map(|p| {
+            let p = p.canonicalize().ok()?;
+            Some(p.strip_prefix(&root).ok()?.to_path_buf())
+        }));
+    }
+    rel
+}
+
+/// Whether the entry `name` in the directory being walked is a skipped file.
+fn is_skipped(ctx: &WalkCtx, name: &OsStr) -> bool {
+    ctx.skipped
+        .iter()
+        .any(|p| p.file_name() == Some(name) && p.parent() == Some(ctx.rel.as_path()))
+}
+
+/// Hand the diagnostics raised so far to the visitor.
+fn flush_diagnostics(ctx: &mut WalkCtx, visitor: &mut dyn WalkVisitor) {
+    for d in ctx.diagnostics.drain(..) {
+        visitor.diagnostic(d);
+    }
+}
+
+/// Hand a decided entry (and the diagnostics raised before it) to the visitor.
+fn visit(
+    ctx: &mut WalkCtx,
+    visitor: &mut dyn WalkVisitor,
+    path: &Path,
+    kind: EntryKind,
+    (status, decided_by): (Status, Option<RuleMatch>),
+) {
+    flush_diagnostics(ctx, visitor);
+    visitor.entry(&WalkEntry {
+        path,
+        kind,
+        status,
+        decided_by: decided_by.as_ref(),
+    });
+}
+
+/// Decide a single relative path the way the walk would, without touching the filesystem.
+/// Its ancestors are decided first, so a path under an ignored directory inherits the ignore.
+///
+/// Rules with a `where` clause never match, since there is no entry to evaluate them against,
+/// and a directory is only allowed by a rule of its own (not because something below it is).
+pub(crate) fn classify_path(
+    spec: &ParsedSpec,
+    settings: &MatchSettings,
+    rel_path: &Path,
+    kind: EntryKind,
+) -> (Status, Option<RuleMatch>) {
+    let rules = &spec.rules;
+    let mut ctx = WalkCtx {
+        root: PathBuf::new(),
+        rel: PathBuf::new(),
+        depth: 0,
+        diagnostics: Vec::new(),
+        live_rule_idxs: (0..rules.len()).collect(),
+        inherited: InheritedState::None,
+        settings: *settings,
+        limits: spec.limits,
+        manifests: Vec::new(),
+        skipped: Vec::new(),
+    };
+
+    let mut parts = rel_path.iter().peekable();
+    while let Some(name) = parts.next() {
+        ctx.rel.push(name);
+        ctx.depth += 1;
+        let kind = if parts.peek().is_some() {
+            EntryKind::Dir
+        } else {
+            kind
+        };
+        let verdict = classify_entry_last_wins(&ctx, rules, &ctx.rel, kind, None, &mut Vec::new());
+        if parts.peek().is_none() {
+            return verdict.decide(rules);
+        }
+        if let Verdict::Ignore { rule_idx, .. } = verdict {
+            ctx.inherited = InheritedState::SubtreeIgnored { rule_idx };
+        }
+    }
+    (Status::Unaccounted, None)
+}
+
+/// Lines of the `deny` rules without a `where` clause that match a path, as
+/// [`classify_path`] would see it.
+pub(crate) fn denied_lines(
+    spec: &ParsedSpec,
+    settings: &MatchSettings,
+    rel_path: &Path,
+    kind: EntryKind,
+) -> Vec<usize> {
+    let rel_path = &*match_path(settings.normalization, rel_path);
+    spec.rules
+        .iter()
+        .filter(|r| r.kind == RuleKind::Deny && r.predicates.is_empty())
+        .filter(|r| match kind {
+            EntryKind::Dir => matches_denied_anchored_dir(r, rel_path)
+                .or_else(|| matches_denied_unanchored_dir(r, rel_path))
+                .is_some(),
+            EntryKind::File => matches_denied_anchored_file(r, rel_path)
+                .or_else(|| matches_denied_unanchored_file(r, rel_path))
+                .is_some(),
+        })
+        .map(|r| r.line)
+        .collect()
 }
 
 /// Walk a directory with a mutable context representing "where we are".
@@ -141,7 +358,15 @@ pub fn walk_tree(root: &Path, rules: &[Rule]) -> Result<WalkOutput, Error> {
 /// Later, this is where you will:
 /// - refine ctx.live_rule_idxs based on which rules can still match below
 /// - compute effective decisions for this directory (dir-only allow, ignore subtree, etc.)
-fn walk_dir(ctx: &mut WalkCtx, rules: &[Rule]) -> Result<(), Error> {
+///
+/// `ignored` is whether the directory itself is ignored. Returns whether anything in it
+/// was allowed.
+fn walk_dir(
+    ctx: &mut WalkCtx,
+    rules: &[Rule],
+    ignored: bool,
+    visitor: &mut dyn WalkVisitor,
+) -> Result<bool, Error> {
     // Build the absolute path we are currently at.
     let abs = ctx.root.join(&ctx.rel);
 
@@ -161,12 +386,20 @@ fn walk_dir(ctx: &mut WalkCtx, rules: &[Rule]) -> Result<(), Error> {
     }
     entries.sort_by_key(|e| e.file_name());
 
+    if !ignored {
+        let entry_count = entries
+            .iter()
+            .filter(|e| !is_skipped(ctx, &e.file_name()))
+            .count();
+        check_entry_count(ctx, entry_count);
+    }
+
+    let mut any_allowed = false;
     for ent in entries {
         let name = ent.file_name();
-        let name = name.to_string_lossy();
 
-        // Skip the spec file itself (optional, but usually desired).
-        if ctx.rel.as_os_str().is_empty() && name == ".fspec" {
+        // Skip the spec file itself, and files configuring the check.
+        if is_skipped(ctx, &name) {
             continue;
         }
 
@@ -188,28 +421,47 @@ fn walk_dir(ctx: &mut WalkCtx, rules: &[Rule]) -> Result<(), Error> {
             let saved_live = ctx.live_rule_idxs.clone();
             let saved_inh = ctx.inherited.clone();
 
-            ctx.rel.push(name.as_ref());
+            ctx.rel.push(&name);
             ctx.depth += 1;
 
             let rel_path = ctx.rel.clone();
+            let abs_path = ctx.root.join(&rel_path);
+            let facts = EntryFacts::new(&abs_path, EntryKind::Dir);
+            let mut misses = Vec::new();
+            let verdict = classify_entry_last_wins(
+                ctx,
+                rules,
+                &rel_path,
+                EntryKind::Dir,
+                Some(&facts),
+                &mut misses,
+            );
+            if !verdict.is_ignored() {
+                check_name(ctx, &rel_path);
+                check_limits(ctx, &rel_path);
+                check_deny_rules(ctx, rules, &rel_path, EntryKind::Dir, &facts);
+            }
 
-            match classify_entry_last_wins(ctx, rules, &rel_path, EntryKind::Dir) {
-                Verdict::Allow { rule_idx: _ } => ctx
-                    .walk_output
-                    .allow_with_ancestors(rel_path.clone(), false),
-                Verdict::Unaccounted => ctx.walk_output.mark_unaccounted_dir(rel_path),
-                Verdict::Ignore { rule_idx } => {
-                    ctx.walk_output.mark_ignored_dir(rel_path.clone());
-                    // we just ignored a directory. set the inherited context flag.
-                    ctx.inherited = InheritedState::SubtreeIgnored { rule_idx };
-                }
-                Verdict::IgnoredByInheritance { rule_idx: _ } => {
-                    ctx.walk_output.mark_ignored_dir(rel_path);
-                }
+            // we just ignored a directory. set the inherited context flag.
+            if let Verdict::Ignore { rule_idx, .. } = verdict {
+                ctx.inherited = InheritedState::SubtreeIgnored { rule_idx };
             }
 
             // Recurse
-            walk_dir(ctx, rules)?;
+            let allowed_below = walk_dir(ctx, rules, verdict.is_ignored(), visitor)?;
+
+            // Only now do we know the directory's status: anything allowed below it makes it
+            // allowed too, whatever decided it.
+            let decided = match verdict {
+                Verdict::Allow { .. } => verdict.decide(rules),
+                _ if allowed_below => (Status::Allowed, None),
+                _ => verdict.decide(rules),
+            };
+            if decided.0 == Status::Unaccounted {
+                report_predicate_misses(ctx, &rel_path, misses);
+            }
+            any_allowed |= decided.0 == Status::Allowed;
+            visit(ctx, visitor, &rel_path, EntryKind::Dir, decided);
 
             // Restore context (so we can continue siblings)
             ctx.rel = saved_rel;
@@ -217,52 +469,267 @@ fn walk_dir(ctx: &mut WalkCtx, rules: &[Rule]) -> Result<(), Error> {
             ctx.live_rule_idxs = saved_live;
             ctx.inherited = saved_inh;
         } else if ty.is_file() {
-            let rel_path = ctx.rel.join(name.as_ref());
-
-            match classify_entry_last_wins(ctx, rules, &rel_path, EntryKind::File) {
-                Verdict::Allow { rule_idx: _ } => {
-                    ctx.walk_output.allow_with_ancestors(rel_path.clone(), true)
-                }
-                Verdict::Unaccounted => ctx.walk_output.mark_unaccounted_file(rel_path),
-                Verdict::Ignore { rule_idx: _ } => {
-                    ctx.walk_output.mark_ignored_file(rel_path.clone());
-                }
-                Verdict::IgnoredByInheritance { rule_idx: _ } => {
-                    ctx.walk_output.mark_ignored_file(rel_path);
+            let rel_path = ctx.rel.join(&name);
+
+            let abs_path = ctx.root.join(&rel_path);
+            let facts = EntryFacts::new(&abs_path, EntryKind::File);
+            let mut misses = Vec::new();
+            let verdict = classify_entry_last_wins(
+                ctx,
+                rules,
+                &rel_path,
+                EntryKind::File,
+                Some(&facts),
+                &mut misses,
+            );
+            if !verdict.is_ignored() {
+                check_name(ctx, &rel_path);
+                check_limits(ctx, &rel_path);
+                check_deny_rules(ctx, rules, &rel_path, EntryKind::File, &facts);
+            }
+            if matches!(verdict, Verdict::Unaccounted) {
+                report_predicate_misses(ctx, &rel_path, misses);
+            }
+            let allowed = matches!(verdict, Verdict::Allow { .. });
+            for m in &mut ctx.manifests {
+                if let Some(d) = m.check_file(&ctx.root, &rel_path, allowed) {
+                    ctx.diagnostics.push(d);
                 }
             }
+
+            any_allowed |= allowed;
+            visit(
+                ctx,
+                visitor,
+                &rel_path,
+                EntryKind::File,
+                verdict.decide(rules),
+            );
         } else {
             // symlink / fifo / socket / etc.
             // For now, silently skip (could be reported in future)
         }
     }
 
-    Ok(())
-}
-
-#[derive(Debug, Clone, Copy)]
-enum EntryKind {
-    File,
-    Dir,
+    Ok(any_allowed)
 }
 
-#[derive(Debug, Clone, Copy)]
-#[allow(dead_code)] // rule_idx fields reserved for future diagnostics
+#[derive(Debug, Clone)]
 enum Verdict {
-    // rule_idx reserved for future diagnostics
-    Allow { rule_idx: usize },
-    Ignore { rule_idx: usize },
-    // rule_idx reserved for future diagnostics
+    Allow { rule_idx: usize, captures: Captures },
+    Ignore { rule_idx: usize, captures: Captures },
     IgnoredByInheritance { rule_idx: usize },
     Unaccounted,
 }
 
+impl Verdict {
+    fn is_ignored(&self) -> bool {
+        matches!(
+            self,
+            Verdict::Ignore { .. } | Verdict::IgnoredByInheritance { .. }
+        )
+    }
+}
+
+impl Verdict {
+    /// The status this verdict gives a path, and the rule that decided it.
+    fn decide(self, rules: &[Rule]) -> (Status, Option<RuleMatch>) {
+        match self {
+            Verdict::Allow { rule_idx, captures } => {
+                (Status::Allowed, Some(rule_match(rules, rule_idx, captures)))
+            }
+            Verdict::Ignore { rule_idx, captures } => {
+                (Status::Ignored, Some(rule_match(rules, rule_idx, captures)))
+            }
+            Verdict::IgnoredByInheritance { rule_idx } => (
+                Status::Ignored,
+                Some(rule_match(rules, rule_idx, Captures::new())),
+            ),
+            Verdict::Unaccounted => (Status::Unaccounted, None),
+        }
+    }
+}
+
+fn rule_match(rules: &[Rule], rule_idx: usize, captures: Captures) -> RuleMatch {
+    RuleMatch {
+        rule_line: rules[rule_idx].line,
+        captures,
+        source: rules[rule_idx].source.clone(),
+    }
+}
+
+/// Raise diagnostics about the last name in `rel`: not valid UTF-8 (if enabled), or not in the
+/// configured normalization form. Not called for ignored entries.
+fn check_name(ctx: &mut WalkCtx, rel: &Path) {
+    let name = rel.file_name().unwrap_or_default();
+    let Some(text) = name.to_str() else {
+        if ctx.settings.report_non_utf8_names {
+            let path = escape_path(rel);
+            ctx.diagnostics.push(Diagnostic {
+                code: "non_utf8_name",
+                severity: Severity::Warning,
+                message: format!("name is not valid UTF-8: {}", path),
+                path,
+                rule_lines: Vec::new(),
+            });
+        }
+        return;
+    };
+
+    let form = ctx.settings.normalization;
+    if form.apply(text) == text {
+        return;
+    }
+    let path = escape_path(rel);
+    ctx.diagnostics.push(Diagnostic {
+        code: "unnormalized_name",
+        severity: Severity::Warning,
+        message: format!("name is not in {} form: {}", form.as_str(), path),
+        path,
+        rule_lines: Vec::new(),
+    });
+}
+
+/// Raise diagnostics for an entry that exceeds the spec's depth, path length or name length
+/// limits. Depth and path length are only reported at the first path that exceeds them,
+/// not again for everything below it.
+fn check_limits(ctx: &mut WalkCtx, rel: &Path) {
+    let Limits {
+        max_depth,
+        max_path_length,
+        max_name_length,
+        ..
+    } = ctx.limits;
+
+    if let Some(limit) = max_depth {
+        let depth = rel.iter().count();
+        if depth == limit.value + 1 {
+            push_limit(
+                ctx,
+                "max_depth_exceeded",
+                rel,
+                format!("depth {} exceeds max-depth {}", depth, limit.value),
+                limit.line,
+            );
+        }
+    }
+
+    if let Some(limit) = max_path_length {
+        let len = path_len(rel);
+        let parent_len = rel.parent().map_or(0, path_len);
+        if len > limit.value && parent_len <= limit.value {
+            push_limit(
+                ctx,
+                "max_path_length_exceeded",
+                rel,
+                format!(
+                    "path length {} exceeds max-path-length {}",
+                    len, limit.value
+                ),
+                limit.line,
+            );
+        }
+    }
+
+    if let (Some(limit), Some(name)) = (max_name_length, rel.file_name()) {
+        let len = name.as_encoded_bytes().len();
+        if len > limit.value {
+            push_limit(
+                ctx,
+                "max_name_length_exceeded",
+                rel,
+                format!(
+                    "name length {} exceeds max-name-length {}",
+                    len, limit.value
+                ),
+                limit.line,
+            );
+        }
+    }
+}
+
+/// Raise a diagnostic if the current directory holds more entries than `max-entries` allows.
+fn check_entry_count(ctx: &mut WalkCtx, count: usize) {
+    let Some(limit) = ctx.limits.max_entries else {
+        return;
+    };
+    if count > limit.value {
+        let rel = ctx.rel.clone();
+        push_limit(
+            ctx,
+            "max_entries_exceeded",
+            &rel,
+            format!(
+                "directory has {} entries, exceeding max-entries {}",
+                count, limit.value
+            ),
+            limit.line,
+        );
+    }
+}
+
+fn push_limit(ctx: &mut WalkCtx, code: &'static str, rel: &Path, message: String, line: usize) {
+    let path = if rel.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        escape_path(rel)
+    };
+    ctx.diagnostics.push(Diagnostic {
+        code,
+        severity: ctx.settings.default_severity,
+        message: format!("{}: {}", message, path),
+        path,
+        rule_lines: vec![line],
+    });
+}
+
+/// Length of a relative path in bytes, with `/` separators.
+fn path_len(rel: &Path) -> usize {
+    let mut parts: usize = 0;
+    let mut len = 0;
+    for c in rel.iter() {
+        parts += 1;
+        len += c.as_encoded_bytes().len();
+    }
+    len + parts.saturating_sub(1)
+}
+
+/// The path rules are matched against: `rel_path` in the configured normalization form.
+/// Components that aren't valid UTF-8 are left as they are.
+fn match_path(form: NormalizationForm, rel_path: &Path) -> Cow<'_, Path> {
+    if form == NormalizationForm::None {
+        return Cow::Borrowed(rel_path);
+    }
+    if let Some(s) = rel_path.to_str() {
+        return match form.apply(s) {
+            Cow::Borrowed(_) => Cow::Borrowed(rel_path),
+            Cow::Owned(s) => Cow::Owned(PathBuf::from(s)),
+        };
+    }
+    Cow::Owned(
+        rel_path
+            .iter()
+            .map(|c| match c.to_str() {
+                Some(s) => OsString::from(form.apply(s).into_owned()),
+                None => c.to_os_string(),
+            })
+            .collect(),
+    )
+}
+
+/// Decide a path by last-match-wins over the live rules, falling back to an inherited ignore.
+/// `facts` is `None` when there is nothing on disk to evaluate `where` clauses against; rules
+/// with one then don't match.
 fn classify_entry_last_wins(
     ctx: &WalkCtx,
     rules: &[Rule],
     rel_path: &Path,
     kind: EntryKind,
+    facts: Option<&EntryFacts>,
+    misses: &mut Vec<PredicateMiss>,
 ) -> Verdict {
+    let rel_path = &*match_path(ctx.settings.normalization, rel_path);
+
     // 1) last rule wins: scan from bottom to top over live rules
     // This must happen BEFORE checking inheritance, so that later rules can
     // override inherited ignore state (e.g., "ignore /bin/" then "allow /bin/allowed.txt")
@@ -274,42 +741,43 @@ fn classify_entry_last_wins(
         // Today: only "allow + anchored + (dir|file)" exists.
         // Tomorrow: add ignore, unanchored, etc, in this same match ladder.
 
-        match (r.kind, kind) {
-            (RuleKind::Allow, EntryKind::Dir) => {
-                if matches_allowed_anchored_dir(r, rel_path) {
-                    return Verdict::Allow { rule_idx };
-                }
-                if matches_allowed_unanchored_dir(r, rel_path) {
-                    return Verdict::Allow { rule_idx };
-                }
-            }
-
-            (RuleKind::Allow, EntryKind::File) => {
-                if matches_allowed_anchored_file(r, rel_path) {
-                    return Verdict::Allow { rule_idx };
-                }
-                if matches_allowed_unanchored_file(r, rel_path) {
-                    return Verdict::Allow { rule_idx };
-                }
-            }
+        let matched = match (r.kind, kind) {
+            (RuleKind::Allow, EntryKind::Dir) => matches_allowed_anchored_dir(r, rel_path)
+                .or_else(|| matches_allowed_unanchored_dir(r, rel_path)),
+            (RuleKind::Allow, EntryKind::File) => matches_allowed_anchored_file(r, rel_path)
+                .or_else(|| matches_allowed_unanchored_file(r, rel_path)),
+            (RuleKind::Ignore, EntryKind::Dir) => matches_ignored_anchored_dir(r, rel_path)
+                .or_else(|| matches_ignored_unanchored_dir(r, rel_path)),
+            (RuleKind::Ignore, EntryKind::File) => matches_ignored_anchored_file(r, rel_path)
+                .or_else(|| matches_ignored_unanchored_file(r, rel_path)),
+            // Deny rules don't take part in last-match-wins, see `check_deny_rules`.
+            (RuleKind::Deny, _) => None,
+        };
+        let Some(captures) = matched else {
+            continue;
+        };
 
-            (RuleKind::Ignore, EntryKind::Dir) => {
-                if matches_ignored_anchored_dir(r, rel_path) {
-                    return Verdict::Ignore { rule_idx };
-                }
-                if matches_ignored_unanchored_dir(r, rel_path) {
-                    return Verdict::Ignore { rule_idx };
-                }
-            }
-            (RuleKind::Ignore, EntryKind::File) => {
-                if matches_ignored_anchored_file(r, rel_path) {
-                    return Verdict::Ignore { rule_idx };
-                }
-                if matches_ignored_unanchored_file(r, rel_path) {
-                    return Verdict::Ignore { rule_idx };
-                }
+        // 3) the rule's `where` clause, if any, must hold too
+        if !r.predicates.is_empty() {
+            let Some(facts) = facts else {
+                continue;
+            };
+            let (holds, details) = eval_all(&r.predicates, facts);
+            if !holds {
+                let magic = Predicate::Magic { negated: false };
+                misses.push(PredicateMiss {
+                    rule_line: r.line,
+                    details,
+                    content_mismatch: r.predicates.contains(&magic) && !magic.eval(facts).0,
+                });
+                continue;
             }
         }
+
+        return match r.kind {
+            RuleKind::Ignore => Verdict::Ignore { rule_idx, captures },
+            _ => Verdict::Allow { rule_idx, captures },
+        };
     }
 
     // 0) inheritance gate: only apply if no explicit rule matched
@@ -322,3 +790,88 @@ fn classify_entry_last_wins(
 
     Verdict::Unaccounted
 }
+
+/// A rule whose pattern matched a path but whose `where` clause didn't hold.
+#[derive(Debug, Clone)]
+struct PredicateMiss {
+    rule_line: usize,
+    details: Vec<String>,
+    /// The rule's `magic` predicate failed: the content doesn't match the extension.
+    content_mismatch: bool,
+}
+
+/// Report an unaccounted path that would have been matched by some rules, if not for
+/// their `where` clauses, with what was observed. A failed `magic` check is reported as a
+/// `content_mismatch` warning rather than the informational `predicate_failed`.
+fn report_predicate_misses(ctx: &mut WalkCtx, rel_path: &Path, misses: Vec<PredicateMiss>) {
+    if misses.is_empty() {
+        return;
+    }
+    let path = escape_path(rel_path);
+    let why = misses
+        .iter()
+        .map(|m| format!("line {}: {}", m.rule_line, m.details.join(", ")))
+        .collect::<Vec<_>>()
+        .join("; ");
+    let (code, severity) = if misses.iter().any(|m| m.content_mismatch) {
+        ("content_mismatch", Severity::Warning)
+    } else {
+        ("predicate_failed", Severity::Info)
+    };
+    ctx.diagnostics.push(Diagnostic {
+        code,
+        severity,
+        message: format!(
+            "matched by name, but the where clause failed ({}): {}",
+            why, path
+        ),
+        path,
+        rule_lines: misses.iter().map(|m| m.rule_line).collect(),
+    });
+}
+
+/// Check a path that isn't ignored against every `deny` rule, reporting each that matches
+/// (name and `where` clause both) as a `denied` error.
+fn check_deny_rules(
+    ctx: &mut WalkCtx,
+    rules: &[Rule],
+    rel_path: &Path,
+    kind: EntryKind,
+    facts: &EntryFacts,
+) {
+    let form = ctx.settings.normalization;
+    let match_rel = &*match_path(form, rel_path);
+    for r in rules.iter().filter(|r| r.kind == RuleKind::Deny) {
+        let matched = match kind {
+            EntryKind::Dir => matches_denied_anchored_dir(r, match_rel)
+                .or_else(|| matches_denied_unanchored_dir(r, match_rel)),
+            EntryKind::File => matches_denied_anchored_file(r, match_rel)
+                .or_else(|| matches_denied_unanchored_file(r, match_rel)),
+        };
+        if matched.is_none() {
+            continue;
+        }
+        let (holds, details) = eval_all(&r.predicates, facts);
+        if !holds {
+            continue;
+        }
+        let path = escape_path(rel_path);
+        let message = if details.is_empty() {
+            format!("denied by rule on line {}: {}", r.line, path)
+        } else {
+            format!(
+                "denied by rule on line {} ({}): {}",
+                r.line,
+                details.join(", "),
+                path
+            )
+        };
+        ctx.diagnostics.push(Diagnostic {
+            code: "denied",
+            severity: Severity::Error,
+            message,
+            path,
+            rule_lines: vec![r.line],
+        });
+    }
+}
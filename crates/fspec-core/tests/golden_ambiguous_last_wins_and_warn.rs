@@ -33,5 +33,7 @@ allow /src/**/file.txt
 
     assert!(report.is_allowed("src/file.txt"));
 
-    // TODO: Check which rule allowed src/file.txt when tags are available.
+    // The later, more specific rule (line 3) decided the path.
+    let entry = report.entry("src/file.txt").unwrap();
+    assert_eq!(entry.rule_line, Some(3));
 }
@@ -0,0 +1,110 @@
+use std::fs;
+use std::path::Path;
+
+use fspec_core::{MatchSettings, check_tree};
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn golden_case_insensitive_placeholder_flag() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        r#"
+allow ./photos/{name:snake_case}.{ext:i:jpg|jpeg}
+"#,
+    );
+
+    write_file(&root.join("photos/beach_day.JPG"), "dummy_file");
+    write_file(&root.join("photos/sunset.Jpeg"), "dummy_file");
+    write_file(&root.join("photos/Sunset.jpg"), "dummy_file");
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    assert!(report.is_allowed("photos/beach_day.JPG"));
+    assert_eq!(
+        report.entry("photos/beach_day.JPG").unwrap().captures["ext"],
+        "JPG"
+    );
+    assert!(report.is_allowed("photos/sunset.Jpeg"));
+    // only the flagged placeholder ignores case
+    assert!(report.is_unaccounted("photos/Sunset.jpg"));
+    // lookups are case-sensitive by default
+    assert_eq!(report.entry("photos/BEACH_DAY.JPG"), None);
+}
+
+#[test]
+fn golden_case_insensitive_setting() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        r#"
+allow ./photos/{name:snake_case}.{jpg|jpeg}
+"#,
+    );
+
+    write_file(&root.join("Photos/beach_day.JPG"), "dummy_file");
+    write_file(&root.join("Photos/Sunset.jpg"), "dummy_file");
+
+    let settings = MatchSettings {
+        case_insensitive: true,
+        ..MatchSettings::default()
+    };
+    let report = check_tree(root, &settings).unwrap();
+
+    // literals and one-of choices ignore case; limiters still check it
+    assert!(report.is_allowed("Photos/beach_day.JPG"));
+    assert!(report.is_unaccounted("Photos/Sunset.jpg"));
+
+    // lookups ignore case, but stored paths keep their on-disk spelling
+    assert!(report.is_allowed("photos/BEACH_DAY.jpg"));
+    let paths: Vec<&str> = report.entries().map(|(p, _)| p).collect();
+    assert!(paths.contains(&"Photos/beach_day.JPG"));
+}
+
+#[test]
+fn golden_case_insensitive_keeps_names_that_differ_only_in_case() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(&root.join(".fspec"), "allow ./notes/*.txt\n");
+    write_file(&root.join("notes/readme.txt"), "dummy_file");
+    write_file(&root.join("notes/README.txt"), "dummy_file");
+    write_file(&root.join("notes/ReadMe.md"), "dummy_file");
+    write_file(&root.join("notes/readme.md"), "dummy_file");
+    if fs::read_dir(root.join("notes")).unwrap().count() < 4 {
+        return; // case-insensitive file system: only one spelling of each exists
+    }
+
+    let settings = MatchSettings {
+        case_insensitive: true,
+        ..MatchSettings::default()
+    };
+    let report = check_tree(root, &settings).unwrap();
+
+    let paths: Vec<&str> = report.entries().map(|(p, _)| p).collect();
+    assert_eq!(
+        paths,
+        [
+            "notes",
+            "notes/README.txt",
+            "notes/ReadMe.md",
+            "notes/readme.md",
+            "notes/readme.txt"
+        ]
+    );
+    assert_eq!(report.unaccounted_paths().len(), 2);
+    // exact spellings find their own entry; others find the smallest
+    assert!(report.is_unaccounted("notes/readme.md"));
+    assert!(report.is_unaccounted("NOTES/README.MD"));
+    assert!(report.is_allowed("Notes/readme.TXT"));
+}
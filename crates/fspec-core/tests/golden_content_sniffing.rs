@@ -0,0 +1,78 @@
+use std::fs;
+use std::path::Path;
+
+use fspec_core::{MatchSettings, Severity, check_tree};
+
+fn write_bytes(path: &Path, contents: &[u8]) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+const JPEG: &[u8] = b"\xFF\xD8\xFF\xE0\0\x10JFIF\0";
+const HTML: &[u8] = b"<!DOCTYPE html>\n<html><body>502 Bad Gateway</body></html>\n";
+
+#[test]
+fn golden_content_sniffing() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_bytes(
+        &root.join(".fspec"),
+        br#"
+allow ./images/{snake_case}.{png|jpg} where magic
+allow ./downloads/*.zip
+deny ./downloads/*.zip where not magic
+"#,
+    );
+
+    write_bytes(&root.join("images/real_png.png"), PNG);
+    write_bytes(&root.join("images/real_jpg.jpg"), JPEG);
+    write_bytes(&root.join("images/jpeg_in_disguise.png"), JPEG);
+    write_bytes(&root.join("images/error_page.png"), HTML);
+    write_bytes(&root.join("downloads/data.zip"), b"PK\x03\x04\x14\0");
+    write_bytes(&root.join("downloads/broken.zip"), HTML);
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    assert!(report.is_allowed("images/real_png.png"));
+    assert!(report.is_allowed("images/real_jpg.jpg"));
+    assert!(report.is_unaccounted("images/jpeg_in_disguise.png"));
+    assert!(report.is_unaccounted("images/error_page.png"));
+
+    let find = |path: &str| {
+        report
+            .diagnostics()
+            .iter()
+            .find(|d| d.path == path)
+            .unwrap_or_else(|| panic!("no diagnostic for {path}"))
+    };
+
+    let d = find("images/jpeg_in_disguise.png");
+    assert_eq!(d.code, "content_mismatch");
+    assert_eq!(d.severity, Severity::Warning);
+    assert_eq!(d.rule_lines, vec![2]);
+    assert!(
+        d.message.contains("content is JPEG, not PNG"),
+        "{}",
+        d.message
+    );
+    assert!(
+        find("images/error_page.png")
+            .message
+            .contains("content is HTML, not PNG")
+    );
+
+    let d = find("downloads/broken.zip");
+    assert_eq!(d.code, "denied");
+    assert_eq!(d.rule_lines, vec![4]);
+    assert!(
+        d.message.contains("content is HTML, not ZIP"),
+        "{}",
+        d.message
+    );
+
+    assert_eq!(report.diagnostics().len(), 3);
+}
@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::Path;
+
+use fspec_core::{MatchSettings, check_tree};
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn golden_date_limiters() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        r#"
+allow ./shoots/{day:date("%Y-%m-%d")}_shoot/IMG_{day:date("%Y%m%d")}_{time:date("%H%M%S")}.jpg
+allow ./logs/{stamp:iso8601}.log
+"#,
+    );
+
+    // file date equals parent folder date, written in a different format
+    write_file(
+        &root.join("shoots/2024-03-17_shoot/IMG_20240317_142233.jpg"),
+        "dummy_file",
+    );
+    // file date differs from the folder date
+    write_file(
+        &root.join("shoots/2024-03-17_shoot/IMG_20240318_142233.jpg"),
+        "dummy_file",
+    );
+    // not a time of day
+    write_file(
+        &root.join("shoots/2024-03-17_shoot/IMG_20240317_256000.jpg"),
+        "dummy_file",
+    );
+    // no Feb 30, no month 13
+    write_file(
+        &root.join("shoots/2024-02-30_shoot/IMG_20240230_120000.jpg"),
+        "dummy_file",
+    );
+    write_file(
+        &root.join("shoots/2024-13-01_shoot/IMG_20241301_120000.jpg"),
+        "dummy_file",
+    );
+
+    write_file(&root.join("logs/2024-03-17.log"), "dummy_file");
+    write_file(&root.join("logs/20240317T142233Z.log"), "dummy_file");
+    write_file(&root.join("logs/2024-02-30.log"), "dummy_file");
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    let img = "shoots/2024-03-17_shoot/IMG_20240317_142233.jpg";
+    assert!(report.is_allowed(img));
+    let entry = report.entry(img).unwrap();
+    assert_eq!(entry.captures["day"], "2024-03-17");
+    assert_eq!(entry.captures["time"], "14:22:33");
+
+    assert!(report.is_unaccounted("shoots/2024-03-17_shoot/IMG_20240318_142233.jpg"));
+    assert!(report.is_unaccounted("shoots/2024-03-17_shoot/IMG_20240317_256000.jpg"));
+    assert!(report.is_unaccounted("shoots/2024-02-30_shoot/IMG_20240230_120000.jpg"));
+    assert!(report.is_unaccounted("shoots/2024-13-01_shoot/IMG_20241301_120000.jpg"));
+
+    assert!(report.is_allowed("logs/2024-03-17.log"));
+    assert_eq!(
+        report.entry("logs/20240317T142233Z.log").unwrap().captures["stamp"],
+        "2024-03-17T14:22:33Z"
+    );
+    assert!(report.is_unaccounted("logs/2024-02-30.log"));
+}
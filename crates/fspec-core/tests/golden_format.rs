@@ -0,0 +1,59 @@
+use std::fs;
+use std::path::Path;
+
+use fspec_core::{MatchSettings, check_tree, format_spec};
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn golden_formatted_spec_checks_the_same() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    let spec = root.join(".fspec");
+
+    write_file(
+        &spec,
+        "  # archive layout\n\
+         /movies/{ year : int(4) }/{ title }_{year}.{ ext : mkv | \"mp4\" }\n\
+         ignore   .git/\n\n\n\
+         deny **/*.mkv where size<1KB\n\
+         /.fspec",
+    );
+    write_file(
+        &root.join("movies/2001/Odyssey_2001.mkv"),
+        "x".repeat(2048).as_str(),
+    );
+    write_file(&root.join("movies/2001/Odyssey_2002.mp4"), "x");
+    write_file(&root.join("movies/1999/tiny_1999.mkv"), "x");
+
+    let settings = MatchSettings::default();
+    let before = check_tree(root, &settings).unwrap();
+
+    let formatted = format_spec(&spec, &settings).unwrap();
+    assert_eq!(
+        formatted,
+        "# archive layout\n\
+         allow ./movies/{year:int(4)}/{title}_{year}.{ext:mkv|mp4}\n\
+         ignore .git/\n\n\
+         deny **/*.mkv where size < 1KB\n\
+         allow ./.fspec\n"
+    );
+    fs::write(&spec, &formatted).unwrap();
+    assert_eq!(format_spec(&spec, &settings).unwrap(), formatted);
+
+    let after = check_tree(root, &settings).unwrap();
+    assert_eq!(before.unaccounted_paths(), after.unaccounted_paths());
+    let codes = |r: &fspec_core::Report| -> Vec<_> {
+        r.diagnostics()
+            .iter()
+            .map(|d| (d.code, d.path.clone()))
+            .collect()
+    };
+    assert_eq!(codes(&before), codes(&after));
+    assert!(!after.unaccounted_paths().is_empty());
+}
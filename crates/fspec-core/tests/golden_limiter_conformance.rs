@@ -0,0 +1,66 @@
+use std::fs;
+use std::path::Path;
+
+use fspec_core::{Conformance, Error, MatchSettings, check_tree};
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn golden_unknown_limiter_rejected_when_strict() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(&root.join(".fspec"), "allow ./{name:snakecase}.txt\n");
+    write_file(&root.join("some_name.txt"), "dummy_file");
+
+    let err = check_tree(root, &MatchSettings::default()).unwrap_err();
+    let msg = err.to_string();
+    assert!(
+        matches!(&err, Error::Spec { errors, .. } if matches!(errors[..], [Error::Parse { .. }])),
+        "{msg}"
+    );
+    assert!(msg.contains("did you mean 'snake_case'?"), "{msg}");
+}
+
+#[test]
+fn golden_unknown_limiter_matches_anything_when_lenient() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        "allow ./{name:future_limiter(3)}.txt\n",
+    );
+    write_file(&root.join("Any Name.txt"), "dummy_file");
+
+    let settings = MatchSettings {
+        conformance: Conformance::Lenient,
+        ..MatchSettings::default()
+    };
+    let report = check_tree(root, &settings).unwrap();
+
+    assert!(report.is_allowed("./Any Name.txt"));
+    assert!(report.unaccounted_paths().is_empty());
+}
+
+#[test]
+fn golden_bad_limiter_args_rejected_even_when_lenient() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        "allow ./{year:int(0)}.txt\nallow ./{slug:re(\"[a-z\")}.md\n",
+    );
+
+    let settings = MatchSettings {
+        conformance: Conformance::Lenient,
+        ..MatchSettings::default()
+    };
+    assert!(check_tree(root, &settings).is_err());
+}
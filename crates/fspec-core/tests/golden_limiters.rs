@@ -77,3 +77,25 @@ allow ./{alnum}.alnum
     assert!(report.is_allowed("1234567890.numbers"));
     assert!(report.is_allowed("abc123.alnum"));
 }
+
+#[test]
+fn golden_anonymous_placeholders_match_independently() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        "allow ./{:int(4)}_{:int(2)}.txt\nallow ./{y:int(4)}/{y}_{:int(2)}.log\n",
+    );
+    write_file(&root.join("2024_12.txt"), "x");
+    write_file(&root.join("2024/2024_01.log"), "x");
+    write_file(&root.join("2024/2023_01.log"), "x");
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    // Unnamed placeholders capture nothing, so they never have to agree with each other.
+    assert!(report.is_allowed("2024_12.txt"));
+    assert!(report.is_allowed("2024/2024_01.log"));
+    // Named ones still do.
+    assert!(!report.is_allowed("2024/2023_01.log"));
+}
@@ -0,0 +1,44 @@
+use std::fs;
+
+use fspec_core::{Conformance, MatchSettings, Severity, lint_spec};
+
+#[test]
+fn golden_lint_reports_dead_rules_without_walking() {
+    let tmp = tempfile::tempdir().unwrap();
+    let spec = tmp.path().join("media.fspec");
+    fs::write(
+        &spec,
+        "\
+# line 3 makes line 2 pointless
+allow ./incoming/{title}.mkv
+ignore ./incoming/*
+allow ./movies/{year:int(4)}/{title}_{year}.{ext:mkv|mp4}
+allow ./movies/{year:int(4)}/{title}_{year}.{ext:mkv|mp4}
+allow ./{name:kebabcase}.txt
+allow ./cache/**/
+",
+    )
+    .unwrap();
+
+    let settings = MatchSettings {
+        conformance: Conformance::Lenient,
+        ..MatchSettings::default()
+    };
+    let diags = lint_spec(&spec, &settings).unwrap();
+    let found: Vec<_> = diags
+        .iter()
+        .filter(|d| d.severity > Severity::Info)
+        .map(|d| (d.code, d.rule_lines.clone()))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("shadowed_rule", vec![2, 3]),
+            ("duplicate_rule", vec![4, 5]),
+            ("unknown_limiter", vec![6]),
+            ("unmatchable_rule", vec![7]),
+        ]
+    );
+    // Nothing was walked: the spec's directory holds no tree at all.
+    assert!(diags.iter().all(|d| d.path.is_empty()));
+}
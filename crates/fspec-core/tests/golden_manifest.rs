@@ -0,0 +1,97 @@
+use std::fs;
+use std::path::Path;
+
+use fspec_core::{MatchSettings, check_tree};
+use sha2::{Digest, Sha256};
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+fn sha256_hex(s: &str) -> String {
+    Sha256::digest(s.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[test]
+fn golden_manifest() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        r#"
+manifest ./release/SHA256SUMS
+allow ./release/SHA256SUMS
+allow ./release/**/*.csv
+ignore ./release/*.tmp
+"#,
+    );
+
+    write_file(&root.join("release/train.csv"), "a,b\n1,2\n");
+    write_file(&root.join("release/splits/test.csv"), "a,b\n3,4\n");
+    write_file(&root.join("release/valid.csv"), "tampered\n");
+    write_file(&root.join("release/extra.csv"), "not in the manifest\n");
+    write_file(&root.join("release/scratch.tmp"), "ignored and unlisted\n");
+    write_file(
+        &root.join("release/SHA256SUMS"),
+        &format!(
+            "{}  train.csv\n{} *splits/test.csv\n{}  ./valid.csv\n{}  gone.csv\n",
+            sha256_hex("a,b\n1,2\n"),
+            sha256_hex("a,b\n3,4\n"),
+            sha256_hex("original\n"),
+            sha256_hex("whatever\n"),
+        ),
+    );
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    let mut found: Vec<(&str, &str)> = report
+        .diagnostics()
+        .iter()
+        .map(|d| (d.code, d.path.as_str()))
+        .collect();
+    found.sort();
+    assert_eq!(
+        found,
+        vec![
+            ("manifest_hash_mismatch", "release/valid.csv"),
+            ("manifest_missing", "release/gone.csv"),
+            ("manifest_unlisted", "release/extra.csv"),
+        ]
+    );
+    assert!(report.diagnostics().iter().all(|d| d.rule_lines == vec![2]));
+    assert!(!report.is_ok());
+}
+
+#[test]
+fn golden_manifest_unreadable_and_invalid_lines() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        "manifest ./a/SHA256SUMS\nmanifest ./b/SHA256SUMS\nallow ./b/SHA256SUMS\n",
+    );
+    write_file(&root.join("b/SHA256SUMS"), "this is not a checksum line\n");
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    let codes: Vec<(&str, &str)> = report
+        .diagnostics()
+        .iter()
+        .map(|d| (d.code, d.path.as_str()))
+        .collect();
+    assert_eq!(
+        codes,
+        vec![
+            ("manifest_unreadable", "a/SHA256SUMS"),
+            ("manifest_invalid", "b/SHA256SUMS"),
+        ]
+    );
+}
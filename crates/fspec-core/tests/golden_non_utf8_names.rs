@@ -0,0 +1,134 @@
+// Names that aren't valid UTF-8 can only be created on some filesystems (macOS and
+// Windows reject them), so this golden runs on Linux only.
+#![cfg(target_os = "linux")]
+
+use std::ffi::OsStr;
+use std::fs;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+
+use fspec_core::{MatchSettings, check_tree};
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn golden_non_utf8_names() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        r#"
+allow ./imports/*.txt
+allow ./imports/{name}.doc
+allow ./archive/{name:snake_case}.txt
+"#,
+    );
+
+    // Latin-1 "café.txt" and "cafè.txt": lossy conversion would turn both into "caf\u{FFFD}.txt"
+    let imports = root.join("imports");
+    write_file(
+        &imports.join(OsStr::from_bytes(b"caf\xe9.txt")),
+        "dummy_file",
+    );
+    write_file(
+        &imports.join(OsStr::from_bytes(b"caf\xe8.txt")),
+        "dummy_file",
+    );
+    write_file(
+        &imports.join(OsStr::from_bytes(b"r\xe9sum\xe9.doc")),
+        "dummy_file",
+    );
+    // inside a non-UTF-8 directory
+    write_file(
+        &root.join(OsStr::from_bytes(b"archive/\xc0\xc1/notes.txt")),
+        "dummy_file",
+    );
+    // limiters only accept valid UTF-8
+    write_file(
+        &root.join(OsStr::from_bytes(b"archive/n\xf6tes.txt")),
+        "dummy_file",
+    );
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    // Distinct names stay distinct, escaped as \xNN, and match byte-wise.
+    assert!(report.is_allowed(r"imports/caf\xE9.txt"));
+    assert!(report.is_allowed(r"imports/caf\xE8.txt"));
+    assert!(report.is_allowed(r"imports/r\xE9sum\xE9.doc"));
+    assert_eq!(
+        report.entry(r"imports/r\xE9sum\xE9.doc").unwrap().captures["name"],
+        r"r\xE9sum\xE9"
+    );
+    assert!(report.is_unaccounted(r"archive/\xC0\xC1"));
+    assert!(report.is_unaccounted(r"archive/n\xF6tes.txt"));
+    assert!(report.diagnostics().is_empty());
+
+    // Opt-in findings for invalid names.
+    let settings = MatchSettings {
+        report_non_utf8_names: true,
+        ..MatchSettings::default()
+    };
+    let report = check_tree(root, &settings).unwrap();
+    let mut flagged: Vec<&str> = report
+        .diagnostics()
+        .iter()
+        .filter(|d| d.code == "non_utf8_name")
+        .map(|d| d.path.as_str())
+        .collect();
+    flagged.sort();
+    assert_eq!(
+        flagged,
+        vec![
+            r"archive/\xC0\xC1",
+            r"archive/n\xF6tes.txt",
+            r"imports/caf\xE8.txt",
+            r"imports/caf\xE9.txt",
+            r"imports/r\xE9sum\xE9.doc",
+        ]
+    );
+}
+
+#[test]
+fn golden_non_utf8_names_not_reported_when_ignored() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(&root.join(".fspec"), "ignore ./ig/\nallow ./ok/*\n");
+    write_file(&root.join(OsStr::from_bytes(b"ig/b\xffad")), "dummy_file");
+    write_file(&root.join(OsStr::from_bytes(b"ok/b\xffad")), "dummy_file");
+
+    let settings = MatchSettings {
+        report_non_utf8_names: true,
+        ..MatchSettings::default()
+    };
+    let report = check_tree(root, &settings).unwrap();
+
+    assert!(report.is_ignored(r"ig/b\xFFad"));
+    let flagged: Vec<&str> = report
+        .diagnostics()
+        .iter()
+        .map(|d| d.path.as_str())
+        .collect();
+    assert_eq!(flagged, [r"ok/b\xFFad"]);
+}
+
+#[test]
+fn golden_backslash_names_do_not_collide_with_escapes() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(&root.join(".fspec"), "allow ./keep.txt\n");
+    // a file really named `caf\xE9` next to Latin-1 "café"
+    write_file(&root.join(r"caf\xE9"), "dummy_file");
+    write_file(&root.join(OsStr::from_bytes(b"caf\xe9")), "dummy_file");
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    assert_eq!(report.unaccounted_paths(), [r"caf\\xE9", r"caf\xE9"]);
+}
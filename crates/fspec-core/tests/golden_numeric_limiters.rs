@@ -0,0 +1,62 @@
+use std::fs;
+use std::path::Path;
+
+use fspec_core::{MatchSettings, check_tree};
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn golden_numeric_limiters() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        r#"
+allow ./series/{name:pascal_case}/season_{season:int(2+)}/{name}.s{season}e{episode:int(2..3)}.mkv
+allow ./movies/{year:range(1900,2100)}/*.mp4
+"#,
+    );
+
+    // int(2+): at least two digits
+    write_file(
+        &root.join("series/Foo/season_01/Foo.s01e02.mkv"),
+        "dummy_file",
+    );
+    write_file(
+        &root.join("series/Foo/season_100/Foo.s100e100.mkv"),
+        "dummy_file",
+    );
+    write_file(
+        &root.join("series/Foo/season_1/Foo.s1e02.mkv"),
+        "dummy_file",
+    );
+    // int(2..3): two to three digits
+    write_file(
+        &root.join("series/Foo/season_02/Foo.s02e1000.mkv"),
+        "dummy_file",
+    );
+
+    // range(1900,2100): numeric value, not just digit count
+    write_file(&root.join("movies/1999/a.mp4"), "dummy_file");
+    write_file(&root.join("movies/02000/b.mp4"), "dummy_file");
+    write_file(&root.join("movies/1899/c.mp4"), "dummy_file");
+    write_file(&root.join("movies/2101/d.mp4"), "dummy_file");
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    assert!(report.is_allowed("series/Foo/season_01/Foo.s01e02.mkv"));
+    assert!(report.is_allowed("series/Foo/season_100/Foo.s100e100.mkv"));
+    assert!(report.is_unaccounted("series/Foo/season_1/Foo.s1e02.mkv"));
+    assert!(report.is_unaccounted("series/Foo/season_02/Foo.s02e1000.mkv"));
+
+    assert!(report.is_allowed("movies/1999/a.mp4"));
+    assert!(report.is_allowed("movies/02000/b.mp4"));
+    assert!(report.is_unaccounted("movies/1899/c.mp4"));
+    assert!(report.is_unaccounted("movies/2101/d.mp4"));
+}
@@ -0,0 +1,68 @@
+use std::fs;
+use std::path::Path;
+
+use fspec_core::{EntryKind, MatchSettings, Status, check_tree};
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn golden_report_details() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        r#"
+allow ./movies/{year:int(4)}/{title:snake_case}_{year}.{ext:mp4|mkv}
+ignore ./tmp/
+allow ./{:int(4)}_{:int(2)}.txt
+"#,
+    );
+
+    write_file(&root.join("movies/1999/the_matrix_1999.mkv"), "dummy_file");
+    write_file(&root.join("tmp/scratch.txt"), "dummy_file");
+    write_file(&root.join("2024_03.txt"), "dummy_file");
+    write_file(&root.join("stray.txt"), "dummy_file");
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    // Captures and the deciding rule line are recorded for allowed files.
+    let movie = report.entry("movies/1999/the_matrix_1999.mkv").unwrap();
+    assert_eq!(movie.status, Status::Allowed);
+    assert_eq!(movie.kind, Some(EntryKind::File));
+    assert_eq!(movie.rule_line, Some(2));
+    assert_eq!(movie.captures["year"], "1999");
+    assert_eq!(movie.captures["title"], "the_matrix");
+    assert_eq!(movie.captures["ext"], "mkv");
+
+    // Directories allowed only structurally have no deciding rule.
+    let year_dir = report.entry("movies/1999").unwrap();
+    assert_eq!(year_dir.kind, Some(EntryKind::Dir));
+    assert_eq!(year_dir.rule_line, None);
+
+    // Inherited ignores point at the ignoring rule.
+    assert_eq!(report.entry("tmp").unwrap().rule_line, Some(3));
+    assert_eq!(report.entry("tmp/scratch.txt").unwrap().rule_line, Some(3));
+
+    // Anonymous placeholders are not compared with each other and are not captured.
+    let anon = report.entry("2024_03.txt").unwrap();
+    assert_eq!(anon.status, Status::Allowed);
+    assert!(anon.captures.is_empty());
+
+    let stray = report.entry("stray.txt").unwrap();
+    assert_eq!(stray.status, Status::Unaccounted);
+    assert_eq!(stray.rule_line, None);
+
+    let summary = report.summary();
+    assert_eq!(summary.allowed_files, 2);
+    assert_eq!(summary.allowed_dirs, 2);
+    assert_eq!(summary.ignored_files, 1);
+    assert_eq!(summary.ignored_dirs, 1);
+    assert_eq!(summary.unaccounted_files, 1);
+    assert_eq!(summary.unaccounted_dirs, 0);
+}
@@ -0,0 +1,132 @@
+use std::fs;
+use std::path::Path;
+
+use fspec_core::{MatchSettings, Severity, check_tree};
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn golden_deny_by_size_and_empty_dirs() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        r#"
+allow ./videos/{snake_case}.mp4
+allow ./videos/drafts/
+allow ./videos/archive/
+# truncated transfers
+deny *.mp4 where size < 1KB
+# no empty directories
+deny */ where empty
+"#,
+    );
+
+    write_file(&root.join("videos/complete.mp4"), &"x".repeat(2048));
+    write_file(&root.join("videos/truncated.mp4"), "x");
+    fs::create_dir_all(root.join("videos/drafts")).unwrap();
+    write_file(&root.join("videos/archive/old.txt"), "dummy_file");
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    // deny never changes a path's status
+    assert!(report.is_allowed("videos/truncated.mp4"));
+
+    let denied: Vec<_> = report
+        .diagnostics()
+        .iter()
+        .filter(|d| d.code == "denied")
+        .collect();
+    assert_eq!(denied.len(), 2, "{denied:?}");
+    assert_eq!(denied[0].path, "videos/drafts");
+    assert_eq!(denied[0].rule_lines, vec![8]);
+    assert_eq!(denied[1].path, "videos/truncated.mp4");
+    assert_eq!(denied[1].rule_lines, vec![6]);
+    assert!(
+        denied[1].message.contains("size 1B < 1KB"),
+        "{}",
+        denied[1].message
+    );
+    assert!(denied.iter().all(|d| d.severity == Severity::Error));
+    assert!(!report.is_ok());
+}
+
+#[test]
+fn golden_deny_skips_ignored_paths() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        r#"
+allow ./*.mp4
+ignore ./tmp/
+deny *.mp4 where size < 1KB
+"#,
+    );
+    write_file(&root.join("tmp/partial.mp4"), "x");
+    write_file(&root.join("ok.mp4"), &"x".repeat(1024));
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+    assert!(report.diagnostics().is_empty());
+    assert!(report.is_ok());
+}
+
+#[cfg(unix)]
+#[test]
+fn golden_where_clause_on_allow_and_mode() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        r#"
+allow ./published/*.html where mode == 0644
+allow ./bin/*
+deny ./bin/* where not executable
+"#,
+    );
+
+    let set_mode = |rel: &str, mode: u32| {
+        let p = root.join(rel);
+        write_file(&p, "dummy_file");
+        fs::set_permissions(&p, fs::Permissions::from_mode(mode)).unwrap();
+    };
+    set_mode("published/index.html", 0o644);
+    set_mode("published/draft.html", 0o600);
+    set_mode("bin/tool", 0o755);
+    set_mode("bin/notes", 0o644);
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    assert!(report.is_allowed("published/index.html"));
+    assert!(report.is_unaccounted("published/draft.html"));
+    let miss = report
+        .diagnostics()
+        .iter()
+        .find(|d| d.code == "predicate_failed")
+        .unwrap();
+    assert_eq!(miss.path, "published/draft.html");
+    assert_eq!(miss.rule_lines, vec![2]);
+    assert!(
+        miss.message.contains("mode 0600 not == 0644"),
+        "{}",
+        miss.message
+    );
+
+    let denied: Vec<_> = report
+        .diagnostics()
+        .iter()
+        .filter(|d| d.code == "denied")
+        .map(|d| d.path.as_str())
+        .collect();
+    assert_eq!(denied, vec!["bin/notes"]);
+}
@@ -0,0 +1,95 @@
+use std::fs;
+
+use fspec_core::{EntryKind, Error, MatchSettings, Spec, Status};
+
+const SPEC: &str = "\
+allow ./movies/{year:int(4)}/{title}_{year}.{ext:mkv|mp4}
+ignore ./cache/
+allow ./cache/keep.txt
+allow ./big/*.bin where size > 1MB
+deny *.exe
+";
+
+#[test]
+fn golden_spec_classifies_paths_without_touching_disk() {
+    let spec = Spec::parse(SPEC, &MatchSettings::default()).unwrap();
+
+    let movie = spec.classify("movies/2001/space_2001.mkv", EntryKind::File);
+    assert_eq!(movie.status, Status::Allowed);
+    assert_eq!(movie.rule_line, Some(1));
+    assert_eq!(movie.captures["title"], "space");
+    assert_eq!(movie.captures["ext"], "mkv");
+
+    // year mismatch: the back-reference must agree
+    let mismatch = spec.classify("./movies/2001/space_1999.mkv", EntryKind::File);
+    assert_eq!(mismatch.status, Status::Unaccounted);
+    assert_eq!(mismatch.rule_line, None);
+
+    // ignores are inherited from ancestors, and later rules can re-allow
+    let cached = spec.classify("cache/a/b.tmp", EntryKind::File);
+    assert_eq!(
+        (cached.status, cached.rule_line),
+        (Status::Ignored, Some(2))
+    );
+    let kept = spec.classify("/cache/keep.txt", EntryKind::File);
+    assert_eq!((kept.status, kept.rule_line), (Status::Allowed, Some(3)));
+
+    // `where` clauses need the entry itself, so they never match by name alone
+    assert_eq!(
+        spec.classify("big/a.bin", EntryKind::File).status,
+        Status::Unaccounted
+    );
+
+    assert_eq!(spec.denied_by("tools/setup.exe", EntryKind::File), [5]);
+    assert!(spec.denied_by("tools/setup.sh", EntryKind::File).is_empty());
+}
+
+#[test]
+fn golden_spec_checks_many_roots_from_one_parse() {
+    let spec = Spec::parse(SPEC, &MatchSettings::default()).unwrap();
+
+    let good = tempfile::tempdir().unwrap();
+    fs::create_dir_all(good.path().join("movies/2001")).unwrap();
+    fs::write(good.path().join("movies/2001/space_2001.mp4"), b"").unwrap();
+
+    let bad = tempfile::tempdir().unwrap();
+    fs::write(bad.path().join("notes.txt"), b"").unwrap();
+
+    assert!(spec.check(good.path()).unwrap().is_ok());
+    let report = spec.check(bad.path()).unwrap();
+    assert_eq!(report.unaccounted_paths(), ["notes.txt"]);
+}
+
+#[test]
+fn golden_spec_parse_and_from_file_report_errors() {
+    let errors =
+        Spec::parse("allow ./a\nallow ./{x:int(}\n", &MatchSettings::default()).unwrap_err();
+    assert!(matches!(errors[..], [Error::Parse { line: 2, .. }]));
+
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("bad.fspec");
+    fs::write(&path, "allow ./{x:int(}\n").unwrap();
+    let err = Spec::from_file(&path, &MatchSettings::default()).unwrap_err();
+    assert!(matches!(err, Error::Spec { ref errors, .. } if errors.len() == 1));
+
+    fs::write(&path, "allow ./a.txt\n").unwrap();
+    let spec = Spec::from_file(&path, &MatchSettings::default()).unwrap();
+    assert_eq!(spec.rules().len(), 1);
+}
+
+#[test]
+fn golden_spec_extend_appends_rules_after_the_last_line() {
+    let mut spec = Spec::parse("allow ./**/*.txt\n# end\n", &MatchSettings::default()).unwrap();
+    spec.extend("preset vcs", "ignore .git/\n").unwrap();
+
+    // Not a line of the spec file, so no line is reported for it.
+    let git = spec.classify("sub/.git/config", EntryKind::File);
+    assert_eq!((git.status, git.rule_line), (Status::Ignored, None));
+    assert_eq!(spec.rules().len(), 2);
+    assert_eq!(spec.rules()[0].source, None);
+    assert_eq!(spec.rules()[1].source.as_deref(), Some("preset vcs"));
+    assert_eq!(spec.rules()[1].line, 3);
+
+    let errors = spec.extend("preset x", "ignore ./{x:int(}\n").unwrap_err();
+    assert!(matches!(errors[..], [Error::Parse { line: 4, .. }]));
+}
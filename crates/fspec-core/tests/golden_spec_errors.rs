@@ -0,0 +1,41 @@
+use std::fs;
+use std::path::Path;
+
+use fspec_core::{Error, MatchSettings, Severity, check_tree};
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn golden_every_bad_line_is_reported() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        "allow ./{year:int(}/x\n\
+         allow ./ok.txt\n\
+         max-depth {six\n\
+         allow ./{name:snakecase}.txt\n",
+    );
+
+    let err = check_tree(root, &MatchSettings::default()).unwrap_err();
+    let msg = err.to_string();
+    match &err {
+        Error::Spec { errors, .. } => assert_eq!(errors.len(), 3, "{msg}"),
+        other => panic!("expected spec errors, got {other:?}"),
+    }
+    assert!(msg.ends_with(&format!("3 errors in {}", root.join(".fspec").display())));
+
+    let diags = err.diagnostics();
+    let lines: Vec<_> = diags.iter().map(|d| d.rule_lines.clone()).collect();
+    assert_eq!(lines, [vec![1], vec![3], vec![4]]);
+    assert!(diags.iter().all(|d| d.code == "parse_error"));
+    assert!(diags.iter().all(|d| d.severity == Severity::Error));
+    assert!(diags[2].message.starts_with("column 15: unknown limiter"));
+    assert!(diags[0].path.ends_with(".fspec"));
+}
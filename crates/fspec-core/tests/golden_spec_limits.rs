@@ -0,0 +1,115 @@
+use std::fs;
+use std::path::Path;
+
+use fspec_core::{Error, MatchSettings, Severity, check_tree};
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+#[test]
+fn golden_spec_limits() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+
+    write_file(
+        &root.join(".fspec"),
+        r#"
+max-depth 3
+max-path-length 20
+max-name-length 12
+max-entries 5
+allow ./a/**/*.txt
+allow ./p/**/*.txt
+allow ./many/*
+allow ./long_named_file.txt
+ignore ./vendor/
+"#,
+    );
+
+    // depth 4: reported at a/b/c/d only, not again for e.txt below it
+    write_file(&root.join("a/b/c/d/e.txt"), "dummy_file");
+    // 19-byte name
+    write_file(&root.join("long_named_file.txt"), "dummy_file");
+    // 23-byte path at p/qqqqqqqqqq/rrrrrrrrrr; the file below is too deep but not reported twice for length
+    write_file(&root.join("p/qqqqqqqqqq/rrrrrrrrrr/s.txt"), "dummy_file");
+    // six entries; the root has five (the .fspec itself doesn't count)
+    for i in 1..=6 {
+        write_file(&root.join(format!("many/{i}.txt")), "dummy_file");
+    }
+
+    // ignored paths aren't checked
+    write_file(
+        &root.join("vendor/deep/er/than/allowed_and_long_named.txt"),
+        "dummy_file",
+    );
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    let mut found: Vec<(&str, &str, Vec<usize>)> = report
+        .diagnostics()
+        .iter()
+        .map(|d| (d.code, d.path.as_str(), d.rule_lines.clone()))
+        .collect();
+    found.sort();
+    assert_eq!(
+        found,
+        vec![
+            ("max_depth_exceeded", "a/b/c/d", vec![2]),
+            (
+                "max_depth_exceeded",
+                "p/qqqqqqqqqq/rrrrrrrrrr/s.txt",
+                vec![2]
+            ),
+            ("max_entries_exceeded", "many", vec![5]),
+            ("max_name_length_exceeded", "long_named_file.txt", vec![4]),
+            (
+                "max_path_length_exceeded",
+                "p/qqqqqqqqqq/rrrrrrrrrr",
+                vec![3]
+            ),
+        ]
+    );
+    assert!(
+        report
+            .diagnostics()
+            .iter()
+            .all(|d| d.severity == Severity::Warning)
+    );
+
+    // limits don't change whether paths are allowed
+    assert!(report.is_allowed("a/b/c/d/e.txt"));
+    assert!(report.is_allowed("long_named_file.txt"));
+}
+
+#[test]
+fn golden_limit_without_a_number_is_an_error() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    write_file(&root.join("a/b/c/d/e.txt"), "dummy_file");
+
+    for (directive, want) in [
+        (
+            "max-depth two",
+            "max-depth expects a non-negative integer, found 'two'",
+        ),
+        ("max-depth", "expected a number after max-depth"),
+    ] {
+        // a typo mustn't turn the limit into a rule and let the tree pass
+        write_file(&root.join(".fspec"), &format!("{directive}\nallow **/*\n"));
+        match check_tree(root, &MatchSettings::default()).unwrap_err() {
+            Error::Spec { errors, .. } => {
+                assert_eq!(errors.len(), 1, "{directive}");
+                assert!(
+                    matches!(&errors[0], Error::Parse { line: 1, msg, .. } if msg == want),
+                    "{directive}: {:?}",
+                    errors[0]
+                );
+            }
+            other => panic!("{directive}: expected spec errors, got {other:?}"),
+        }
+    }
+}
@@ -42,6 +42,7 @@ allow unanchored_allowed_dir
     let settings = MatchSettings {
         allow_file_or_dir_leaf: false,
         default_severity: Severity::Warning,
+        ..MatchSettings::default()
     };
 
     let report = check_tree(root, &settings).unwrap();
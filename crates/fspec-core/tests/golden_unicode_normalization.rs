@@ -0,0 +1,115 @@
+use std::fs;
+use std::path::Path;
+
+use fspec_core::{MatchSettings, NormalizationForm, check_tree};
+
+fn write_file(path: &Path, contents: &str) {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).unwrap();
+    }
+    fs::write(path, contents).unwrap();
+}
+
+// "café" typed in the spec (NFC) vs. copied from macOS (NFD: "e" + combining acute).
+const CAFE_NFC: &str = "caf\u{e9}";
+const CAFE_NFD: &str = "cafe\u{301}";
+
+fn setup(root: &Path) {
+    write_file(
+        &root.join(".fspec"),
+        &format!("allow ./menus/{CAFE_NFC}/*.pdf\nallow ./menus/{{snake_case}}.pdf\n"),
+    );
+    write_file(
+        &root.join(format!("menus/{CAFE_NFD}/lunch.pdf")),
+        "dummy_file",
+    );
+    write_file(&root.join("menus/plain_menu.pdf"), "dummy_file");
+}
+
+/// The café directory's name as stored. Some filesystems normalize names themselves,
+/// so the NFD-specific assertions only run when the name was kept byte-exact.
+fn on_disk_name(root: &Path) -> String {
+    fs::read_dir(root.join("menus"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .find(|n| n.starts_with("caf"))
+        .unwrap()
+}
+
+#[test]
+fn golden_unicode_normalization_off_by_default() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+
+    let report = check_tree(root, &MatchSettings::default()).unwrap();
+
+    let on_disk = on_disk_name(root);
+    if on_disk == CAFE_NFD {
+        assert!(report.is_unaccounted(&format!("menus/{CAFE_NFD}/lunch.pdf")));
+    }
+    assert!(report.diagnostics().is_empty());
+}
+
+#[test]
+fn golden_unicode_normalization_nfc() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+
+    let settings = MatchSettings {
+        normalization: NormalizationForm::Nfc,
+        ..MatchSettings::default()
+    };
+    let report = check_tree(root, &settings).unwrap();
+
+    let on_disk = on_disk_name(root);
+
+    // Matches regardless of the form the name is stored in; reported under its on-disk name.
+    assert!(report.is_allowed(&format!("menus/{on_disk}/lunch.pdf")));
+    assert!(report.is_allowed("menus/plain_menu.pdf"));
+
+    if on_disk == CAFE_NFD {
+        let diags = report.diagnostics();
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].code, "unnormalized_name");
+        assert_eq!(diags[0].path, format!("menus/{CAFE_NFD}"));
+    }
+}
+
+#[test]
+fn golden_unicode_normalization_nfd_spec_literals() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+
+    // NFD mode: the NFC literal in the spec is decomposed before matching.
+    let settings = MatchSettings {
+        normalization: NormalizationForm::Nfd,
+        ..MatchSettings::default()
+    };
+    let report = check_tree(root, &settings).unwrap();
+
+    let on_disk = on_disk_name(root);
+    assert!(report.is_allowed(&format!("menus/{on_disk}/lunch.pdf")));
+    if on_disk == CAFE_NFD {
+        assert!(report.diagnostics().is_empty());
+    }
+}
+
+#[test]
+fn golden_unicode_normalization_skips_ignored_paths() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    setup(root);
+    write_file(&root.join(".fspec"), "ignore ./menus/\n");
+
+    let settings = MatchSettings {
+        normalization: NormalizationForm::Nfc,
+        ..MatchSettings::default()
+    };
+    let report = check_tree(root, &settings).unwrap();
+
+    assert!(report.is_ignored(&format!("menus/{}", on_disk_name(root))));
+    assert!(report.diagnostics().is_empty());
+}
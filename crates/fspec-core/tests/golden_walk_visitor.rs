@@ -0,0 +1,99 @@
+use std::fs;
+use std::path::Path;
+
+use fspec_core::{
+    Diagnostic, EntryKind, MatchSettings, Spec, Status, WalkEntry, WalkOutput, WalkVisitor,
+};
+
+/// Records what it's handed, in order, without keeping any per-path state of its own.
+#[derive(Default)]
+struct Recorder {
+    events: Vec<String>,
+}
+
+impl WalkVisitor for Recorder {
+    fn entry(&mut self, e: &WalkEntry<'_>) {
+        let kind = match e.kind {
+            EntryKind::File => "file",
+            EntryKind::Dir => "dir",
+        };
+        let by = e.decided_by.map_or(String::new(), |m| {
+            let caps: Vec<_> = m.captures.iter().map(|(k, v)| format!("{k}={v}")).collect();
+            format!(" line {} {}", m.rule_line, caps.join(","))
+        });
+        self.events.push(format!(
+            "{kind} {} {:?}{}",
+            e.path.display(),
+            e.status,
+            by.trim_end()
+        ));
+    }
+
+    fn diagnostic(&mut self, d: Diagnostic) {
+        self.events.push(format!("diag {} {}", d.code, d.path));
+    }
+}
+
+fn touch(root: &Path, rel: &str) {
+    let p = root.join(rel);
+    fs::create_dir_all(p.parent().unwrap()).unwrap();
+    fs::write(p, b"").unwrap();
+}
+
+const SPEC: &str = "\
+allow ./movies/{year:int(4)}/{title}.mkv
+ignore ./cache/
+allow ./cache/keep.txt
+deny *.exe
+";
+
+#[test]
+fn golden_walk_visitor_streams_entries_in_walk_order() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    touch(root, "movies/2001/space.mkv");
+    touch(root, "cache/junk.tmp");
+    touch(root, "cache/keep.txt");
+    touch(root, "setup.exe");
+
+    let spec = Spec::parse(SPEC, &MatchSettings::default()).unwrap();
+    let mut rec = Recorder::default();
+    spec.walk(root, &mut rec).unwrap();
+
+    // Directories come after their contents, with their final status: `cache` was ignored by
+    // line 2 but holds an allowed file, and `movies` is only allowed structurally.
+    assert_eq!(
+        rec.events,
+        [
+            "file cache/junk.tmp Ignored line 2",
+            "file cache/keep.txt Allowed line 3",
+            "dir cache Allowed",
+            "file movies/2001/space.mkv Allowed line 1 title=space,year=2001",
+            "dir movies/2001 Allowed",
+            "dir movies Allowed",
+            "diag denied setup.exe",
+            "file setup.exe Unaccounted",
+        ]
+    );
+}
+
+#[test]
+fn golden_walk_output_is_a_visitor() {
+    let tmp = tempfile::tempdir().unwrap();
+    let root = tmp.path();
+    touch(root, "cache/junk.tmp");
+    touch(root, "notes.txt");
+
+    let spec = Spec::parse(SPEC, &MatchSettings::default()).unwrap();
+    let mut out = WalkOutput::default();
+    spec.walk(root, &mut out).unwrap();
+
+    assert!(out.ignored_dirs.contains(Path::new("cache")));
+    assert!(out.ignored_files.contains(Path::new("cache/junk.tmp")));
+    assert!(out.unaccounted_files.contains(Path::new("notes.txt")));
+    assert_eq!(out.decided_by[Path::new("cache")].rule_line, 2);
+
+    let report = spec.check(root).unwrap();
+    assert_eq!(report.status_of("cache/junk.tmp"), Some(Status::Ignored));
+    assert_eq!(report.unaccounted_paths(), ["notes.txt"]);
+}
@@ -0,0 +1,365 @@
+// What the server knows about an `.fspec` buffer, independent of the LSP transport.
+//
+// Positions from fspec-core are 1-based lines and 1-based byte columns; LSP positions are
+// 0-based lines and UTF-16 columns. The helpers at the bottom convert between the two.
+
+use fspec_core::{Error, MatchSettings, Report, Severity, Status, lint_source};
+use fspec_placeholder::validate::KNOWN_LIMITERS;
+use fspec_placeholder::{Part, PlaceholderNode, parse_component};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, Diagnostic, DiagnosticSeverity, NumberOrString, Position,
+    Range,
+};
+
+const KEYWORDS: &[(&str, &str)] = &[
+    (
+        "allow",
+        "allow matching paths (and their parent directories)",
+    ),
+    ("ignore", "ignore matching paths and everything below them"),
+    (
+        "deny",
+        "report matching paths as errors, whatever other rules say",
+    ),
+    (
+        "max-depth",
+        "limit how many components below the root entries may be",
+    ),
+    ("max-path-length", "limit relative path length in bytes"),
+    ("max-name-length", "limit name length in bytes"),
+    ("max-entries", "limit how many entries a directory may hold"),
+    ("manifest", "verify files against a SHA256SUMS manifest"),
+];
+
+const PREDICATES: &[(&str, &str)] = &[
+    ("size", "file size, e.g. size < 1KB"),
+    ("mode", "permission bits, e.g. mode == 0644"),
+    ("empty", "empty file or directory"),
+    ("executable", "any execute bit set"),
+    ("magic", "content agrees with the extension"),
+    ("not", "negate the next predicate"),
+    ("and", "combine predicates"),
+];
+
+/// Hover lists at most this many paths.
+const HOVER_PATHS: usize = 20;
+
+/// Parse errors, or lint findings if the buffer parses.
+pub fn diagnostics(text: &str, settings: &MatchSettings) -> Vec<Diagnostic> {
+    let lines: Vec<&str> = text.lines().collect();
+    let line_text = |line: usize| lines.get(line.wrapping_sub(1)).copied().unwrap_or("");
+
+    match lint_source(text, settings) {
+        Err(errors) => errors
+            .iter()
+            .filter_map(|e| match e {
+                Error::Parse {
+                    line,
+                    col,
+                    len,
+                    msg,
+                    ..
+                } => {
+                    let t = line_text(*line);
+                    let start = position(t, *line, col - 1);
+                    let end = position(t, *line, col - 1 + len);
+                    Some(diagnostic(
+                        Range::new(start, end),
+                        DiagnosticSeverity::ERROR,
+                        None,
+                        msg,
+                    ))
+                }
+                _ => None,
+            })
+            .collect(),
+        Ok(findings) => findings
+            .iter()
+            .filter_map(|d| {
+                let line = *d.rule_lines.first()?;
+                let t = line_text(line);
+                let range = Range::new(position(t, line, 0), position(t, line, t.len()));
+                let severity = match d.severity {
+                    Severity::Error => DiagnosticSeverity::ERROR,
+                    Severity::Warning => DiagnosticSeverity::WARNING,
+                    Severity::Info => DiagnosticSeverity::INFORMATION,
+                };
+                Some(diagnostic(range, severity, Some(d.code), &d.message))
+            })
+            .collect(),
+    }
+}
+
+fn diagnostic(
+    range: Range,
+    severity: DiagnosticSeverity,
+    code: Option<&str>,
+    message: &str,
+) -> Diagnostic {
+    Diagnostic {
+        range,
+        severity: Some(severity),
+        code: code.map(|c| NumberOrString::String(c.to_string())),
+        source: Some("fspec".to_string()),
+        message: message.to_string(),
+        ..Diagnostic::default()
+    }
+}
+
+/// Completions at byte column `col` of `line`: limiter names inside a placeholder after `:`,
+/// predicates after `where`, and keywords/directives for the first word of a line.
+pub fn completions(line: &str, col: usize) -> Vec<CompletionItem> {
+    let before = line.get(..col).unwrap_or(line);
+
+    if let Some(open) = open_brace(before)
+        && before[open..].contains(':')
+    {
+        return KNOWN_LIMITERS
+            .iter()
+            .map(|name| item(name, CompletionItemKind::FUNCTION, "limiter"))
+            .collect();
+    }
+    if before.contains(" where ") {
+        return PREDICATES
+            .iter()
+            .map(|(name, doc)| item(name, CompletionItemKind::OPERATOR, doc))
+            .collect();
+    }
+    let word = before.trim_start();
+    if !word.starts_with('#') && !word.contains(char::is_whitespace) {
+        return KEYWORDS
+            .iter()
+            .map(|(name, doc)| item(name, CompletionItemKind::KEYWORD, doc))
+            .collect();
+    }
+    Vec::new()
+}
+
+fn item(label: &str, kind: CompletionItemKind, detail: &str) -> CompletionItem {
+    CompletionItem {
+        label: label.to_string(),
+        kind: Some(kind),
+        detail: Some(detail.to_string()),
+        ..CompletionItem::default()
+    }
+}
+
+/// Start of the `{` that `before` ends inside of, if any.
+fn open_brace(before: &str) -> Option<usize> {
+    let (mut open, mut in_quote) = (None, false);
+    for (i, b) in before.bytes().enumerate() {
+        match b {
+            b'"' => in_quote = !in_quote,
+            b'{' if !in_quote => open = Some(i),
+            b'}' if !in_quote => open = None,
+            _ => {}
+        }
+    }
+    open
+}
+
+/// Markdown describing the paths that rule line `line` decides in the last check, with
+/// what it captured for each.
+pub fn hover(report: &Report, line: usize) -> Option<String> {
+    let decided: Vec<_> = report
+        .entries()
+        .filter(|(_, e)| e.rule_line == Some(line))
+        .collect();
+    if decided.is_empty() {
+        return Some("No paths in the workspace are decided by this rule.".to_string());
+    }
+
+    let mut out = format!("**{} path(s)** decided by this rule:\n\n", decided.len());
+    for (path, e) in decided.iter().take(HOVER_PATHS) {
+        let status = match e.status {
+            Status::Allowed => "",
+            Status::Ignored => " (ignored)",
+            Status::Unaccounted => " (unaccounted)",
+        };
+        out.push_str(&format!("- `{}`{}", path, status));
+        if !e.captures.is_empty() {
+            let caps: Vec<_> = e.captures.iter().map(|(k, v)| format!("{k}={v}")).collect();
+            out.push_str(&format!(" — {}", caps.join(", ")));
+        }
+        out.push('\n');
+    }
+    if decided.len() > HOVER_PATHS {
+        out.push_str(&format!("- … and {} more\n", decided.len() - HOVER_PATHS));
+    }
+    Some(out)
+}
+
+/// A named placeholder in a line: its name, byte range, and whether it has a limiter.
+struct Placeholder<'a> {
+    name: &'a str,
+    start: usize,
+    end: usize,
+    defines: bool,
+}
+
+/// Named placeholders in a rule line, in order.
+fn placeholders(line: &str) -> Vec<Placeholder<'_>> {
+    let mut out = Vec::new();
+    let (mut start, mut in_quote) = (None, false);
+    for (i, b) in line.bytes().enumerate() {
+        match b {
+            b'"' => in_quote = !in_quote,
+            b'{' if !in_quote => start = Some(i),
+            b'}' if !in_quote => {
+                let Some(s) = start.take() else { continue };
+                let Ok(ast) = parse_component(&line[s..=i]) else {
+                    continue;
+                };
+                let Some(Part::Placeholder(ph)) = ast.parts.first() else {
+                    continue;
+                };
+                let (name, defines) = match &ph.node {
+                    PlaceholderNode::Capture(c) => (c.name.as_str(), c.limiter.is_some()),
+                    PlaceholderNode::OneOf(o) => match &o.name {
+                        Some(n) => (n.name.as_str(), true),
+                        None => continue,
+                    },
+                };
+                if !name.is_empty() {
+                    // borrow the name from the line, not the temporary AST
+                    let at = s + line[s..=i].find(name).unwrap_or(0);
+                    out.push(Placeholder {
+                        name: &line[at..at + name.len()],
+                        start: s,
+                        end: i + 1,
+                        defines,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Where the placeholder at byte column `col` of `line` is defined: the first placeholder of
+/// that name in the rule with a limiter or choices (else the first one). `None` if the cursor
+/// isn't on a placeholder, or is already on its definition.
+pub fn placeholder_definition(line: &str, col: usize) -> Option<(usize, usize)> {
+    let all = placeholders(line);
+    let here = all.iter().find(|p| (p.start..p.end).contains(&col))?;
+    let same = || all.iter().filter(|p| p.name == here.name);
+    let def = same().find(|p| p.defines).or_else(|| same().next())?;
+    (def.start != here.start).then_some((def.start, def.end))
+}
+
+/// The path argument of a `manifest` directive line.
+pub fn manifest_path(line: &str) -> Option<&str> {
+    let rest = line.trim_start().strip_prefix("manifest")?;
+    rest.starts_with(char::is_whitespace)
+        .then(|| rest.trim())
+        .filter(|p| !p.is_empty())
+}
+
+/// The LSP position of byte column `byte` (0-based) on 1-based `line`.
+pub fn position(text: &str, line: usize, byte: usize) -> Position {
+    let byte = floor_char_boundary(text, byte);
+    Position::new(
+        line.saturating_sub(1) as u32,
+        text[..byte].encode_utf16().count() as u32,
+    )
+}
+
+/// The byte column of a UTF-16 column in `text`.
+pub fn byte_col(text: &str, utf16: u32) -> usize {
+    let mut units = 0;
+    for (i, c) in text.char_indices() {
+        if units >= utf16 as usize {
+            return i;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+fn floor_char_boundary(text: &str, byte: usize) -> usize {
+    let mut b = byte.min(text.len());
+    while !text.is_char_boundary(b) {
+        b -= 1;
+    }
+    b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fspec_core::{EntryKind, ReportEntry};
+
+    fn labels(items: Vec<CompletionItem>) -> Vec<String> {
+        items.into_iter().map(|i| i.label).collect()
+    }
+
+    #[test]
+    fn parse_errors_and_lint_findings_become_diagnostics() {
+        let settings = MatchSettings::default();
+        let d = diagnostics("allow ./a.txt\nallow ./é/{y:int(}", &settings);
+        assert_eq!(d.len(), 1);
+        assert_eq!(d[0].severity, Some(DiagnosticSeverity::ERROR));
+        // `é` is two bytes but one UTF-16 unit
+        assert_eq!(d[0].range.start, Position::new(1, 17));
+
+        let d = diagnostics("allow ./a.txt\nallow ./*.txt\n", &settings);
+        assert_eq!(d.len(), 1);
+        assert_eq!(
+            d[0].code,
+            Some(NumberOrString::String("shadowed_rule".into()))
+        );
+        assert_eq!(
+            d[0].range,
+            Range::new(Position::new(0, 0), Position::new(0, 13))
+        );
+    }
+
+    #[test]
+    fn completes_by_context() {
+        let line = "allow ./{year:in";
+        assert!(labels(completions(line, line.len())).contains(&"int".to_string()));
+        let line = "deny *.mp4 where s";
+        assert!(labels(completions(line, line.len())).contains(&"size".to_string()));
+        assert!(labels(completions("  al", 4)).contains(&"max-depth".to_string()));
+        assert!(completions("allow ./a", 9).is_empty());
+        assert!(completions("allow ./{x}/\"{y:\"", 17).is_empty());
+    }
+
+    #[test]
+    fn placeholder_references_go_to_the_limited_occurrence() {
+        let line = "allow ./{year}/{title}_{year:int(4)}.mkv";
+        assert_eq!(placeholder_definition(line, 10), Some((23, 36)));
+        assert_eq!(placeholder_definition(line, 25), None);
+        assert_eq!(placeholder_definition(line, 17), None);
+        assert_eq!(placeholder_definition(line, 2), None);
+    }
+
+    #[test]
+    fn hover_lists_decided_paths_with_captures() {
+        let mut report = Report::default();
+        report.set_status("movies/2001", Status::Allowed);
+        let entry = |rule_line| ReportEntry {
+            status: Status::Allowed,
+            kind: Some(EntryKind::File),
+            rule_line: Some(rule_line),
+            captures: [("year".to_string(), "2001".to_string())].into(),
+        };
+        report.set_entry("movies/2001/a.mkv", entry(2));
+        report.set_entry("notes.txt", entry(3));
+
+        let text = hover(&report, 2).unwrap();
+        assert!(text.starts_with("**1 path(s)**"), "{text}");
+        assert!(text.contains("- `movies/2001/a.mkv` — year=2001"), "{text}");
+        assert!(hover(&report, 9).unwrap().starts_with("No paths"));
+    }
+
+    #[test]
+    fn converts_between_bytes_and_utf16() {
+        let line = "allow ./日本/x";
+        assert_eq!(position(line, 1, 14).character, 10);
+        assert_eq!(byte_col(line, 10), 14);
+        assert_eq!(manifest_path("manifest  ./SUMS "), Some("./SUMS"));
+        assert_eq!(manifest_path("manifests"), None);
+    }
+}
@@ -0,0 +1,261 @@
+// fspec-lsp: a language server for `.fspec` files, over stdio.
+//
+// Diagnostics come from the parser and linter on every change. Hover, which lists the paths a
+// rule decides, uses a check of the spec's directory that is re-run when the file is opened
+// or saved. Both use the settings `fspec` would in that directory, from its `fspec.toml`.
+
+mod analysis;
+
+use fspec::config;
+use fspec_core::{MatchSettings, Report};
+use lsp_server::{Connection, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics, ShowMessage,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, Request as _};
+use lsp_types::{
+    CompletionOptions, CompletionParams, CompletionResponse, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
+    GotoDefinitionParams, GotoDefinitionResponse, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, Location, MarkupContent, MarkupKind, MessageType, OneOf, Position,
+    PublishDiagnosticsParams, Range, ServerCapabilities, ShowMessageParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Url,
+};
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::Path;
+
+/// An open `.fspec` buffer.
+struct Doc {
+    text: String,
+    /// The last check of the spec's directory, if the file is on disk and the check ran.
+    report: Option<Report>,
+    /// The settings the check used, which diagnostics use too.
+    settings: MatchSettings,
+}
+
+struct Server {
+    connection: Connection,
+    docs: HashMap<Url, Doc>,
+}
+
+fn main() -> Result<(), Box<dyn Error + Sync + Send>> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![":".to_string(), "{".to_string()]),
+            ..CompletionOptions::default()
+        }),
+        definition_provider: Some(OneOf::Left(true)),
+        ..ServerCapabilities::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    let mut server = Server {
+        connection,
+        docs: HashMap::new(),
+    };
+    server.run()?;
+
+    let Server { connection, .. } = server;
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+impl Server {
+    fn run(&mut self) -> Result<(), Box<dyn Error + Sync + Send>> {
+        while let Ok(msg) = self.connection.receiver.recv() {
+            match msg {
+                Message::Request(req) => {
+                    if self.connection.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+                    self.request(req)?;
+                }
+                Message::Notification(note) => self.notification(note)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn request(&mut self, req: Request) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let result = match req.method.as_str() {
+            HoverRequest::METHOD => {
+                serde_json::to_value(self.hover(serde_json::from_value(req.params)?))?
+            }
+            Completion::METHOD => {
+                serde_json::to_value(self.completion(serde_json::from_value(req.params)?))?
+            }
+            GotoDefinition::METHOD => {
+                serde_json::to_value(self.definition(serde_json::from_value(req.params)?))?
+            }
+            _ => serde_json::Value::Null,
+        };
+        self.connection
+            .sender
+            .send(Message::Response(Response::new_ok(req.id, result)))?;
+        Ok(())
+    }
+
+    fn notification(&mut self, note: Notification) -> Result<(), Box<dyn Error + Sync + Send>> {
+        match note.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let p: DidOpenTextDocumentParams = serde_json::from_value(note.params)?;
+                let uri = p.text_document.uri;
+                let (settings, report) = self.check(&uri)?;
+                self.docs.insert(
+                    uri.clone(),
+                    Doc {
+                        text: p.text_document.text,
+                        report,
+                        settings,
+                    },
+                );
+                self.publish(&uri)?;
+            }
+            DidChangeTextDocument::METHOD => {
+                let mut p: DidChangeTextDocumentParams = serde_json::from_value(note.params)?;
+                // full sync: the last change holds the whole buffer
+                if let (Some(change), Some(doc)) = (
+                    p.content_changes.pop(),
+                    self.docs.get_mut(&p.text_document.uri),
+                ) {
+                    doc.text = change.text;
+                }
+                self.publish(&p.text_document.uri)?;
+            }
+            DidSaveTextDocument::METHOD => {
+                let p: DidSaveTextDocumentParams = serde_json::from_value(note.params)?;
+                let (settings, report) = self.check(&p.text_document.uri)?;
+                if let Some(doc) = self.docs.get_mut(&p.text_document.uri) {
+                    doc.report = report;
+                    doc.settings = settings;
+                }
+                // the config may have changed with it
+                self.publish(&p.text_document.uri)?;
+            }
+            DidCloseTextDocument::METHOD => {
+                let p: DidCloseTextDocumentParams = serde_json::from_value(note.params)?;
+                self.docs.remove(&p.text_document.uri);
+                self.send_diagnostics(p.text_document.uri, Vec::new())?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Check the directory holding the spec file, for hover, with what `fspec` run there
+    /// without flags would use: its `fspec.toml` settings and ignore presets. A config that
+    /// can't be read is shown to the user, and the defaults are used without a check.
+    fn check(
+        &self,
+        uri: &Url,
+    ) -> Result<(MatchSettings, Option<Report>), Box<dyn Error + Sync + Send>> {
+        let Some(spec) = uri.to_file_path().ok() else {
+            return Ok((MatchSettings::default(), None));
+        };
+        let root = spec.parent().unwrap_or(Path::new(""));
+        let resolved = match config::resolve_without_flags(root) {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                let params = ShowMessageParams {
+                    typ: MessageType::ERROR,
+                    message: e,
+                };
+                self.connection
+                    .sender
+                    .send(Message::Notification(Notification::new(
+                        ShowMessage::METHOD.to_string(),
+                        params,
+                    )))?;
+                return Ok((MatchSettings::default(), None));
+            }
+        };
+        let report = config::load_spec(&resolved, &spec)
+            .and_then(|s| s.check(root))
+            .ok();
+        Ok((resolved.settings, report))
+    }
+
+    fn publish(&self, uri: &Url) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let Some(doc) = self.docs.get(uri) else {
+            return Ok(());
+        };
+        let diagnostics = analysis::diagnostics(&doc.text, &doc.settings);
+        self.send_diagnostics(uri.clone(), diagnostics)
+    }
+
+    fn send_diagnostics(
+        &self,
+        uri: Url,
+        diagnostics: Vec<lsp_types::Diagnostic>,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+        self.connection
+            .sender
+            .send(Message::Notification(Notification::new(
+                PublishDiagnostics::METHOD.to_string(),
+                params,
+            )))?;
+        Ok(())
+    }
+
+    /// The line at `pos` and the byte column the position falls on.
+    fn line_at(&self, uri: &Url, pos: Position) -> Option<(&str, usize)> {
+        let line = self.docs.get(uri)?.text.lines().nth(pos.line as usize)?;
+        Some((line, analysis::byte_col(line, pos.character)))
+    }
+
+    fn hover(&self, p: HoverParams) -> Option<Hover> {
+        let doc_pos = p.text_document_position_params;
+        let doc = self.docs.get(&doc_pos.text_document.uri)?;
+        let line = doc.text.lines().nth(doc_pos.position.line as usize)?;
+        let rule = line.trim_start();
+        if rule.is_empty() || rule.starts_with('#') {
+            return None;
+        }
+        let value = analysis::hover(doc.report.as_ref()?, doc_pos.position.line as usize + 1)?;
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        })
+    }
+
+    fn completion(&self, p: CompletionParams) -> Option<CompletionResponse> {
+        let doc_pos = p.text_document_position;
+        let (line, col) = self.line_at(&doc_pos.text_document.uri, doc_pos.position)?;
+        let items = analysis::completions(line, col);
+        (!items.is_empty()).then_some(CompletionResponse::Array(items))
+    }
+
+    fn definition(&self, p: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let doc_pos = p.text_document_position_params;
+        let uri = doc_pos.text_document.uri;
+        let line_no = doc_pos.position.line as usize + 1;
+        let (line, col) = self.line_at(&uri, doc_pos.position)?;
+
+        let location = if let Some(target) = analysis::manifest_path(line) {
+            let spec = uri.to_file_path().ok()?;
+            let path = spec.parent().unwrap_or(Path::new("")).join(target);
+            let start = Position::new(0, 0);
+            Location::new(Url::from_file_path(path).ok()?, Range::new(start, start))
+        } else {
+            let (start, end) = analysis::placeholder_definition(line, col)?;
+            let range = Range::new(
+                analysis::position(line, line_no, start),
+                analysis::position(line, line_no, end),
+            );
+            Location::new(uri.clone(), range)
+        };
+        Some(GotoDefinitionResponse::Scalar(location))
+    }
+}
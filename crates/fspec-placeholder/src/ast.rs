@@ -2,6 +2,8 @@
 
 #![allow(dead_code)]
 
+use crate::datetime::{DateFormat, iso8601_regex_fragment, normalize_iso8601};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ComponentAst {
     pub parts: Vec<Part>,
@@ -23,6 +25,8 @@ pub struct LiteralPart {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PlaceholderPart {
     pub node: PlaceholderNode,
+    /// `i:` flag (`{ext:i:jpg|jpeg}`): match this placeholder ignoring ASCII/Unicode case.
+    pub case_insensitive: bool,
     /// Span of the entire `{ ... }` including braces.
     pub span: Span,
 }
@@ -78,46 +82,50 @@ impl LimiterSpec {
     ///
     /// Returns a regex pattern string (without anchors) that can be embedded in larger regex patterns.
     /// For unknown or invalid limiters, returns `.+` as a fallback (matches non-empty string).
-    ///
-    /// To add a new limiter:
-    /// 1. Add a new match arm below matching the limiter name
-    /// 2. Extract and validate any required arguments from `self.args`
-    /// 3. Return the appropriate regex fragment string
+    /// Use [`crate::lower_limiter`] to reject those instead.
     pub fn to_regex_fragment(&self) -> String {
-        match self.name.as_str() {
-            // ASCII case/style
-            "snake_case" => r"[a-z0-9]+(?:_[a-z0-9]+)*".to_string(),
-            "kebab_case" => r"[a-z0-9]+(?:-[a-z0-9]+)*".to_string(),
-            "pascal_case" => r"[A-Z][a-z0-9]*(?:[A-Z][a-z0-9]*)*".to_string(),
-            "upper_case" => r"[A-Z0-9]+".to_string(),
-            "lower_case" => r"[a-z0-9]+".to_string(),
-
-            // int(n): exactly n digits
-            "int" => {
-                if let Some(LimiterArg::Number { value, .. }) = self.args.first()
-                    && let Ok(n) = value.parse::<usize>()
-                {
-                    return format!(r"[0-9]{{{}}}", n);
-                }
-                // fallback: invalid args -> non-empty
-                ".+".to_string()
-            }
-
-            // re("..."): user regex
-            "re" => {
-                if let Some(LimiterArg::Str { value, .. }) = self.args.first() {
-                    return format!(r"(?:{})", value);
-                }
-                ".+".to_string()
-            }
+        crate::lower_limiter(self, crate::Conformance::Lenient)
+            .map(|lim| lim.to_regex_fragment())
+            .unwrap_or_else(|_| ".+".to_string())
+    }
 
-            // Unicode-ish buckets (regex crate supports \p{..})
-            "letters" => r"\p{L}+".to_string(),
-            "numbers" => r"\p{Nd}+".to_string(),
-            "alnum" => r"(?:\p{L}|\p{Nd})+".to_string(),
+    /// A check on the captured value that can't be expressed as a regex fragment.
+    /// See [`Limiter::value_check`]. Invalid limiters have none.
+    pub fn value_check(&self) -> Option<ValueCheck> {
+        crate::lower_limiter(self, crate::Conformance::Lenient)
+            .ok()
+            .and_then(|lim| lim.value_check())
+    }
+}
+
+/// Validation applied to a captured value after the regex matched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueCheck {
+    /// Decimal value within `min..=max` (leading zeros allowed).
+    IntRange { min: u64, max: u64 },
+    /// Valid calendar date/time in the given format.
+    Date(DateFormat),
+    /// Valid ISO 8601 date or date-time.
+    Iso8601,
+}
 
-            // Unknown limiter: accept non-empty
-            _ => ".+".to_string(),
+impl ValueCheck {
+    pub fn accepts(&self, value: &str) -> bool {
+        self.normalize(value).is_some()
+    }
+
+    /// The value to record as the capture, or `None` if the check rejects `value`.
+    ///
+    /// Date/time checks normalize to ISO 8601 form (`20240317` -> `2024-03-17`) so that
+    /// same-name placeholders using different formats compare equal; others keep `value`.
+    pub fn normalize(&self, value: &str) -> Option<String> {
+        match self {
+            ValueCheck::IntRange { min, max } => value
+                .parse::<u64>()
+                .is_ok_and(|v| (*min..=*max).contains(&v))
+                .then(|| value.to_string()),
+            ValueCheck::Date(format) => format.normalize(value),
+            ValueCheck::Iso8601 => normalize_iso8601(value),
         }
     }
 }
@@ -132,9 +140,24 @@ pub struct CaptureNode {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LimiterArg {
-    Number { value: String, span: Span },
-    Ident { value: String, span: Span },
-    Str { value: String, span: Span },
+    Number {
+        value: String,
+        span: Span,
+    },
+    /// `n+` (`max: None`) or `n..m` (inclusive), e.g. `int(2+)`, `int(2..4)`
+    NumberRange {
+        min: String,
+        max: Option<String>,
+        span: Span,
+    },
+    Ident {
+        value: String,
+        span: Span,
+    },
+    Str {
+        value: String,
+        span: Span,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -165,6 +188,33 @@ pub enum Limiter {
         span: Span,
     },
 
+    // `int(n+)` / `int(n..m)`: n or more (up to m) ASCII digits, where 1 <= n <= m
+    IntDigits {
+        min_digits: u32,
+        max_digits: Option<u32>,
+        digits_span: Span,
+        span: Span,
+    },
+
+    // `range(min, max)`: ASCII digits whose decimal value is in min..=max
+    Range {
+        min: u64,
+        max: u64,
+        span: Span,
+    },
+
+    // `date("%Y-%m-%d")`: digits laid out by a strftime-style format, valid calendar date/time
+    Date {
+        format: DateFormat,
+        format_span: Span,
+        span: Span,
+    },
+
+    // `iso8601`: ISO 8601 date, optionally with a time (basic or extended form)
+    Iso8601 {
+        span: Span,
+    },
+
     // regex escape hatch (dialect is implementation-defined; recommend Rust `regex`)
     Re {
         pattern: String,
@@ -195,6 +245,68 @@ pub enum Limiter {
     },
 }
 
+impl Limiter {
+    /// Regex fragment (without anchors) matching values allowed by this limiter.
+    ///
+    /// To add a new limiter:
+    /// 1. Add a variant above and lower it in `validate::lower_limiter` (name, arity, argument checks)
+    /// 2. Return the appropriate regex fragment string here
+    /// 3. If the regex can't fully express it, also return a [`ValueCheck`] from `value_check`
+    pub fn to_regex_fragment(&self) -> String {
+        match self {
+            // ASCII case/style
+            Limiter::SnakeCase { .. } => r"[a-z0-9]+(?:_[a-z0-9]+)*".to_string(),
+            Limiter::KebabCase { .. } => r"[a-z0-9]+(?:-[a-z0-9]+)*".to_string(),
+            Limiter::PascalCase { .. } => r"[A-Z][a-z0-9]*(?:[A-Z][a-z0-9]*)*".to_string(),
+            Limiter::UpperCase { .. } => r"[A-Z0-9]+".to_string(),
+            Limiter::LowerCase { .. } => r"[a-z0-9]+".to_string(),
+
+            Limiter::Int { digits, .. } => format!(r"[0-9]{{{}}}", digits),
+            Limiter::IntDigits {
+                min_digits,
+                max_digits: None,
+                ..
+            } => format!(r"[0-9]{{{},}}", min_digits),
+            Limiter::IntDigits {
+                min_digits,
+                max_digits: Some(max),
+                ..
+            } => format!(r"[0-9]{{{},{}}}", min_digits, max),
+            // the numeric value is checked by `value_check`
+            Limiter::Range { .. } => r"[0-9]+".to_string(),
+            // the calendar value is checked by `value_check`
+            Limiter::Date { format, .. } => format.to_regex_fragment(),
+            Limiter::Iso8601 { .. } => iso8601_regex_fragment(),
+
+            Limiter::Re { pattern, .. } => format!(r"(?:{})", pattern),
+
+            // Unicode-ish buckets (regex crate supports \p{..})
+            Limiter::Letters { .. } => r"\p{L}+".to_string(),
+            Limiter::Numbers { .. } => r"\p{Nd}+".to_string(),
+            Limiter::Alnum { .. } => r"(?:\p{L}|\p{Nd})+".to_string(),
+
+            // Unknown limiter (lenient conformance): accept non-empty
+            Limiter::Unknown { .. } => ".+".to_string(),
+        }
+    }
+
+    /// A check on the captured value that can't be expressed as a regex fragment.
+    ///
+    /// Matchers apply it to the text captured by [`Self::to_regex_fragment`]; a component
+    /// only matches if every such check accepts its capture.
+    pub fn value_check(&self) -> Option<ValueCheck> {
+        match self {
+            Limiter::Range { min, max, .. } => Some(ValueCheck::IntRange {
+                min: *min,
+                max: *max,
+            }),
+            Limiter::Date { format, .. } => Some(ValueCheck::Date(format.clone())),
+            Limiter::Iso8601 { .. } => Some(ValueCheck::Iso8601),
+            _ => None,
+        }
+    }
+}
+
 /// Byte span in the original component string.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Span {
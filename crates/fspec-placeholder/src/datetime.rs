@@ -0,0 +1,348 @@
+// fspec-placeholder/src/datetime.rs
+
+//! Date/time limiters: `date("<format>")` and `iso8601`.
+//!
+//! Both match a fixed-width digit shape with a regex fragment, then validate the
+//! calendar value of the captured text (no month 13, no Feb 30) and normalize it to
+//! an ISO-8601-style string, so the same date written in different formats compares equal.
+
+/// One piece of a `date("...")` format string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateItem {
+    /// `%Y`: four-digit year
+    Year,
+    /// `%m`: two-digit month, 01-12
+    Month,
+    /// `%d`: two-digit day of month, 01-31 (checked against the month and year)
+    Day,
+    /// `%H`: two-digit hour, 00-23
+    Hour,
+    /// `%M`: two-digit minute, 00-59
+    Minute,
+    /// `%S`: two-digit second, 00-59
+    Second,
+    /// Text that must appear verbatim (`%%` is a literal `%`)
+    Literal(String),
+}
+
+/// A parsed `date("...")` format string, e.g. `%Y-%m-%d` or `%Y%m%d_%H%M%S`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateFormat {
+    pub items: Vec<DateItem>,
+}
+
+impl DateFormat {
+    /// Parse a strftime-style format. Supported directives: `%Y %m %d %H %M %S %%`.
+    /// Each directive may appear at most once, and at least one is required.
+    pub fn parse(format: &str) -> Result<Self, String> {
+        let mut items: Vec<DateItem> = Vec::new();
+        let mut literal = String::new();
+        let mut chars = format.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                literal.push(c);
+                continue;
+            }
+            let item = match chars.next() {
+                Some('%') => {
+                    literal.push('%');
+                    continue;
+                }
+                Some('Y') => DateItem::Year,
+                Some('m') => DateItem::Month,
+                Some('d') => DateItem::Day,
+                Some('H') => DateItem::Hour,
+                Some('M') => DateItem::Minute,
+                Some('S') => DateItem::Second,
+                Some(other) => {
+                    return Err(format!(
+                        "unsupported date directive '%{}' (expected one of %Y %m %d %H %M %S %%)",
+                        other
+                    ));
+                }
+                None => return Err("date format ends with a lone '%'".to_string()),
+            };
+            if items.contains(&item) {
+                return Err(format!("date directive {} appears more than once", item));
+            }
+            if !literal.is_empty() {
+                items.push(DateItem::Literal(std::mem::take(&mut literal)));
+            }
+            items.push(item);
+        }
+        if !literal.is_empty() {
+            items.push(DateItem::Literal(literal));
+        }
+
+        if !items.iter().any(|i| !matches!(i, DateItem::Literal(_))) {
+            return Err("date format has no directives".to_string());
+        }
+        Ok(DateFormat { items })
+    }
+
+    /// Regex fragment (without anchors) matching the digit shape of this format.
+    pub fn to_regex_fragment(&self) -> String {
+        self.items
+            .iter()
+            .map(|item| match item {
+                DateItem::Year => "[0-9]{4}".to_string(),
+                DateItem::Literal(s) => regex::escape(s),
+                _ => "[0-9]{2}".to_string(),
+            })
+            .collect()
+    }
+
+    /// Parse `value` with this format. Returns the normalized form if it is a valid
+    /// calendar date/time, e.g. `20240317` with `%Y%m%d` -> `2024-03-17`.
+    pub fn normalize(&self, value: &str) -> Option<String> {
+        let mut fields = Fields::default();
+        let mut rest = value;
+
+        for item in &self.items {
+            let width = match item {
+                DateItem::Literal(s) => {
+                    rest = rest.strip_prefix(s.as_str())?;
+                    continue;
+                }
+                DateItem::Year => 4,
+                _ => 2,
+            };
+            let (digits, tail) = take_digits(rest, width)?;
+            rest = tail;
+            let slot = match item {
+                DateItem::Year => &mut fields.year,
+                DateItem::Month => &mut fields.month,
+                DateItem::Day => &mut fields.day,
+                DateItem::Hour => &mut fields.hour,
+                DateItem::Minute => &mut fields.minute,
+                DateItem::Second => &mut fields.second,
+                DateItem::Literal(_) => unreachable!(),
+            };
+            *slot = Some(digits);
+        }
+
+        if !rest.is_empty() || !fields.is_valid() {
+            return None;
+        }
+        Some(fields.to_iso())
+    }
+}
+
+impl std::fmt::Display for DateItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateItem::Year => write!(f, "%Y"),
+            DateItem::Month => write!(f, "%m"),
+            DateItem::Day => write!(f, "%d"),
+            DateItem::Hour => write!(f, "%H"),
+            DateItem::Minute => write!(f, "%M"),
+            DateItem::Second => write!(f, "%S"),
+            DateItem::Literal(s) => write!(f, "{}", s.replace('%', "%%")),
+        }
+    }
+}
+
+/// Regex fragment for `iso8601`: a date in extended (`2024-03-17`) or basic (`20240317`)
+/// form, optionally followed by `T` and a time (`14`, `14:22`, `14:22:33`, `142233`) and `Z`.
+pub fn iso8601_regex_fragment() -> String {
+    r"[0-9]{4}-?[0-9]{2}-?[0-9]{2}(?:T[0-9]{2}(?::?[0-9]{2}){0,2}Z?)?".to_string()
+}
+
+/// Validate an `iso8601` value and normalize it to extended form
+/// (`20240317T142233Z` -> `2024-03-17T14:22:33Z`).
+///
+/// The date and the time must each use one form consistently (`2024-0317` is rejected).
+pub fn normalize_iso8601(value: &str) -> Option<String> {
+    let (date, time) = match value.split_once('T') {
+        Some((d, t)) => (d, Some(t)),
+        None => (value, None),
+    };
+
+    let format = if date.len() == 10 {
+        "%Y-%m-%d"
+    } else {
+        "%Y%m%d"
+    };
+    let mut out = DateFormat::parse(format).ok()?.normalize(date)?;
+
+    if let Some(time) = time {
+        let (time, zulu) = match time.strip_suffix('Z') {
+            Some(t) => (t, true),
+            None => (time, false),
+        };
+        let format = match time.len() {
+            2 => "%H",
+            4 => "%H%M",
+            5 => "%H:%M",
+            6 => "%H%M%S",
+            8 => "%H:%M:%S",
+            _ => return None,
+        };
+        out.push('T');
+        out.push_str(&DateFormat::parse(format).ok()?.normalize(time)?);
+        if zulu {
+            out.push('Z');
+        }
+    }
+    Some(out)
+}
+
+#[derive(Debug, Default)]
+struct Fields {
+    year: Option<u32>,
+    month: Option<u32>,
+    day: Option<u32>,
+    hour: Option<u32>,
+    minute: Option<u32>,
+    second: Option<u32>,
+}
+
+impl Fields {
+    fn is_valid(&self) -> bool {
+        let in_range = |v: Option<u32>, lo: u32, hi: u32| v.is_none_or(|v| (lo..=hi).contains(&v));
+        if !in_range(self.month, 1, 12)
+            || !in_range(self.hour, 0, 23)
+            || !in_range(self.minute, 0, 59)
+            || !in_range(self.second, 0, 59)
+        {
+            return false;
+        }
+        // Without a year, Feb 29 is allowed; without a month, any day up to 31.
+        let max_day = match self.month {
+            Some(m) => days_in_month(self.year.unwrap_or(2000), m),
+            None => 31,
+        };
+        in_range(self.day, 1, max_day)
+    }
+
+    /// `YYYY-MM-DD`, `HH:MM:SS`, or both joined with `T`, keeping only the fields present.
+    fn to_iso(&self) -> String {
+        let date: Vec<String> = [(self.year, 4), (self.month, 2), (self.day, 2)]
+            .iter()
+            .filter_map(|(v, w)| v.map(|v| format!("{:0w$}", v, w = *w)))
+            .collect();
+        let time: Vec<String> = [self.hour, self.minute, self.second]
+            .iter()
+            .filter_map(|v| v.map(|v| format!("{:02}", v)))
+            .collect();
+        match (date.is_empty(), time.is_empty()) {
+            (false, false) => format!("{}T{}", date.join("-"), time.join(":")),
+            (false, true) => date.join("-"),
+            _ => time.join(":"),
+        }
+    }
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Split exactly `width` ASCII digits off the front of `s`.
+fn take_digits(s: &str, width: usize) -> Option<(u32, &str)> {
+    let digits = s.get(..width)?;
+    if !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((digits.parse().ok()?, &s[width..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(s: &str) -> DateFormat {
+        DateFormat::parse(s).unwrap()
+    }
+
+    #[test]
+    fn parses_directives_and_literals() {
+        assert_eq!(
+            fmt("%Y-%m-%d").items,
+            vec![
+                DateItem::Year,
+                DateItem::Literal("-".into()),
+                DateItem::Month,
+                DateItem::Literal("-".into()),
+                DateItem::Day,
+            ]
+        );
+        assert_eq!(
+            fmt("%Y%m%d").to_regex_fragment(),
+            "[0-9]{4}[0-9]{2}[0-9]{2}"
+        );
+        assert_eq!(fmt("%H.%M").to_regex_fragment(), r"[0-9]{2}\.[0-9]{2}");
+    }
+
+    #[test]
+    fn rejects_bad_formats() {
+        assert!(DateFormat::parse("%Y-%q").is_err());
+        assert!(DateFormat::parse("%Y%Y").is_err());
+        assert!(DateFormat::parse("%").is_err());
+        assert!(DateFormat::parse("plain").is_err());
+        assert!(DateFormat::parse("100%%").is_err());
+    }
+
+    #[test]
+    fn normalizes_valid_dates() {
+        assert_eq!(
+            fmt("%Y-%m-%d").normalize("2024-03-17").as_deref(),
+            Some("2024-03-17")
+        );
+        assert_eq!(
+            fmt("%Y%m%d").normalize("20240317").as_deref(),
+            Some("2024-03-17")
+        );
+        assert_eq!(
+            fmt("%H%M%S").normalize("142233").as_deref(),
+            Some("14:22:33")
+        );
+        assert_eq!(
+            fmt("%Y%m%d_%H%M%S").normalize("20240317_142233").as_deref(),
+            Some("2024-03-17T14:22:33")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_calendar_values() {
+        let ymd = fmt("%Y%m%d");
+        assert_eq!(ymd.normalize("20241317"), None); // month 13
+        assert_eq!(ymd.normalize("20240230"), None); // Feb 30
+        assert_eq!(ymd.normalize("20230229"), None); // not a leap year
+        assert!(ymd.normalize("20240229").is_some());
+        assert_eq!(ymd.normalize("19000229"), None);
+        assert!(ymd.normalize("20000229").is_some());
+        assert_eq!(ymd.normalize("20240400"), None); // day 0
+        assert_eq!(fmt("%H%M").normalize("2400"), None);
+        assert_eq!(fmt("%M%S").normalize("5960"), None);
+        assert_eq!(fmt("%m-%d").normalize("02-29").as_deref(), Some("02-29"));
+    }
+
+    #[test]
+    fn iso8601_forms() {
+        assert_eq!(
+            normalize_iso8601("2024-03-17").as_deref(),
+            Some("2024-03-17")
+        );
+        assert_eq!(normalize_iso8601("20240317").as_deref(), Some("2024-03-17"));
+        assert_eq!(
+            normalize_iso8601("20240317T142233Z").as_deref(),
+            Some("2024-03-17T14:22:33Z")
+        );
+        assert_eq!(
+            normalize_iso8601("2024-03-17T14:22").as_deref(),
+            Some("2024-03-17T14:22")
+        );
+        assert_eq!(normalize_iso8601("2024-0317"), None);
+        assert_eq!(normalize_iso8601("2024-02-30"), None);
+        assert_eq!(normalize_iso8601("2024-03-17T1422:33"), None);
+        assert_eq!(normalize_iso8601("2024-03-17T25"), None);
+    }
+}
@@ -0,0 +1,171 @@
+// Canonical source form of a component, used by `fspec fmt`.
+//
+// Printing a parsed component and parsing it again gives the same AST (spans aside):
+// whitespace inside braces is dropped, literals are quoted only when they must be, and
+// one-of choices are written bare when they're identifiers.
+
+use std::fmt;
+
+use crate::ast::{
+    CaptureNode, Choice, ComponentAst, LimiterArg, LimiterSpec, OneOfNode, Part, PlaceholderNode,
+    PlaceholderPart,
+};
+
+impl fmt::Display for ComponentAst {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Adjacent literals (`ab"*"`) are one run of text; print them together.
+        let mut literal = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(l) => {
+                    literal.push_str(&l.value);
+                    continue;
+                }
+                Part::Star(_) => {
+                    write_literal(f, &literal)?;
+                    f.write_str("*")?;
+                }
+                Part::Placeholder(ph) => {
+                    write_literal(f, &literal)?;
+                    write!(f, "{}", ph)?;
+                }
+            }
+            literal.clear();
+        }
+        write_literal(f, &literal)
+    }
+}
+
+/// Literal text, quoted if it has characters the tokenizer treats specially, or whitespace
+/// that the rule parser would trim or read as ` where `.
+fn write_literal(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    let needs_quotes = s.contains(['*', '{', '}', '"'])
+        || s.starts_with(char::is_whitespace)
+        || s.ends_with(char::is_whitespace)
+        || s.split(char::is_whitespace)
+            .skip(1)
+            .any(|w| w.starts_with("where"));
+    if needs_quotes {
+        write_quoted(f, s)
+    } else {
+        f.write_str(s)
+    }
+}
+
+fn write_quoted(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"{}\"", s.replace('"', "\"\""))
+}
+
+fn is_ident(s: &str) -> bool {
+    let mut bytes = s.bytes();
+    bytes
+        .next()
+        .is_some_and(|b| b.is_ascii_alphabetic() || b == b'_')
+        && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+impl fmt::Display for PlaceholderPart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flag = if self.case_insensitive { "i:" } else { "" };
+        match &self.node {
+            PlaceholderNode::Capture(CaptureNode { name, limiter, .. }) => match limiter {
+                Some(lim) => write!(f, "{{{}:{}{}}}", name, flag, lim),
+                None => write!(f, "{{{}}}", name),
+            },
+            PlaceholderNode::OneOf(OneOfNode { name, choices, .. }) => {
+                match (name, self.case_insensitive) {
+                    (Some(n), _) => write!(f, "{{{}:{}", n.name, flag)?,
+                    (None, true) => write!(f, "{{:{}", flag)?,
+                    (None, false) => f.write_str("{")?,
+                }
+                for (i, choice) in choices.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str("|")?;
+                    }
+                    match choice {
+                        Choice::Ident { value, .. } => f.write_str(value)?,
+                        Choice::Str { value, .. } if is_ident(value) => f.write_str(value)?,
+                        Choice::Str { value, .. } => write_quoted(f, value)?,
+                    }
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+impl fmt::Display for LimiterSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if self.args.is_empty() {
+            return Ok(());
+        }
+        f.write_str("(")?;
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", arg)?;
+        }
+        f.write_str(")")
+    }
+}
+
+impl fmt::Display for LimiterArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimiterArg::Number { value, .. } | LimiterArg::Ident { value, .. } => {
+                f.write_str(value)
+            }
+            LimiterArg::NumberRange { min, max: None, .. } => write!(f, "{}+", min),
+            LimiterArg::NumberRange {
+                min,
+                max: Some(max),
+                ..
+            } => write!(f, "{}..{}", min, max),
+            LimiterArg::Str { value, .. } => write_quoted(f, value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse_component;
+
+    fn canonical(src: &str) -> String {
+        parse_component(src).unwrap().to_string()
+    }
+
+    #[test]
+    fn prints_canonical_form() {
+        for (src, want) in [
+            ("{ year : int( 4 ) }", "{year:int(4)}"),
+            (
+                "{ n:range(1,12) }-{ t :int( 2 .. 4) }",
+                "{n:range(1, 12)}-{t:int(2..4)}",
+            ),
+            (r#"{ext: "mp4" | mkv|"m 4v"}"#, r#"{ext:mp4|mkv|"m 4v"}"#),
+            ("{ :i: jpg|jpeg }", "{:i:jpg|jpeg}"),
+            ("{a|b}{:int(2+)}", "{a|b}{:int(2+)}"),
+            (r#"{d:date("%Y-%m-%d")}"#, r#"{d:date("%Y-%m-%d")}"#),
+            (r#""plain"*.txt"#, "plain*.txt"),
+            (r#"ab"*"c"#, r#""ab*c""#),
+            (r#""say ""hi"" {x}""#, r#""say ""hi"" {x}""#),
+            (r#""x where y".txt"#, r#""x where y.txt""#),
+        ] {
+            assert_eq!(canonical(src), want, "{src}");
+        }
+    }
+
+    #[test]
+    fn canonical_form_parses_to_the_same_component() {
+        for src in [
+            "{ year : int( 4 ) }_{title}.{ext:i:mkv|mp4}",
+            r#"{n:re("a""b")} - "{x}".txt"#,
+            "{:i:a|b}*{c|d}",
+        ] {
+            let printed = canonical(src);
+            assert_eq!(canonical(&printed), printed, "{src}");
+        }
+    }
+}
@@ -1,7 +1,12 @@
 pub mod ast;
+pub mod datetime;
+mod display;
 pub mod parser;
 mod parser_tests;
 pub mod tokenizer;
+pub mod validate;
 
 pub use ast::*;
+pub use datetime::DateFormat;
 pub use parser::parse_component;
+pub use validate::{Conformance, lower_limiter, validate_component};
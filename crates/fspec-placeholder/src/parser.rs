@@ -14,6 +14,10 @@ pub enum ParseErrorKind {
     EmptyPlaceholder,
     EmptyOneOf,
     EmptyOneOfArm,
+    // Semantic validation (see `validate`)
+    UnknownLimiter,
+    InvalidLimiterArgs,
+    InvalidRegex,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -26,7 +30,7 @@ pub struct ParseError {
 }
 
 impl ParseError {
-    fn new(
+    pub(crate) fn new(
         kind: ParseErrorKind,
         at: usize,
         span: Option<Span>,
@@ -139,6 +143,7 @@ fn parse_placeholder(c: &mut Cursor<'_>) -> Result<PlaceholderPart, ParseError>
     // Anonymous placeholders start with ':' directly after '{'
     if matches!(first.token, Token::Colon) {
         c.bump(); // consume ':'
+        let case_insensitive = take_case_flag(c);
 
         // Look ahead to determine if this is an anonymous one-of or anonymous capture with limiter
         let after_colon = c.peek().ok_or_else(|| {
@@ -281,6 +286,7 @@ fn parse_placeholder(c: &mut Cursor<'_>) -> Result<PlaceholderPart, ParseError>
                     choices,
                     span: full,
                 }),
+                case_insensitive,
                 span: full,
             });
         } else {
@@ -296,6 +302,7 @@ fn parse_placeholder(c: &mut Cursor<'_>) -> Result<PlaceholderPart, ParseError>
                     limiter,
                     span: full,
                 }),
+                case_insensitive,
                 span: full,
             });
         }
@@ -332,6 +339,7 @@ fn parse_placeholder(c: &mut Cursor<'_>) -> Result<PlaceholderPart, ParseError>
         )
     })?;
 
+    let mut case_insensitive = false;
     let node = match &next.token {
         Token::Pipe => {
             // OneOf: choice ('|' choice)+
@@ -434,6 +442,16 @@ fn parse_placeholder(c: &mut Cursor<'_>) -> Result<PlaceholderPart, ParseError>
                 ));
             }
 
+            // Flags between the name and the body: {name:i:...}.
+            // Consume ':' and the flag so the second ':' is next, like {name:...}.
+            if matches!(next.token, Token::Colon)
+                && is_case_flag(c.toks.get(c.i + 1), c.toks.get(c.i + 2))
+            {
+                c.bump();
+                c.bump();
+                case_insensitive = true;
+            }
+
             // Check if this looks like a named one-of: {name:choice|choice}
             // We need to look ahead: after ':' should be IDENT/Quoted, then '|'
             if matches!(next.token, Token::Colon) {
@@ -572,6 +590,7 @@ fn parse_placeholder(c: &mut Cursor<'_>) -> Result<PlaceholderPart, ParseError>
                                 choices,
                                 span: full,
                             }),
+                            case_insensitive,
                             span: full,
                         });
                     }
@@ -613,10 +632,29 @@ fn parse_placeholder(c: &mut Cursor<'_>) -> Result<PlaceholderPart, ParseError>
 
     Ok(PlaceholderPart {
         node,
+        case_insensitive,
         span: ph_span,
     })
 }
 
+/// `i` followed by `:` right after a placeholder's first ':' is the case-insensitivity flag,
+/// as in `{ext:i:jpg|jpeg}` or `{:i:jpg|jpeg}`.
+fn is_case_flag(flag: Option<&SpannedToken>, colon: Option<&SpannedToken>) -> bool {
+    matches!(flag.map(|t| &t.token), Some(Token::Ident(s)) if s == "i")
+        && matches!(colon.map(|t| &t.token), Some(Token::Colon))
+}
+
+/// Consume an `i:` flag if present (cursor just past the placeholder's first ':').
+fn take_case_flag(c: &mut Cursor<'_>) -> bool {
+    if is_case_flag(c.peek(), c.toks.get(c.i + 1)) {
+        c.bump();
+        c.bump();
+        true
+    } else {
+        false
+    }
+}
+
 fn parse_limiter_spec(c: &mut Cursor<'_>) -> Result<LimiterSpec, ParseError> {
     let name_tok = c.peek().ok_or_else(|| {
         ParseError::new(
@@ -725,9 +763,46 @@ fn parse_limiter_arg(c: &mut Cursor<'_>) -> Result<LimiterArg, ParseError> {
     let arg = match &t.token {
         Token::Number(s) => {
             c.bump();
-            LimiterArg::Number {
-                value: s.clone(),
-                span: sp,
+            match c.peek().map(|n| &n.token) {
+                // `n+`: at least n
+                Some(Token::Plus) => {
+                    let plus = c.bump().unwrap();
+                    LimiterArg::NumberRange {
+                        min: s.clone(),
+                        max: None,
+                        span: span_join(sp, span(plus.start, plus.end)),
+                    }
+                }
+                // `n..m`: between n and m (inclusive)
+                Some(Token::DotDot) => {
+                    let dots = c.bump().unwrap();
+                    let max_tok = c.peek().ok_or_else(|| {
+                        ParseError::new(
+                            ParseErrorKind::UnexpectedEof,
+                            dots.end,
+                            Some(span(dots.start, dots.end)),
+                            "expected number after '..'",
+                        )
+                    })?;
+                    let Token::Number(max) = &max_tok.token else {
+                        return Err(ParseError::new(
+                            ParseErrorKind::ExpectedToken("NUMBER"),
+                            max_tok.start,
+                            Some(span(max_tok.start, max_tok.end)),
+                            "expected number after '..'",
+                        ));
+                    };
+                    c.bump();
+                    LimiterArg::NumberRange {
+                        min: s.clone(),
+                        max: Some(max.clone()),
+                        span: span_join(sp, span(max_tok.start, max_tok.end)),
+                    }
+                }
+                _ => LimiterArg::Number {
+                    value: s.clone(),
+                    span: sp,
+                },
             }
         }
         Token::Ident(s) => {
@@ -760,6 +835,7 @@ fn parse_limiter_arg(c: &mut Cursor<'_>) -> Result<LimiterArg, ParseError> {
 fn limiter_arg_span(a: &LimiterArg) -> Span {
     match a {
         LimiterArg::Number { span, .. } => *span,
+        LimiterArg::NumberRange { span, .. } => *span,
         LimiterArg::Ident { span, .. } => *span,
         LimiterArg::Str { span, .. } => *span,
     }
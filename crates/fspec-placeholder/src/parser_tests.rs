@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod tests {
-    use crate::ast::{Choice, LimiterArg, Part, PlaceholderNode};
+    use crate::ast::{Choice, LimiterArg, LimiterSpec, Part, PlaceholderNode, Span, ValueCheck};
     use crate::parser::{ParseErrorKind, parse_component};
 
     #[test]
@@ -1199,4 +1199,97 @@ mod tests {
         assert!(one.name.is_none());
         assert_eq!(one.choices.len(), 5);
     }
+
+    // ===== Tests for numeric range arguments =====
+
+    fn first_limiter(input: &str) -> LimiterSpec {
+        let ast = parse_component(input).unwrap();
+        let p = match &ast.parts[0] {
+            Part::Placeholder(p) => p,
+            _ => panic!("expected placeholder"),
+        };
+        match &p.node {
+            PlaceholderNode::Capture(c) => c.limiter.clone().unwrap(),
+            _ => panic!("expected capture"),
+        }
+    }
+
+    #[test]
+    fn parse_int_min_width_arg() {
+        let lim = first_limiter("{season:int(2+)}");
+        assert_eq!(lim.name, "int");
+        match &lim.args[0] {
+            LimiterArg::NumberRange { min, max, span } => {
+                assert_eq!(min, "2");
+                assert_eq!(*max, None);
+                assert_eq!(*span, Span::new(12, 14));
+            }
+            _ => panic!("expected number range arg"),
+        }
+    }
+
+    #[test]
+    fn parse_int_width_range_arg_with_whitespace() {
+        let lim = first_limiter("{n:int( 2 .. 4 )}");
+        match &lim.args[0] {
+            LimiterArg::NumberRange { min, max, .. } => {
+                assert_eq!(min, "2");
+                assert_eq!(max.as_deref(), Some("4"));
+            }
+            _ => panic!("expected number range arg"),
+        }
+    }
+
+    #[test]
+    fn parse_range_limiter_two_numbers() {
+        let lim = first_limiter("{year:range(1900, 2100)}");
+        assert_eq!(lim.name, "range");
+        assert_eq!(lim.args.len(), 2);
+        assert_eq!(
+            lim.value_check(),
+            Some(ValueCheck::IntRange {
+                min: 1900,
+                max: 2100
+            })
+        );
+    }
+
+    #[test]
+    fn parse_dotdot_without_max_is_rejected() {
+        let err = parse_component("{n:int(2..)}").unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::ExpectedToken("NUMBER"));
+    }
+
+    #[test]
+    fn parse_case_insensitive_flag() {
+        let ast = parse_component("{ext:i:jpg|jpeg}").unwrap();
+        let Part::Placeholder(ph) = &ast.parts[0] else {
+            panic!("expected placeholder");
+        };
+        assert!(ph.case_insensitive);
+        match &ph.node {
+            PlaceholderNode::OneOf(o) => {
+                assert_eq!(o.name.as_ref().unwrap().name, "ext");
+                assert_eq!(o.choices.len(), 2);
+            }
+            _ => panic!("expected named one-of"),
+        }
+
+        for input in ["{:i:jpg|jpeg}", "{name:i:snake_case}", "{:i:int(2)}"] {
+            let ast = parse_component(input).unwrap();
+            let Part::Placeholder(ph) = &ast.parts[0] else {
+                panic!("expected placeholder");
+            };
+            assert!(ph.case_insensitive, "{input}");
+        }
+
+        // `i` alone is still a limiter name / one-of choice, not a flag
+        for input in ["{ext:i}", "{ext:i|j}", "{name:snake_case}"] {
+            let ast = parse_component(input).unwrap();
+            let Part::Placeholder(ph) = &ast.parts[0] else {
+                panic!("expected placeholder");
+            };
+            assert!(!ph.case_insensitive, "{input}");
+        }
+    }
 }
@@ -16,6 +16,8 @@ pub enum Token {
     LParen, // '('
     RParen, // ')'
     Comma,  // ','
+    Plus,   // '+' (inside braces, e.g. `int(2+)`)
+    DotDot, // '..' (inside braces, e.g. `int(2..4)`)
 
     // Value tokens
     /// An unquoted literal run outside braces. May contain spaces.
@@ -86,7 +88,7 @@ enum Mode {
 ///   - otherwise: LiteralRun up to next special char among `* { } "`
 /// - Inside braces:
 ///   - whitespace is skipped (tolerant formatting)
-///   - recognizes: `}` `:` `|` `(` `)` `,`
+///   - recognizes: `}` `:` `|` `(` `)` `,` `+` `..`
 ///   - `"` => QuotedString
 ///   - IDENT => Ident
 ///   - NUMBER => Number
@@ -193,6 +195,7 @@ impl<'a> Tokenizer<'a> {
             b'(' => Some(Token::LParen),
             b')' => Some(Token::RParen),
             b',' => Some(Token::Comma),
+            b'+' => Some(Token::Plus),
             _ => None,
         };
 
@@ -217,6 +220,16 @@ impl<'a> Tokenizer<'a> {
             }));
         }
 
+        // '..' (a lone '.' is still unexpected)
+        if b == b'.' && self.peek_byte_at(self.pos + 1) == Some(b'.') {
+            self.pos += 2;
+            return Ok(Some(SpannedToken {
+                token: Token::DotDot,
+                start,
+                end: self.pos,
+            }));
+        }
+
         // Number?
         if is_ascii_digit(b) {
             let end = self.scan_while(is_ascii_digit);
@@ -893,4 +906,46 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn inside_braces_plus_and_dotdot() {
+        assert_eq!(
+            toks("{n:int(2+)}"),
+            vec![
+                Token::LBrace,
+                Token::Ident("n".into()),
+                Token::Colon,
+                Token::Ident("int".into()),
+                Token::LParen,
+                Token::Number("2".into()),
+                Token::Plus,
+                Token::RParen,
+                Token::RBrace,
+            ]
+        );
+        assert_eq!(
+            toks("{int( 2 .. 4 )}"),
+            vec![
+                Token::LBrace,
+                Token::Ident("int".into()),
+                Token::LParen,
+                Token::Number("2".into()),
+                Token::DotDot,
+                Token::Number("4".into()),
+                Token::RParen,
+                Token::RBrace,
+            ]
+        );
+    }
+
+    #[test]
+    fn single_dot_inside_braces_is_error() {
+        let err = Tokenizer::new("{int(2.4)}").tokenize_all().unwrap_err();
+        assert_eq!(err.kind, TokenizeErrorKind::UnexpectedChar('.'));
+    }
+
+    #[test]
+    fn plus_and_dots_outside_braces_are_literal() {
+        assert_eq!(toks("a+b..c"), vec![Token::LiteralRun("a+b..c".into())]);
+    }
 }
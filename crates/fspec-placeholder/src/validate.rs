@@ -0,0 +1,374 @@
+// fspec-placeholder/src/validate.rs
+
+//! Semantic validation: lowering parsed [`LimiterSpec`]s into typed [`Limiter`]s.
+//!
+//! The parser accepts any `name(args...)` shape; this pass applies the Level-1
+//! rules from the README (known names, arity, argument values, valid regexes).
+
+use crate::ast::*;
+use crate::datetime::DateFormat;
+use crate::parser::{ParseError, ParseErrorKind};
+
+/// How strictly limiters are validated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Conformance {
+    /// Unknown limiter names are errors.
+    #[default]
+    Strict,
+    /// Unknown limiter names lower to [`Limiter::Unknown`] (matches any non-empty value),
+    /// so specs written for a newer conformance level still load.
+    /// Known limiters are validated the same way in both modes.
+    Lenient,
+}
+
+/// Names accepted at Level 1.
+pub const KNOWN_LIMITERS: &[&str] = &[
+    "snake_case",
+    "kebab_case",
+    "pascal_case",
+    "upper_case",
+    "lower_case",
+    "int",
+    "re",
+    "range",
+    "date",
+    "iso8601",
+    "letters",
+    "numbers",
+    "alnum",
+];
+
+/// Validate a limiter and lower it into its typed form.
+pub fn lower_limiter(spec: &LimiterSpec, conformance: Conformance) -> Result<Limiter, ParseError> {
+    let span = spec.span;
+    let no_args = |lim: Limiter| -> Result<Limiter, ParseError> {
+        if let Some(first) = spec.args.first() {
+            return Err(invalid_args(
+                arg_span(first),
+                format!("limiter '{}' takes no arguments", spec.name),
+            ));
+        }
+        Ok(lim)
+    };
+
+    match spec.name.as_str() {
+        "snake_case" => no_args(Limiter::SnakeCase { span }),
+        "kebab_case" => no_args(Limiter::KebabCase { span }),
+        "pascal_case" => no_args(Limiter::PascalCase { span }),
+        "upper_case" => no_args(Limiter::UpperCase { span }),
+        "lower_case" => no_args(Limiter::LowerCase { span }),
+        "letters" => no_args(Limiter::Letters { span }),
+        "numbers" => no_args(Limiter::Numbers { span }),
+        "alnum" => no_args(Limiter::Alnum { span }),
+        "iso8601" => no_args(Limiter::Iso8601 { span }),
+
+        "int" => match spec.args.as_slice() {
+            [LimiterArg::Number { value, span: sp }] => Ok(Limiter::Int {
+                digits: digit_count(value, *sp)?,
+                digits_span: *sp,
+                span,
+            }),
+            [LimiterArg::NumberRange { min, max, span: sp }] => {
+                let min_digits = digit_count(min, *sp)?;
+                let max_digits = match max {
+                    Some(m) => {
+                        let m = digit_count(m, *sp)?;
+                        if m < min_digits {
+                            return Err(invalid_args(
+                                *sp,
+                                format!(
+                                    "int({}..{}): minimum is greater than maximum",
+                                    min_digits, m
+                                ),
+                            ));
+                        }
+                        Some(m)
+                    }
+                    None => None,
+                };
+                Ok(Limiter::IntDigits {
+                    min_digits,
+                    max_digits,
+                    digits_span: *sp,
+                    span,
+                })
+            }
+            _ => Err(invalid_args(
+                span,
+                "int expects exactly one argument: int(n), int(n+) or int(n..m)",
+            )),
+        },
+
+        "range" => match spec.args.as_slice() {
+            [
+                LimiterArg::Number {
+                    value: lo,
+                    span: lo_sp,
+                },
+                LimiterArg::Number {
+                    value: hi,
+                    span: hi_sp,
+                },
+            ] => {
+                let min = parse_u64(lo, *lo_sp)?;
+                let max = parse_u64(hi, *hi_sp)?;
+                if min > max {
+                    return Err(invalid_args(
+                        span,
+                        format!("range({}, {}): minimum is greater than maximum", min, max),
+                    ));
+                }
+                Ok(Limiter::Range { min, max, span })
+            }
+            _ => Err(invalid_args(
+                span,
+                "range expects exactly two numeric arguments: range(min, max)",
+            )),
+        },
+
+        "re" => match spec.args.as_slice() {
+            [LimiterArg::Str { value, span: sp }] => {
+                if let Err(e) = regex::Regex::new(&format!("^(?:{})$", value)) {
+                    return Err(ParseError::new(
+                        ParseErrorKind::InvalidRegex,
+                        sp.start,
+                        Some(*sp),
+                        format!("invalid regex in re(...): {}", e),
+                    ));
+                }
+                Ok(Limiter::Re {
+                    pattern: value.clone(),
+                    pattern_span: *sp,
+                    span,
+                })
+            }
+            _ => Err(invalid_args(
+                span,
+                "re expects exactly one quoted string argument: re(\"...\")",
+            )),
+        },
+
+        "date" => match spec.args.as_slice() {
+            [LimiterArg::Str { value, span: sp }] => Ok(Limiter::Date {
+                format: DateFormat::parse(value).map_err(|msg| invalid_args(*sp, msg))?,
+                format_span: *sp,
+                span,
+            }),
+            _ => Err(invalid_args(
+                span,
+                "date expects exactly one quoted format argument: date(\"%Y-%m-%d\")",
+            )),
+        },
+
+        _ => match conformance {
+            Conformance::Lenient => Ok(Limiter::Unknown {
+                name: spec.name.clone(),
+                name_span: spec.name_span,
+                args: spec.args.clone(),
+                span,
+            }),
+            Conformance::Strict => {
+                let mut msg = format!("unknown limiter '{}'", spec.name);
+                if let Some(s) = suggest(&spec.name) {
+                    msg.push_str(&format!(" (did you mean '{}'?)", s));
+                }
+                Err(ParseError::new(
+                    ParseErrorKind::UnknownLimiter,
+                    spec.name_span.start,
+                    Some(spec.name_span),
+                    msg,
+                ))
+            }
+        },
+    }
+}
+
+/// Validate every limiter in a component.
+pub fn validate_component(ast: &ComponentAst, conformance: Conformance) -> Result<(), ParseError> {
+    for part in &ast.parts {
+        if let Part::Placeholder(PlaceholderPart {
+            node:
+                PlaceholderNode::Capture(CaptureNode {
+                    limiter: Some(lim), ..
+                }),
+            ..
+        }) = part
+        {
+            lower_limiter(lim, conformance)?;
+        }
+    }
+    Ok(())
+}
+
+fn invalid_args(sp: Span, msg: impl Into<String>) -> ParseError {
+    ParseError::new(ParseErrorKind::InvalidLimiterArgs, sp.start, Some(sp), msg)
+}
+
+fn arg_span(a: &LimiterArg) -> Span {
+    match a {
+        LimiterArg::Number { span, .. }
+        | LimiterArg::NumberRange { span, .. }
+        | LimiterArg::Ident { span, .. }
+        | LimiterArg::Str { span, .. } => *span,
+    }
+}
+
+/// A digit count for `int(..)`: must be >= 1.
+fn digit_count(s: &str, sp: Span) -> Result<u32, ParseError> {
+    match s.parse::<u32>() {
+        Ok(0) => Err(invalid_args(sp, "int digit count must be at least 1")),
+        Ok(n) => Ok(n),
+        Err(_) => Err(invalid_args(
+            sp,
+            format!("int digit count '{}' is too large", s),
+        )),
+    }
+}
+
+fn parse_u64(s: &str, sp: Span) -> Result<u64, ParseError> {
+    s.parse::<u64>()
+        .map_err(|_| invalid_args(sp, format!("number '{}' is too large", s)))
+}
+
+/// Closest known limiter name, if it's a plausible typo.
+fn suggest(name: &str) -> Option<&'static str> {
+    KNOWN_LIMITERS
+        .iter()
+        .map(|k| (edit_distance(name, k), *k))
+        .filter(|(d, _)| *d <= 2)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, k)| k)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_component;
+
+    fn lower(input: &str, conformance: Conformance) -> Result<Limiter, ParseError> {
+        let ast = parse_component(input).unwrap();
+        match &ast.parts[0] {
+            Part::Placeholder(PlaceholderPart {
+                node: PlaceholderNode::Capture(c),
+                ..
+            }) => lower_limiter(c.limiter.as_ref().unwrap(), conformance),
+            _ => panic!("expected capture"),
+        }
+    }
+
+    #[test]
+    fn lowers_known_limiters() {
+        assert!(matches!(
+            lower("{n:snake_case}", Conformance::Strict),
+            Ok(Limiter::SnakeCase { .. })
+        ));
+        assert!(matches!(
+            lower("{y:int(4)}", Conformance::Strict),
+            Ok(Limiter::Int { digits: 4, .. })
+        ));
+        assert!(matches!(
+            lower("{y:int(2+)}", Conformance::Strict),
+            Ok(Limiter::IntDigits {
+                min_digits: 2,
+                max_digits: None,
+                ..
+            })
+        ));
+        assert!(matches!(
+            lower("{y:range(1900,2100)}", Conformance::Strict),
+            Ok(Limiter::Range {
+                min: 1900,
+                max: 2100,
+                ..
+            })
+        ));
+        assert!(matches!(
+            lower(r#"{s:re("[a-z]+")}"#, Conformance::Strict),
+            Ok(Limiter::Re { .. })
+        ));
+    }
+
+    #[test]
+    fn unknown_limiter_is_rejected_with_suggestion() {
+        let err = lower("{name:snakecase}", Conformance::Strict).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::UnknownLimiter);
+        assert_eq!(err.span, Some(Span::new(6, 15)));
+        assert!(err.message.contains("did you mean 'snake_case'"));
+    }
+
+    #[test]
+    fn unknown_limiter_is_allowed_when_lenient() {
+        assert!(matches!(
+            lower("{name:future_thing(3)}", Conformance::Lenient),
+            Ok(Limiter::Unknown { .. })
+        ));
+    }
+
+    #[test]
+    fn wrong_arity_is_rejected() {
+        for input in [
+            "{n:snake_case(1)}",
+            "{n:int}",
+            "{n:int(1,2)}",
+            "{n:range(1)}",
+            "{n:re(abc)}",
+            "{d:date}",
+            "{d:date(4)}",
+            "{d:iso8601(1)}",
+        ] {
+            let err = lower(input, Conformance::Lenient).unwrap_err();
+            assert_eq!(err.kind, ParseErrorKind::InvalidLimiterArgs, "{input}");
+        }
+    }
+
+    #[test]
+    fn bad_argument_values_are_rejected() {
+        for input in ["{n:int(0)}", "{n:int(3..2)}", "{n:range(10,1)}"] {
+            let err = lower(input, Conformance::Strict).unwrap_err();
+            assert_eq!(err.kind, ParseErrorKind::InvalidLimiterArgs, "{input}");
+        }
+    }
+
+    #[test]
+    fn date_formats_are_checked() {
+        assert!(matches!(
+            lower(r#"{d:date("%Y-%m-%d")}"#, Conformance::Strict),
+            Ok(Limiter::Date { .. })
+        ));
+        assert!(matches!(
+            lower("{d:iso8601}", Conformance::Strict),
+            Ok(Limiter::Iso8601 { .. })
+        ));
+        let err = lower(r#"{d:date("%Y-%q")}"#, Conformance::Strict).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidLimiterArgs);
+        assert_eq!(err.span, Some(Span::new(8, 15)));
+    }
+
+    #[test]
+    fn invalid_regex_is_rejected_with_arg_span() {
+        let err = lower(r#"{s:re("[a-z")}"#, Conformance::Strict).unwrap_err();
+        assert_eq!(err.kind, ParseErrorKind::InvalidRegex);
+        assert_eq!(err.span, Some(Span::new(6, 12)));
+    }
+
+    #[test]
+    fn validate_component_checks_every_capture() {
+        let ast = parse_component("{a:int(2)}_{b:bogus}").unwrap();
+        assert!(validate_component(&ast, Conformance::Strict).is_err());
+        assert!(validate_component(&ast, Conformance::Lenient).is_ok());
+    }
+}
@@ -1,4 +1,5 @@
-use clap::{Parser, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Deserialize;
 use std::path::PathBuf;
 
 /// fspec: validate a directory tree against an .fspec file.
@@ -6,28 +7,94 @@ use std::path::PathBuf;
 #[command(name = "fspec")]
 #[command(about = "Validate a directory tree against .fspec rules", long_about = None)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Target PATH to scan (defaults to current directory)
     pub path: Option<PathBuf>,
 
     /// Explicit scan root (overrides PATH if provided)
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub root: Option<PathBuf>,
 
-    /// Explicit spec file path (NOT IMPLEMENTED YET in core)
-    #[arg(long)]
+    /// Explicit spec file path (defaults to `.fspec` in the scan root)
+    #[arg(long, global = true)]
     pub spec: Option<PathBuf>,
 
-    /// Output format
-    #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
-    pub format: OutputFormat,
+    /// Config file to use instead of discovering `fspec.toml` upward from the scan root
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
+    /// Ignore any `fspec.toml`
+    #[arg(long, global = true, conflicts_with = "config")]
+    pub no_config: bool,
+
+    /// Output format [default: human]
+    #[arg(long, value_enum)]
+    pub format: Option<OutputFormat>,
+
+    /// Stored JSON report; only findings not already in it are reported and cause failure
+    #[arg(long, value_name = "REPORT")]
+    pub baseline: Option<PathBuf>,
+
+    /// JSON report schema version (`v2` adds every path's status, rule line and captures)
+    /// [default: v1]
+    #[arg(long, value_enum)]
+    pub report_version: Option<ReportVersion>,
+
+    /// Leaf matching mode [default: loose]
+    #[arg(long, value_enum, global = true)]
+    pub leaf: Option<LeafMode>,
+
+    /// Limiter validation level (lenient accepts unknown limiter names) [default: strict]
+    #[arg(long, value_enum, global = true)]
+    pub conformance: Option<ConformanceArg>,
+
+    /// Match literals and one-of choices regardless of case
+    #[arg(long, global = true, overrides_with = "no_ignore_case")]
+    pub ignore_case: bool,
+
+    /// Match case-sensitively even if the config says `ignore-case`
+    #[arg(long, global = true, overrides_with = "ignore_case")]
+    pub no_ignore_case: bool,
+
+    /// Unicode normalization applied to spec literals and names before matching
+    /// [default: none]
+    #[arg(long, value_enum, global = true)]
+    pub normalize: Option<NormalizeArg>,
+
+    /// Report names that aren't valid UTF-8 as findings
+    #[arg(long, global = true, overrides_with = "no_report_non_utf8")]
+    pub report_non_utf8: bool,
+
+    /// Don't report names that aren't valid UTF-8, even if the config says to
+    #[arg(long, global = true, overrides_with = "report_non_utf8")]
+    pub no_report_non_utf8: bool,
+
+    /// Default severity for findings that don't have their own (unaccounted paths, limits)
+    /// [default: warning]
+    #[arg(long, value_enum)]
+    pub severity: Option<SeverityArg>,
+
+    /// Fail if there are more than N unaccounted paths [default: 0]
+    #[arg(long, value_name = "N")]
+    pub max_unaccounted: Option<usize>,
 
-    /// Leaf matching mode
-    #[arg(long, value_enum, default_value_t = LeafMode::Loose)]
-    pub leaf: LeafMode,
+    /// Fail on diagnostics at or above this severity [default: error]
+    #[arg(long, value_enum)]
+    pub fail_on: Option<SeverityArg>,
 
-    /// Default severity to use for findings (and current fail threshold)
-    #[arg(long, value_enum, default_value_t = SeverityArg::Warning)]
-    pub severity: SeverityArg,
+    /// Report allow and ignore rules that decided no path (`unused_rule`) and fail on them
+    #[arg(long, overrides_with = "no_fail_on_unused_rules")]
+    pub fail_on_unused_rules: bool,
+
+    /// Don't report unused rules, even if the config says to
+    #[arg(long, overrides_with = "fail_on_unused_rules")]
+    pub no_fail_on_unused_rules: bool,
+
+    /// Ignore a common set of paths, e.g. `vcs` (repeatable; replaces the config's presets)
+    #[arg(long = "ignore-preset", value_enum, value_name = "PRESET")]
+    pub ignore_presets: Vec<IgnorePreset>,
 
     /// Quiet output (print only paths)
     #[arg(short = 'q', long)]
@@ -38,21 +105,163 @@ pub struct Cli {
     pub verbosity: u8,
 }
 
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print machine-readable schemas for fspec's output formats
+    Schema(SchemaArgs),
+    /// Compare two stored JSON reports (exits 1 if NEW has findings OLD didn't)
+    Diff(DiffArgs),
+    /// Emit one row per allowed file with its captured placeholder values
+    Extract(ExtractArgs),
+    /// Check the spec itself for dead, duplicated or unmatchable rules (no tree is walked)
+    Lint(LintArgs),
+    /// Rewrite the spec in canonical form
+    Fmt(FmtArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct FmtArgs {
+    /// Directory whose `.fspec` is formatted (defaults to current directory)
+    pub path: Option<PathBuf>,
+
+    /// Don't write anything; exit 1 if the spec isn't already formatted
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct LintArgs {
+    /// Directory whose `.fspec` is linted (defaults to current directory)
+    pub path: Option<PathBuf>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = DiffFormat::Human)]
+    pub format: DiffFormat,
+
+    /// Also show informational findings (-v)
+    #[arg(short = 'v', long, action = clap::ArgAction::Count)]
+    pub verbosity: u8,
+}
+
+#[derive(Debug, Args)]
+pub struct ExtractArgs {
+    /// Target PATH to scan (defaults to current directory)
+    pub path: Option<PathBuf>,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ExtractFormat::Jsonl)]
+    pub format: ExtractFormat,
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ExtractFormat {
+    /// JSON Lines: `{"path", "rule_line", "captures"}` per file
+    Jsonl,
+    /// CSV with one column per placeholder name
+    Csv,
+}
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// Earlier report (`--format json`)
+    pub old: PathBuf,
+
+    /// Later report (`--format json`)
+    pub new: PathBuf,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = DiffFormat::Human)]
+    pub format: DiffFormat,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum DiffFormat {
+    Human,
+    Json,
+}
+
+#[derive(Debug, Args)]
+pub struct SchemaArgs {
+    /// Print the JSON Schema for the `--format json` report
+    #[arg(long, required_unless_present = "ndjson")]
+    pub report: bool,
+
+    /// Print the JSON Schema for each line of `--format ndjson` output
+    #[arg(long, conflicts_with = "report")]
+    pub ndjson: bool,
+
+    /// Report schema version to print
+    #[arg(long, value_enum, default_value_t = ReportVersion::V1)]
+    pub report_version: ReportVersion,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum OutputFormat {
     Human,
     Json,
+    /// JUnit XML, one test case per finding
+    Junit,
+    /// GitHub Actions workflow commands (`::error file=...::`)
+    Github,
+    /// GitLab Code Quality report (JSON array)
+    GitlabCodequality,
+    /// One JSON object per finding, printed as the walk proceeds, then a summary record
+    Ndjson,
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReportVersion {
+    /// `fspec.report.v1`: unaccounted paths and diagnostics only
+    V1,
+    /// `fspec.report.v2`: every path with status, kind, deciding rule and captures
+    V2,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum LeafMode {
     Strict,
     Loose,
 }
 
-#[derive(Debug, Clone, Copy, ValueEnum)]
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConformanceArg {
+    Strict,
+    Lenient,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NormalizeArg {
+    None,
+    Nfc,
+    Nfd,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum SeverityArg {
     Info,
     Warning,
     Error,
 }
+
+/// A named set of `ignore` rules for paths most trees want ignored, added after the spec's
+/// own rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IgnorePreset {
+    /// Version control metadata: `.git/`, `.hg/`, `.svn/`, `.jj/`
+    Vcs,
+    /// Files operating systems leave behind: `.DS_Store`, `Thumbs.db`, `desktop.ini`
+    Os,
+    /// Editor state and swap files: `.idea/`, `.vscode/`, `*.swp`, `*~`
+    Editor,
+    /// Rust build output: `./target/`
+    Rust,
+    /// Node.js dependencies: `node_modules/`
+    Node,
+}
@@ -0,0 +1,362 @@
+use fspec_core::{Report, Status};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+/// The parts of a stored JSON report (`fspec.report.v1` or `v2`) needed for
+/// baselines and diffs.
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    pub unaccounted: BTreeSet<String>,
+    /// Status per path. Only v2 reports list every path, so this is `None` for v1.
+    pub statuses: Option<BTreeMap<String, String>>,
+    /// Diagnostics identified by [`diag_key`].
+    pub diagnostics: BTreeSet<(String, String)>,
+}
+
+#[derive(Deserialize)]
+struct StoredReport {
+    schema_version: String,
+    #[serde(default)]
+    unaccounted: Vec<String>,
+    #[serde(default)]
+    entries: Option<Vec<StoredEntry>>,
+    #[serde(default)]
+    diagnostics: Vec<StoredDiag>,
+}
+
+#[derive(Deserialize)]
+struct StoredEntry {
+    path: String,
+    status: String,
+}
+
+#[derive(Deserialize)]
+struct StoredDiag {
+    code: String,
+    path: String,
+    #[serde(default)]
+    message: String,
+}
+
+/// How a diagnostic is recognized across reports: `(code, path)`, or `(code, message)` for one
+/// that isn't about a path (`unused_rule`), so such findings don't all share one key.
+pub fn diag_key(code: &str, path: &str, message: &str) -> (String, String) {
+    let subject = if path.is_empty() { message } else { path };
+    (code.to_string(), subject.to_string())
+}
+
+impl Snapshot {
+    pub fn load(path: &Path) -> Result<Snapshot, String> {
+        let src = fs::read_to_string(path)
+            .map_err(|e| format!("I/O error reading {}: {}", path.display(), e))?;
+        Snapshot::from_json(&src).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn from_json(src: &str) -> Result<Snapshot, String> {
+        let stored: StoredReport =
+            serde_json::from_str(src).map_err(|e| format!("not a JSON fspec report: {e}"))?;
+
+        match stored.schema_version.as_str() {
+            "fspec.report.v1" => {}
+            "fspec.report.v2" => {
+                if stored.entries.is_none() {
+                    return Err("fspec.report.v2 report is missing 'entries'".to_string());
+                }
+            }
+            other => return Err(format!("unsupported report schema_version {other:?}")),
+        }
+
+        let mut unaccounted: BTreeSet<String> = stored.unaccounted.into_iter().collect();
+        let statuses = stored.entries.map(|entries| {
+            entries
+                .into_iter()
+                .map(|e| (e.path, e.status))
+                .collect::<BTreeMap<_, _>>()
+        });
+        if let Some(statuses) = &statuses {
+            unaccounted.extend(
+                statuses
+                    .iter()
+                    .filter(|(_, s)| s.as_str() == "unaccounted")
+                    .map(|(p, _)| p.clone()),
+            );
+        }
+
+        Ok(Snapshot {
+            unaccounted,
+            statuses,
+            diagnostics: stored
+                .diagnostics
+                .into_iter()
+                .map(|d| diag_key(&d.code, &d.path, &d.message))
+                .collect(),
+        })
+    }
+}
+
+/// Drop findings that are already recorded in `baseline` from `report`.
+///
+/// Baselined unaccounted paths and diagnostics are removed entirely, so every
+/// renderer and the exit code only see new findings. Returns how many were dropped.
+pub fn apply_baseline(report: &mut Report, baseline: &Snapshot) -> usize {
+    let mut suppressed = 0;
+
+    report.retain_entries(|path, e| {
+        let known = e.status == Status::Unaccounted && baseline.unaccounted.contains(path);
+        suppressed += usize::from(known);
+        !known
+    });
+    report.retain_diagnostics(|d| {
+        let known = baseline
+            .diagnostics
+            .contains(&diag_key(d.code, &d.path, &d.message));
+        suppressed += usize::from(known);
+        !known
+    });
+
+    suppressed
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct StatusChange {
+    pub path: String,
+    pub old: String,
+    pub new: String,
+}
+
+/// Differences between two stored reports.
+#[derive(Debug, Default, Serialize)]
+pub struct ReportDiff {
+    pub newly_unaccounted: Vec<String>,
+    pub newly_fixed: Vec<String>,
+    /// Only computed when both reports are v2 (v1 doesn't list accounted paths).
+    pub status_changes: Vec<StatusChange>,
+    pub new_diagnostics: Vec<(String, String)>,
+    pub resolved_diagnostics: Vec<(String, String)>,
+}
+
+impl ReportDiff {
+    pub fn has_new_findings(&self) -> bool {
+        !self.newly_unaccounted.is_empty() || !self.new_diagnostics.is_empty()
+    }
+}
+
+pub fn diff(old: &Snapshot, new: &Snapshot) -> ReportDiff {
+    let mut status_changes = Vec::new();
+    if let (Some(old_st), Some(new_st)) = (&old.statuses, &new.statuses) {
+        for (path, new_status) in new_st {
+            match old_st.get(path) {
+                Some(old_status) if old_status != new_status => status_changes.push(StatusChange {
+                    path: path.clone(),
+                    old: old_status.clone(),
+                    new: new_status.clone(),
+                }),
+                _ => {}
+            }
+        }
+    }
+
+    ReportDiff {
+        newly_unaccounted: new
+            .unaccounted
+            .difference(&old.unaccounted)
+            .cloned()
+            .collect(),
+        newly_fixed: old
+            .unaccounted
+            .difference(&new.unaccounted)
+            .cloned()
+            .collect(),
+        status_changes,
+        new_diagnostics: new
+            .diagnostics
+            .difference(&old.diagnostics)
+            .cloned()
+            .collect(),
+        resolved_diagnostics: old
+            .diagnostics
+            .difference(&new.diagnostics)
+            .cloned()
+            .collect(),
+    }
+}
+
+pub fn render_diff_human(d: &ReportDiff) -> String {
+    let mut out = String::new();
+
+    for p in &d.newly_unaccounted {
+        out.push_str(&format!("+ unaccounted: {p}\n"));
+    }
+    for p in &d.newly_fixed {
+        out.push_str(&format!("- unaccounted: {p}\n"));
+    }
+    for c in &d.status_changes {
+        out.push_str(&format!("~ {}: {} -> {}\n", c.path, c.old, c.new));
+    }
+    for (code, path) in &d.new_diagnostics {
+        out.push_str(&format!("+ {code}: {path}\n"));
+    }
+    for (code, path) in &d.resolved_diagnostics {
+        out.push_str(&format!("- {code}: {path}\n"));
+    }
+
+    out.push_str(&format!(
+        "summary: new={} fixed={} changed={}\n",
+        d.newly_unaccounted.len() + d.new_diagnostics.len(),
+        d.newly_fixed.len() + d.resolved_diagnostics.len(),
+        d.status_changes.len()
+    ));
+    out
+}
+
+pub fn render_diff_json(d: &ReportDiff) -> String {
+    serde_json::to_string_pretty(d).unwrap_or_else(|_| "{}".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fspec_core::{Diagnostic, Severity};
+
+    const V1: &str = r#"{
+        "schema_version": "fspec.report.v1",
+        "tool_version": "0.1.0",
+        "ok": false,
+        "unaccounted": ["old/a.txt", "old/b.txt"],
+        "diagnostics": [
+            {"code": "ambiguous_match", "severity": "warning", "path": "x", "message": "m", "rule_lines": [1]}
+        ],
+        "summary": {"unaccounted_count": 2}
+    }"#;
+
+    fn v2(entries: &[(&str, &str)]) -> String {
+        let entries: Vec<serde_json::Value> = entries
+            .iter()
+            .map(|(p, s)| {
+                serde_json::json!({
+                    "path": p, "kind": "file", "status": s, "rule_line": null, "captures": {}
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "schema_version": "fspec.report.v2",
+            "tool_version": "0.1.0",
+            "ok": true,
+            "entries": entries,
+            "diagnostics": [],
+            "summary": {}
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn loads_v1_and_v2_reports() {
+        let s1 = Snapshot::from_json(V1).unwrap();
+        assert_eq!(s1.unaccounted.len(), 2);
+        assert!(s1.statuses.is_none());
+        assert!(
+            s1.diagnostics
+                .contains(&("ambiguous_match".to_string(), "x".to_string()))
+        );
+
+        let s2 = Snapshot::from_json(&v2(&[("a", "allowed"), ("b", "unaccounted")])).unwrap();
+        assert_eq!(s2.unaccounted.iter().collect::<Vec<_>>(), ["b"]);
+        assert_eq!(s2.statuses.unwrap()["a"], "allowed");
+    }
+
+    #[test]
+    fn rejects_unknown_schema_versions() {
+        let err = Snapshot::from_json(r#"{"schema_version": "other.v9"}"#).unwrap_err();
+        assert!(err.contains("unsupported"));
+        assert!(Snapshot::from_json("[]").is_err());
+    }
+
+    #[test]
+    fn baseline_suppresses_known_findings_only() {
+        let baseline = Snapshot::from_json(V1).unwrap();
+
+        let mut report = Report::default();
+        report.set_status("old/a.txt", Status::Unaccounted);
+        report.set_status("new/c.txt", Status::Unaccounted);
+        report.set_status("old/b.txt", Status::Allowed);
+        report.push_diagnostic(Diagnostic {
+            code: "ambiguous_match",
+            severity: Severity::Warning,
+            path: "x".into(),
+            message: "m".into(),
+            rule_lines: vec![1],
+        });
+
+        assert_eq!(apply_baseline(&mut report, &baseline), 2);
+        assert_eq!(report.unaccounted_paths(), ["new/c.txt"]);
+        assert!(report.is_allowed("old/b.txt"));
+        assert!(report.diagnostics().is_empty());
+    }
+
+    #[test]
+    fn pathless_diagnostics_are_keyed_by_message() {
+        let baseline = Snapshot::from_json(
+            r#"{
+            "schema_version": "fspec.report.v1",
+            "diagnostics": [
+                {"code": "unused_rule", "path": "", "message": "rule decided no path: allow ./a"}
+            ]
+        }"#,
+        )
+        .unwrap();
+
+        let mut report = Report::default();
+        for rule in ["allow ./a", "allow ./b"] {
+            report.push_diagnostic(Diagnostic {
+                code: "unused_rule",
+                severity: Severity::Warning,
+                path: String::new(),
+                message: format!("rule decided no path: {rule}"),
+                rule_lines: vec![7],
+            });
+        }
+
+        assert_eq!(apply_baseline(&mut report, &baseline), 1);
+        assert_eq!(
+            report.diagnostics()[0].message,
+            "rule decided no path: allow ./b"
+        );
+    }
+
+    #[test]
+    fn diff_reports_new_fixed_and_changed() {
+        let old = Snapshot::from_json(&v2(&[
+            ("a", "unaccounted"),
+            ("b", "allowed"),
+            ("c", "ignored"),
+        ]))
+        .unwrap();
+        let new = Snapshot::from_json(&v2(&[
+            ("a", "allowed"),
+            ("b", "unaccounted"),
+            ("c", "ignored"),
+        ]))
+        .unwrap();
+
+        let d = diff(&old, &new);
+        assert_eq!(d.newly_unaccounted, ["b"]);
+        assert_eq!(d.newly_fixed, ["a"]);
+        assert_eq!(d.status_changes.len(), 2);
+        assert!(d.has_new_findings());
+
+        let human = render_diff_human(&d);
+        assert!(human.contains("+ unaccounted: b\n"));
+        assert!(human.contains("- unaccounted: a\n"));
+        assert!(human.contains("~ a: unaccounted -> allowed\n"));
+    }
+
+    #[test]
+    fn diff_of_v1_reports_has_no_status_changes() {
+        let old = Snapshot::from_json(V1).unwrap();
+        let d = diff(&old, &old);
+        assert!(d.status_changes.is_empty());
+        assert!(!d.has_new_findings());
+    }
+}
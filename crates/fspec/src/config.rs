@@ -0,0 +1,501 @@
+use crate::args::{
+    Cli, ConformanceArg, IgnorePreset, LeafMode, NormalizeArg, OutputFormat, ReportVersion,
+    SeverityArg,
+};
+use crate::policy::ExitPolicy;
+use clap::{Parser, ValueEnum};
+use fspec_core::{Conformance, Error, MatchSettings, NormalizationForm, Severity, Spec};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// The config file looked for in the scan root and each of its ancestors.
+pub const CONFIG_FILE: &str = "fspec.toml";
+
+/// The contents of an `fspec.toml`. Every key is optional; command-line flags override it.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct Config {
+    /// Spec file, or candidates of which the first that exists is used. Relative to the
+    /// config file's directory.
+    spec: Option<SpecPaths>,
+    #[serde(default)]
+    ignore_presets: Vec<IgnorePreset>,
+    #[serde(default, rename = "match")]
+    matching: MatchSection,
+    #[serde(default)]
+    output: OutputSection,
+    #[serde(default)]
+    exit: ExitSection,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SpecPaths {
+    One(PathBuf),
+    Any(Vec<PathBuf>),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct MatchSection {
+    leaf: Option<LeafMode>,
+    conformance: Option<ConformanceArg>,
+    #[serde(default)]
+    ignore_case: bool,
+    normalize: Option<NormalizeArg>,
+    #[serde(default)]
+    report_non_utf8: bool,
+    severity: Option<SeverityArg>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct OutputSection {
+    format: Option<OutputFormat>,
+    report_version: Option<ReportVersion>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct ExitSection {
+    max_unaccounted: Option<usize>,
+    fail_on: Option<SeverityArg>,
+    #[serde(default)]
+    fail_on_unused_rules: bool,
+}
+
+/// The nearest `fspec.toml` in `root` or one of its ancestors, looking no further up than the
+/// first directory holding `.git` (the repository's root) or the last one on `root`'s
+/// filesystem, so a stray file in `$HOME` or `/` isn't picked up.
+pub fn discover(root: &Path) -> Option<PathBuf> {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    for dir in root.ancestors() {
+        let path = dir.join(CONFIG_FILE);
+        if path.is_file() {
+            return Some(path);
+        }
+        if dir.join(".git").exists() || dir.parent().is_some_and(|p| !same_filesystem(dir, p)) {
+            break;
+        }
+    }
+    None
+}
+
+#[cfg(unix)]
+fn same_filesystem(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (std::fs::metadata(a), std::fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_filesystem(_: &Path, _: &Path) -> bool {
+    true
+}
+
+/// Read and parse the config at `path`.
+pub fn load(path: &Path) -> Result<Config, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    parse(&text).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn parse(text: &str) -> Result<Config, toml::de::Error> {
+    toml::from_str(text)
+}
+
+/// Everything a run needs that the command line and config file decide.
+#[derive(Debug)]
+pub struct Resolved {
+    pub settings: MatchSettings,
+    pub policy: ExitPolicy,
+    pub format: OutputFormat,
+    pub report_version: ReportVersion,
+    pub spec_path: PathBuf,
+    pub ignore_presets: Vec<IgnorePreset>,
+    /// The config file that was loaded, if any; checks leave it out like the spec file.
+    pub config_path: Option<PathBuf>,
+}
+
+/// Layer `cli` over `config` (found in `config_dir`) over the built-in defaults.
+pub fn resolve(cli: &Cli, root: &Path, config: Option<(&Config, &Path)>) -> Resolved {
+    let default = Config::default();
+    let (cfg, config_dir) = config.unwrap_or((&default, root));
+    let m = &cfg.matching;
+
+    let leaf = cli.leaf.or(m.leaf).unwrap_or(LeafMode::Loose);
+    let settings = MatchSettings {
+        allow_file_or_dir_leaf: matches!(leaf, LeafMode::Loose),
+        default_severity: severity_from_arg(
+            cli.severity.or(m.severity).unwrap_or(SeverityArg::Warning),
+        ),
+        conformance: match cli.conformance.or(m.conformance) {
+            Some(ConformanceArg::Strict) | None => Conformance::Strict,
+            Some(ConformanceArg::Lenient) => Conformance::Lenient,
+        },
+        case_insensitive: switch(cli.ignore_case, cli.no_ignore_case, m.ignore_case),
+        normalization: match cli.normalize.or(m.normalize) {
+            Some(NormalizeArg::None) | None => NormalizationForm::None,
+            Some(NormalizeArg::Nfc) => NormalizationForm::Nfc,
+            Some(NormalizeArg::Nfd) => NormalizationForm::Nfd,
+        },
+        report_non_utf8_names: switch(
+            cli.report_non_utf8,
+            cli.no_report_non_utf8,
+            m.report_non_utf8,
+        ),
+    };
+
+    let policy = ExitPolicy {
+        max_unaccounted: cli
+            .max_unaccounted
+            .or(cfg.exit.max_unaccounted)
+            .unwrap_or(0),
+        fail_on: severity_from_arg(
+            cli.fail_on
+                .or(cfg.exit.fail_on)
+                .unwrap_or(SeverityArg::Error),
+        ),
+        fail_on_unused_rules: switch(
+            cli.fail_on_unused_rules,
+            cli.no_fail_on_unused_rules,
+            cfg.exit.fail_on_unused_rules,
+        ),
+    };
+
+    let spec_path = match (&cli.spec, &cfg.spec) {
+        (Some(p), _) => p.clone(),
+        (None, Some(paths)) => {
+            spec_from_config(paths, config_dir).unwrap_or_else(|| root.join(".fspec"))
+        }
+        (None, None) => root.join(".fspec"),
+    };
+
+    let ignore_presets = if cli.ignore_presets.is_empty() {
+        cfg.ignore_presets.clone()
+    } else {
+        cli.ignore_presets.clone()
+    };
+
+    Resolved {
+        settings,
+        policy,
+        format: cli
+            .format
+            .or(cfg.output.format)
+            .unwrap_or(OutputFormat::Human),
+        report_version: cli
+            .report_version
+            .or(cfg.output.report_version)
+            .unwrap_or(ReportVersion::V1),
+        spec_path,
+        ignore_presets,
+        config_path: None,
+    }
+}
+
+/// The config file a run at `root` uses: `--config`, or else the nearest one upward from
+/// `root` unless `--no-config`.
+pub fn config_path(cli: &Cli, root: &Path) -> Option<PathBuf> {
+    match (&cli.config, cli.no_config) {
+        (Some(p), _) => Some(p.clone()),
+        (None, false) => discover(root),
+        (None, true) => None,
+    }
+}
+
+/// Layer `cli` over the config file it uses (see [`config_path`]) for a run at `root`.
+///
+/// # Errors
+///
+/// Returns a message naming the config file if it can't be read or parsed.
+pub fn resolve_run(cli: &Cli, root: &Path) -> Result<Resolved, String> {
+    let Some(path) = config_path(cli, root) else {
+        return Ok(resolve(cli, root, None));
+    };
+    let cfg = load(&path)?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    Ok(Resolved {
+        config_path: Some(path.clone()),
+        ..resolve(cli, root, Some((&cfg, dir)))
+    })
+}
+
+/// What `fspec` run at `root` without flags uses, for tools that should match it.
+///
+/// # Errors
+///
+/// As [`resolve_run`].
+pub fn resolve_without_flags(root: &Path) -> Result<Resolved, String> {
+    resolve_run(&Cli::parse_from(["fspec"]), root)
+}
+
+/// Read the spec at `path` with the resolved settings and add the rules of its ignore presets.
+/// The loaded config file is left out of checks, like the spec file.
+///
+/// # Errors
+///
+/// As [`Spec::from_file`]; a preset that doesn't parse is an [`Error::Spec`] for `path`.
+pub fn load_spec(resolved: &Resolved, path: &Path) -> Result<Spec, Error> {
+    let mut spec = Spec::from_file(path, &resolved.settings)?;
+    if let Some(config_path) = &resolved.config_path {
+        spec.skip_file(config_path);
+    }
+    for &preset in &resolved.ignore_presets {
+        spec.extend(&preset_source(preset), preset_rules(preset))
+            .map_err(|errors| Error::Spec {
+                path: path.to_path_buf(),
+                errors,
+            })?;
+    }
+    Ok(spec)
+}
+
+/// The first configured spec that exists, or the first listed if none do (so the error names
+/// it).
+fn spec_from_config(paths: &SpecPaths, dir: &Path) -> Option<PathBuf> {
+    let paths = match paths {
+        SpecPaths::One(p) => std::slice::from_ref(p),
+        SpecPaths::Any(ps) => ps.as_slice(),
+    };
+    let candidates: Vec<PathBuf> = paths.iter().map(|p| dir.join(p)).collect();
+    candidates
+        .iter()
+        .find(|p| p.is_file())
+        .or(candidates.first())
+        .cloned()
+}
+
+/// A switch set by `--flag` or `--no-flag`, or else by the config.
+fn switch(on: bool, off: bool, config: bool) -> bool {
+    on || (config && !off)
+}
+
+fn severity_from_arg(arg: SeverityArg) -> Severity {
+    match arg {
+        SeverityArg::Info => Severity::Info,
+        SeverityArg::Warning => Severity::Warning,
+        SeverityArg::Error => Severity::Error,
+    }
+}
+
+/// The source preset rules are tagged with, e.g. `preset vcs`.
+pub fn preset_source(preset: IgnorePreset) -> String {
+    let value = preset.to_possible_value().expect("presets aren't skipped");
+    format!("preset {}", value.get_name())
+}
+
+/// The rules an ignore preset adds, in spec syntax.
+pub fn preset_rules(preset: IgnorePreset) -> &'static str {
+    match preset {
+        IgnorePreset::Vcs => "ignore .git/\nignore .hg/\nignore .svn/\nignore .jj/\n",
+        IgnorePreset::Os => "ignore .DS_Store\nignore Thumbs.db\nignore desktop.ini\n",
+        IgnorePreset::Editor => "ignore .idea/\nignore .vscode/\nignore *.swp\nignore *~\n",
+        IgnorePreset::Rust => "ignore ./target/\n",
+        IgnorePreset::Node => "ignore node_modules/\n",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cli(args: &[&str]) -> Cli {
+        Cli::parse_from(std::iter::once("fspec").chain(args.iter().copied()))
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("fspec-config-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn discovers_the_nearest_config_upward_from_the_root() {
+        let dir = temp_dir("discover");
+        let nested = dir.join("a/b");
+        std::fs::create_dir_all(&nested).unwrap();
+        // Nothing below the temp dir yet, so whatever is found is above it.
+        assert_eq!(discover(&nested), discover(&std::env::temp_dir()));
+
+        std::fs::write(dir.join(CONFIG_FILE), "").unwrap();
+        let found = discover(&nested).unwrap();
+        assert_eq!(found, dir.canonicalize().unwrap().join(CONFIG_FILE));
+
+        std::fs::write(dir.join("a").join(CONFIG_FILE), "").unwrap();
+        let found = discover(&nested).unwrap();
+        assert_eq!(
+            found,
+            dir.canonicalize().unwrap().join("a").join(CONFIG_FILE)
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn discovery_stops_at_the_repository_root() {
+        let dir = temp_dir("repo");
+        let nested = dir.join("repo/a");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::create_dir_all(dir.join("repo/.git")).unwrap();
+        std::fs::write(dir.join(CONFIG_FILE), "").unwrap();
+        assert_eq!(discover(&nested), None);
+
+        std::fs::write(dir.join("repo").join(CONFIG_FILE), "").unwrap();
+        let found = discover(&nested).unwrap();
+        assert_eq!(
+            found,
+            dir.canonicalize().unwrap().join("repo").join(CONFIG_FILE)
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn flags_override_the_config_which_overrides_defaults() {
+        let cfg = parse(
+            "ignore-presets = [\"vcs\"]\n\
+             [match]\nleaf = \"strict\"\nseverity = \"error\"\nignore-case = true\n\
+             [output]\nformat = \"json\"\n\
+             [exit]\nmax-unaccounted = 5\nfail-on = \"warning\"\n",
+        )
+        .unwrap();
+        let root = Path::new("tree");
+
+        let r = resolve(&cli(&[]), root, Some((&cfg, root)));
+        assert!(!r.settings.allow_file_or_dir_leaf);
+        assert_eq!(r.settings.default_severity, Severity::Error);
+        assert!(r.settings.case_insensitive);
+        assert!(matches!(r.format, OutputFormat::Json));
+        assert_eq!(r.report_version, ReportVersion::V1);
+        assert_eq!(r.policy.max_unaccounted, 5);
+        assert_eq!(r.policy.fail_on, Severity::Warning);
+        assert_eq!(r.ignore_presets, [IgnorePreset::Vcs]);
+        assert_eq!(r.spec_path, root.join(".fspec"));
+
+        let r = resolve(
+            &cli(&[
+                "--leaf",
+                "loose",
+                "--max-unaccounted",
+                "0",
+                "--format",
+                "human",
+                "--ignore-preset",
+                "rust",
+            ]),
+            root,
+            Some((&cfg, root)),
+        );
+        assert!(r.settings.allow_file_or_dir_leaf);
+        assert_eq!(r.settings.default_severity, Severity::Error);
+        assert!(matches!(r.format, OutputFormat::Human));
+        assert_eq!(r.policy.max_unaccounted, 0);
+        assert_eq!(r.ignore_presets, [IgnorePreset::Rust]);
+
+        let r = resolve(&cli(&[]), root, None);
+        assert!(r.settings.allow_file_or_dir_leaf);
+        assert_eq!(r.settings.default_severity, Severity::Warning);
+        assert_eq!(r.policy.fail_on, Severity::Error);
+        assert!(r.ignore_presets.is_empty());
+    }
+
+    #[test]
+    fn no_flags_turn_off_switches_the_config_turns_on() {
+        let cfg = parse(
+            "[match]\nignore-case = true\nreport-non-utf8 = true\n\
+             [exit]\nfail-on-unused-rules = true\n",
+        )
+        .unwrap();
+        let root = Path::new("tree");
+
+        let r = resolve(&cli(&[]), root, Some((&cfg, root)));
+        assert!(r.settings.case_insensitive && r.settings.report_non_utf8_names);
+        assert!(r.policy.fail_on_unused_rules);
+
+        let off = cli(&[
+            "--no-ignore-case",
+            "--no-report-non-utf8",
+            "--no-fail-on-unused-rules",
+        ]);
+        let r = resolve(&off, root, Some((&cfg, root)));
+        assert!(!r.settings.case_insensitive && !r.settings.report_non_utf8_names);
+        assert!(!r.policy.fail_on_unused_rules);
+
+        // the last of a switch and its `--no-` form wins
+        let r = resolve(&cli(&["--no-ignore-case", "--ignore-case"]), root, None);
+        assert!(r.settings.case_insensitive);
+        let r = resolve(&cli(&["--ignore-case", "--no-ignore-case"]), root, None);
+        assert!(!r.settings.case_insensitive);
+    }
+
+    #[test]
+    fn spec_is_the_first_listed_that_exists_relative_to_the_config() {
+        let dir = temp_dir("spec");
+        std::fs::write(dir.join("b.fspec"), "allow ./x\n").unwrap();
+        let cfg = parse("spec = [\"a.fspec\", \"b.fspec\"]\n").unwrap();
+        let root = dir.join("sub");
+
+        let r = resolve(&cli(&[]), &root, Some((&cfg, &dir)));
+        assert_eq!(r.spec_path, dir.join("b.fspec"));
+
+        std::fs::remove_file(dir.join("b.fspec")).unwrap();
+        let r = resolve(&cli(&[]), &root, Some((&cfg, &dir)));
+        assert_eq!(r.spec_path, dir.join("a.fspec"));
+
+        let r = resolve(&cli(&["--spec", "own.fspec"]), &root, Some((&cfg, &dir)));
+        assert_eq!(r.spec_path, Path::new("own.fspec"));
+
+        let cfg = parse("spec = \"one.fspec\"\n").unwrap();
+        let r = resolve(&cli(&[]), &root, Some((&cfg, &dir)));
+        assert_eq!(r.spec_path, dir.join("one.fspec"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn without_flags_the_nearest_config_is_used() {
+        let dir = temp_dir("no-flags");
+        std::fs::write(
+            dir.join(CONFIG_FILE),
+            "ignore-presets = [\"vcs\"]\n[match]\nleaf = \"strict\"\n",
+        )
+        .unwrap();
+
+        let r = resolve_without_flags(&dir).unwrap();
+        assert!(!r.settings.allow_file_or_dir_leaf);
+        assert_eq!(r.ignore_presets, [IgnorePreset::Vcs]);
+        assert_eq!(
+            r.config_path,
+            Some(dir.canonicalize().unwrap().join(CONFIG_FILE))
+        );
+
+        std::fs::write(dir.join(CONFIG_FILE), "[match]\nleaf = \"tight\"\n").unwrap();
+        let err = resolve_without_flags(&dir).unwrap_err();
+        assert!(err.contains(CONFIG_FILE), "{err}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unknown_keys_and_presets_are_rejected() {
+        assert!(parse("[match]\nleaf-mode = \"strict\"\n").is_err());
+        assert!(parse("colour = true\n").is_err());
+        let err = parse("ignore-presets = [\"vsc\"]\n").unwrap_err();
+        assert!(err.to_string().contains("vsc"), "{err}");
+    }
+
+    #[test]
+    fn every_preset_parses_as_ignore_rules() {
+        for preset in [
+            IgnorePreset::Vcs,
+            IgnorePreset::Os,
+            IgnorePreset::Editor,
+            IgnorePreset::Rust,
+            IgnorePreset::Node,
+        ] {
+            let mut spec = Spec::parse("allow ./src/\n", &MatchSettings::default()).unwrap();
+            spec.extend(&preset_source(preset), preset_rules(preset))
+                .unwrap();
+            assert!(spec.rules().len() > 1, "{preset:?}");
+        }
+    }
+}
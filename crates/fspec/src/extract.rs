@@ -0,0 +1,159 @@
+use fspec_core::{EntryKind, Report, Status};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// One catalog row: an allowed file and what the deciding rule captured.
+#[derive(Serialize)]
+struct Row<'a> {
+    path: &'a str,
+    rule_line: usize,
+    captures: &'a fspec_core::Captures,
+}
+
+fn rows(report: &Report) -> Vec<Row<'_>> {
+    report
+        .entries()
+        .filter_map(|(path, e)| match (e.status, e.kind, e.rule_line) {
+            (Status::Allowed, Some(EntryKind::File), Some(rule_line)) => Some(Row {
+                path,
+                rule_line,
+                captures: &e.captures,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// JSON Lines: one object per allowed file, `{"path", "rule_line", "captures"}`.
+pub fn render_jsonl(report: &Report) -> String {
+    let mut out = String::new();
+    for row in rows(report) {
+        // Serializing a string/int/string-map struct cannot fail.
+        out.push_str(&serde_json::to_string(&row).unwrap_or_default());
+        out.push('\n');
+    }
+    out
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+/// CSV with a `path,rule_line` prefix and one column per placeholder name seen
+/// in any row (sorted). Rows from rules that don't capture a name leave it empty.
+pub fn render_csv(report: &Report) -> String {
+    let rows = rows(report);
+    let names: BTreeSet<&str> = rows
+        .iter()
+        .flat_map(|r| r.captures.keys().map(String::as_str))
+        .collect();
+
+    let mut out = String::new();
+    let header: Vec<String> = ["path", "rule_line"]
+        .into_iter()
+        .chain(names.iter().copied())
+        .map(csv_field)
+        .collect();
+    out.push_str(&header.join(","));
+    out.push('\n');
+
+    for row in &rows {
+        let mut fields = vec![csv_field(row.path), row.rule_line.to_string()];
+        fields.extend(
+            names
+                .iter()
+                .map(|n| csv_field(row.captures.get(*n).map_or("", String::as_str))),
+        );
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fspec_core::ReportEntry;
+
+    fn entry(
+        status: Status,
+        kind: EntryKind,
+        rule_line: Option<usize>,
+        caps: &[(&str, &str)],
+    ) -> ReportEntry {
+        ReportEntry {
+            status,
+            kind: Some(kind),
+            rule_line,
+            captures: caps
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        }
+    }
+
+    fn sample_report() -> Report {
+        let mut report = Report::default();
+        report.set_entry(
+            "series/2001/Foo/season_01/Foo.s01e02.mkv",
+            entry(
+                Status::Allowed,
+                EntryKind::File,
+                Some(3),
+                &[("name", "Foo"), ("season", "01"), ("year", "2001")],
+            ),
+        );
+        report.set_entry(
+            "movies/a, b_1999.mp4",
+            entry(
+                Status::Allowed,
+                EntryKind::File,
+                Some(2),
+                &[("year", "1999")],
+            ),
+        );
+        report.set_entry(
+            "series/2001",
+            entry(Status::Allowed, EntryKind::Dir, None, &[]),
+        );
+        report.set_entry(
+            "tmp/x",
+            entry(Status::Ignored, EntryKind::File, Some(5), &[]),
+        );
+        report.set_entry(
+            "stray",
+            entry(Status::Unaccounted, EntryKind::File, None, &[]),
+        );
+        report
+    }
+
+    #[test]
+    fn jsonl_has_one_row_per_allowed_file() {
+        let out = render_jsonl(&sample_report());
+        let rows: Vec<serde_json::Value> = out
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1]["path"], "series/2001/Foo/season_01/Foo.s01e02.mkv");
+        assert_eq!(rows[1]["rule_line"], 3);
+        assert_eq!(rows[1]["captures"]["season"], "01");
+    }
+
+    #[test]
+    fn csv_has_a_column_per_placeholder_name() {
+        let out = render_csv(&sample_report());
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines[0], "path,rule_line,name,season,year");
+        assert_eq!(lines[1], "\"movies/a, b_1999.mp4\",2,,,1999");
+        assert_eq!(
+            lines[2],
+            "series/2001/Foo/season_01/Foo.s01e02.mkv,3,Foo,01,2001"
+        );
+        assert_eq!(lines.len(), 3);
+    }
+}
@@ -0,0 +1,7 @@
+//! The parts of the `fspec` command line that other tools share, so they resolve settings
+//! from flags and `fspec.toml` the same way: the language server uses [`config`] to match
+//! with what `fspec` would.
+
+pub mod args;
+pub mod config;
+pub mod policy;
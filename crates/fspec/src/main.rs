@@ -1,58 +1,262 @@
-mod args;
+mod baseline;
+mod extract;
+mod ndjson;
 mod render;
+mod schema;
 
-use crate::args::{Cli, LeafMode, SeverityArg};
+use fspec::{args, config, policy};
+
+use crate::args::{
+    Cli, Command, DiffArgs, DiffFormat, ExtractArgs, ExtractFormat, FmtArgs, LintArgs, OutputFormat,
+};
+use crate::baseline::Snapshot;
+use crate::config::Resolved;
+use crate::policy::{EXIT_IO_ERROR, EXIT_SPEC_ERROR, ExitPolicy, Tally};
 use clap::Parser;
-use fspec_core::{MatchSettings, Severity, check_tree, check_tree_with_spec};
+use fspec_core::{Error, Report, Severity, Spec, WalkVisitor, format_spec, lint_spec};
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
 fn main() -> ExitCode {
     let cli = Cli::parse();
 
-    let root: PathBuf = resolve_root(&cli);
+    match &cli.command {
+        Some(Command::Schema(args)) => {
+            if args.report {
+                println!("{}", schema::report_schema(args.report_version));
+            } else if args.ndjson {
+                println!("{}", schema::ndjson_schema());
+            }
+            return ExitCode::from(0);
+        }
+        Some(Command::Diff(args)) => return run_diff(args),
+        Some(Command::Extract(args)) => return run_extract(&cli, args),
+        Some(Command::Lint(args)) => return run_lint(&cli, args),
+        Some(Command::Fmt(args)) => return run_fmt(&cli, args),
+        None => {}
+    }
 
-    let settings = MatchSettings {
-        allow_file_or_dir_leaf: matches!(cli.leaf, LeafMode::Loose),
-        default_severity: match cli.severity {
-            SeverityArg::Info => Severity::Info,
-            SeverityArg::Warning => Severity::Warning,
-            SeverityArg::Error => Severity::Error,
-        },
-    };
+    let root: PathBuf = resolve_root(cli.root.as_ref(), cli.path.as_ref());
+    let resolved = resolve_config(&cli, &root);
+    let policy = resolved.policy;
+    let spec = load_spec(&resolved);
+    if matches!(resolved.format, OutputFormat::Ndjson) {
+        return run_ndjson(&root, &cli, &spec, &policy);
+    }
+    let mut report = run_check(&root, &spec);
 
-    let report = (if let Some(spec) = cli.spec.as_deref() {
-        check_tree_with_spec(&root, Some(spec), &settings)
-    } else {
-        check_tree(&root, &settings)
-    })
-    .unwrap_or_else(|e| {
+    // Before the baseline, so known unused rules are suppressed like any other finding.
+    if policy.fail_on_unused_rules {
+        let decided = policy::decided_rule_lines(&report);
+        for d in policy::unused_rules(spec.rules(), &decided) {
+            report.push_diagnostic(d);
+        }
+    }
+
+    if let Some(path) = cli.baseline.as_deref() {
+        let baseline = load_baseline(path);
+        let suppressed = baseline::apply_baseline(&mut report, &baseline);
+        if cli.verbosity > 0 {
+            eprintln!("baseline: {suppressed} known finding(s) suppressed");
+        }
+    }
+
+    let out = render::render(
+        &report,
+        &resolved.settings,
+        resolved.format,
+        resolved.report_version,
+        &policy,
+        cli.verbosity,
+        cli.quiet,
+    );
+
+    println!("{}", out);
+
+    exit_for(&policy, &Tally::from_report(&report), cli.verbosity)
+}
+
+/// The exit code `policy` gives `tally`, saying why it fails with `-v`.
+fn exit_for(policy: &ExitPolicy, tally: &Tally, verbosity: u8) -> ExitCode {
+    let failure = policy.failure(tally);
+    if let Some(why) = failure.as_deref().filter(|_| verbosity > 0) {
+        eprintln!("fail: {why}");
+    }
+    ExitCode::from(policy.exit_code(tally))
+}
+
+/// Layer the command line over `fspec.toml` (`--config`, or the nearest one upward from
+/// `root`), exiting if the config can't be read.
+fn resolve_config(cli: &Cli, root: &Path) -> Resolved {
+    let resolved = config::resolve_run(cli, root).unwrap_or_else(|e| {
         eprintln!("{e}");
-        std::process::exit(2);
+        std::process::exit(EXIT_SPEC_ERROR.into());
     });
+    if let Some(path) = resolved.config_path.as_ref().filter(|_| cli.verbosity > 0) {
+        eprintln!("config: {}", path.display());
+    }
+    resolved
+}
 
-    let out = render::render(&report, &settings, cli.format, cli.verbosity, cli.quiet);
+/// Print `e` and exit with the code for its kind of error.
+fn exit_on_error(e: Error) -> ! {
+    eprintln!("{e}");
+    std::process::exit(policy::error_exit_code(&e).into());
+}
 
-    println!("{}", out);
+/// Load the configured spec with its ignore presets, exiting on errors.
+fn load_spec(resolved: &Resolved) -> Spec {
+    config::load_spec(resolved, &resolved.spec_path).unwrap_or_else(|e| exit_on_error(e))
+}
 
-    // Current “finding” heuristic: any unaccounted path => fail.
-    // (In the future: incorporate per-item severity + threshold logic.)
-    if report.unaccounted_paths().is_empty() {
-        ExitCode::from(0)
+/// Check `root` against `spec`, exiting on errors.
+fn run_check(root: &Path, spec: &Spec) -> Report {
+    spec.check(root).unwrap_or_else(|e| exit_on_error(e))
+}
+
+fn load_baseline(path: &Path) -> Snapshot {
+    Snapshot::load(path).unwrap_or_else(|e| {
+        eprintln!("baseline: {e}");
+        std::process::exit(EXIT_SPEC_ERROR.into());
+    })
+}
+
+/// `--format ndjson`: print findings as the walk decides them instead of building a report.
+fn run_ndjson(root: &Path, cli: &Cli, spec: &Spec, policy: &ExitPolicy) -> ExitCode {
+    let baseline = cli.baseline.as_deref().map(load_baseline);
+
+    let mut writer =
+        ndjson::NdjsonWriter::new(std::io::stdout().lock(), baseline.as_ref(), *policy);
+    if let Err(e) = spec.walk(root, &mut writer) {
+        exit_on_error(e);
+    }
+    if policy.fail_on_unused_rules {
+        for d in policy::unused_rules(spec.rules(), writer.decided_rule_lines()) {
+            writer.diagnostic(d);
+        }
+    }
+    match writer.finish() {
+        Ok((tally, suppressed)) => {
+            if cli.verbosity > 0 && baseline.is_some() {
+                eprintln!("baseline: {suppressed} known finding(s) suppressed");
+            }
+            exit_for(policy, &tally, cli.verbosity)
+        }
+        Err(e) => {
+            eprintln!("cannot write output: {e}");
+            ExitCode::from(EXIT_IO_ERROR)
+        }
+    }
+}
+
+fn run_extract(cli: &Cli, args: &ExtractArgs) -> ExitCode {
+    let root = resolve_root(cli.root.as_ref(), args.path.as_ref());
+    let report = run_check(&root, &load_spec(&resolve_config(cli, &root)));
+
+    let out = match args.format {
+        ExtractFormat::Jsonl => extract::render_jsonl(&report),
+        ExtractFormat::Csv => extract::render_csv(&report),
+    };
+    print!("{}", out);
+
+    ExitCode::from(0)
+}
+
+fn run_lint(cli: &Cli, args: &LintArgs) -> ExitCode {
+    let root = resolve_root(cli.root.as_ref(), args.path.as_ref());
+    let Resolved {
+        spec_path,
+        settings,
+        ..
+    } = resolve_config(cli, &root);
+    let diags = lint_spec(&spec_path, &settings).unwrap_or_else(|e| exit_on_error(e));
+
+    let out = match args.format {
+        DiffFormat::Human => render::render_lint_human(&diags, &spec_path, args.verbosity),
+        DiffFormat::Json => render::render_lint_json(&diags, &spec_path),
+    };
+    print!("{}", out);
+
+    // Informational findings never fail the lint.
+    if diags.iter().any(|d| d.severity > Severity::Info) {
+        ExitCode::from(1)
     } else {
+        ExitCode::from(0)
+    }
+}
+
+fn run_fmt(cli: &Cli, args: &FmtArgs) -> ExitCode {
+    let root = resolve_root(cli.root.as_ref(), args.path.as_ref());
+    let Resolved {
+        spec_path,
+        settings,
+        ..
+    } = resolve_config(cli, &root);
+    let formatted = format_spec(&spec_path, &settings).unwrap_or_else(|e| exit_on_error(e));
+    // `format_spec` just read it, so a failure here is a race; treat it as unformatted.
+    let current = std::fs::read_to_string(&spec_path).unwrap_or_default();
+    if current == formatted {
+        return ExitCode::from(0);
+    }
+
+    if args.check {
+        println!("would reformat {}", spec_path.display());
+        let mut pairs = current.lines().zip(formatted.lines());
+        match pairs.position(|(old, new)| old != new) {
+            Some(n) => {
+                let old = current.lines().nth(n).unwrap_or_default();
+                let new = formatted.lines().nth(n).unwrap_or_default();
+                println!(
+                    "  first difference at line {}:\n  - {}\n  + {}",
+                    n + 1,
+                    old,
+                    new
+                );
+            }
+            None => println!("  blank lines or the final newline differ"),
+        }
+        return ExitCode::from(1);
+    }
+    if let Err(e) = std::fs::write(&spec_path, formatted) {
+        eprintln!("cannot write {}: {e}", spec_path.display());
+        return ExitCode::from(EXIT_IO_ERROR);
+    }
+    ExitCode::from(0)
+}
+
+fn run_diff(args: &DiffArgs) -> ExitCode {
+    let (old, new) = match (Snapshot::load(&args.old), Snapshot::load(&args.new)) {
+        (Ok(old), Ok(new)) => (old, new),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("{e}");
+            return ExitCode::from(EXIT_SPEC_ERROR);
+        }
+    };
+
+    let d = baseline::diff(&old, &new);
+    let out = match args.format {
+        DiffFormat::Human => baseline::render_diff_human(&d),
+        DiffFormat::Json => baseline::render_diff_json(&d),
+    };
+    println!("{}", out);
+
+    if d.has_new_findings() {
         ExitCode::from(1)
+    } else {
+        ExitCode::from(0)
     }
 }
 
-fn resolve_root(cli: &Cli) -> PathBuf {
+fn resolve_root(root: Option<&PathBuf>, path: Option<&PathBuf>) -> PathBuf {
     // Priority:
     //   1) --root
     //   2) positional PATH
     //   3) "."
-    if let Some(r) = &cli.root {
+    // `fspec.toml` is then looked for from the resolved root upward.
+    if let Some(r) = root {
         return r.clone();
     }
-    if let Some(p) = &cli.path {
+    if let Some(p) = path {
         return p.clone();
     }
     Path::new(".").to_path_buf()
@@ -0,0 +1,260 @@
+use crate::baseline::{Snapshot, diag_key};
+use crate::policy::{ExitPolicy, Tally};
+use fspec_core::{Diagnostic, EntryKind, Severity, Status, WalkEntry, WalkVisitor};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+pub const SCHEMA_VERSION_NDJSON: &str = "fspec.ndjson.v1";
+const TOOL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// One line of `--format ndjson` output.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record<'a> {
+    Unaccounted {
+        path: &'a str,
+        kind: &'static str,
+    },
+    Diagnostic {
+        code: &'a str,
+        severity: &'static str,
+        path: &'a str,
+        message: &'a str,
+        rule_lines: &'a [usize],
+    },
+    Summary {
+        schema_version: &'static str,
+        tool_version: &'static str,
+        ok: bool,
+        #[serde(flatten)]
+        counts: &'a Counts,
+        unaccounted_count: usize,
+        error_count: usize,
+        suppressed: usize,
+    },
+}
+
+#[derive(Debug, Default, Serialize)]
+struct Counts {
+    allowed_files: usize,
+    allowed_dirs: usize,
+    ignored_files: usize,
+    ignored_dirs: usize,
+    unaccounted_files: usize,
+    unaccounted_dirs: usize,
+}
+
+/// Writes each finding of a walk as a JSON line as soon as it's decided: unaccounted paths
+/// and diagnostics, minus anything already in the baseline. Only counts are kept, so memory
+/// doesn't grow with the tree. [`NdjsonWriter::finish`] writes the closing summary record.
+pub struct NdjsonWriter<'b, W: Write> {
+    out: W,
+    baseline: Option<&'b Snapshot>,
+    counts: Counts,
+    /// Findings written, for the exit policy.
+    tally: Tally,
+    /// Decides the summary's `ok`, as it does the exit code.
+    policy: ExitPolicy,
+    /// Lines of the rules that decided some path, for `--fail-on-unused-rules`.
+    decided: BTreeSet<usize>,
+    suppressed: usize,
+    /// The first write error; nothing more is written after it.
+    error: Option<io::Error>,
+}
+
+impl<'b, W: Write> NdjsonWriter<'b, W> {
+    pub fn new(out: W, baseline: Option<&'b Snapshot>, policy: ExitPolicy) -> Self {
+        NdjsonWriter {
+            out,
+            baseline,
+            policy,
+            counts: Counts::default(),
+            tally: Tally::default(),
+            decided: BTreeSet::new(),
+            suppressed: 0,
+            error: None,
+        }
+    }
+
+    fn write(&mut self, record: &Record<'_>) {
+        if self.error.is_some() {
+            return;
+        }
+        // Serializing strings, numbers and flags cannot fail.
+        let line = serde_json::to_string(record).unwrap_or_default();
+        if let Err(e) = writeln!(self.out, "{line}").and_then(|_| self.out.flush()) {
+            self.error = Some(e);
+        }
+    }
+
+    /// Lines of the rules that decided some path so far.
+    pub fn decided_rule_lines(&self) -> &BTreeSet<usize> {
+        &self.decided
+    }
+
+    /// Write the summary record. Returns the findings written and how many the baseline
+    /// suppressed, or the first error writing any record.
+    pub fn finish(mut self) -> io::Result<(Tally, usize)> {
+        let counts = std::mem::take(&mut self.counts);
+        self.write(&Record::Summary {
+            schema_version: SCHEMA_VERSION_NDJSON,
+            tool_version: TOOL_VERSION,
+            ok: self.policy.failure(&self.tally).is_none(),
+            counts: &counts,
+            unaccounted_count: self.tally.unaccounted,
+            error_count: self.tally.errors(),
+            suppressed: self.suppressed,
+        });
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok((self.tally, self.suppressed)),
+        }
+    }
+}
+
+impl<W: Write> WalkVisitor for NdjsonWriter<'_, W> {
+    fn entry(&mut self, e: &WalkEntry<'_>) {
+        let c = &mut self.counts;
+        let count = match (e.status, e.kind) {
+            (Status::Allowed, EntryKind::File) => &mut c.allowed_files,
+            (Status::Allowed, EntryKind::Dir) => &mut c.allowed_dirs,
+            (Status::Ignored, EntryKind::File) => &mut c.ignored_files,
+            (Status::Ignored, EntryKind::Dir) => &mut c.ignored_dirs,
+            (Status::Unaccounted, EntryKind::File) => &mut c.unaccounted_files,
+            (Status::Unaccounted, EntryKind::Dir) => &mut c.unaccounted_dirs,
+        };
+        *count += 1;
+        if let Some(by) = e.decided_by {
+            self.decided.insert(by.rule_line);
+        }
+        if e.status != Status::Unaccounted {
+            return;
+        }
+
+        let path = e.path_text();
+        if self.baseline.is_some_and(|b| b.unaccounted.contains(&path)) {
+            self.suppressed += 1;
+            return;
+        }
+        self.tally.unaccounted += 1;
+        let kind = match e.kind {
+            EntryKind::File => "file",
+            EntryKind::Dir => "dir",
+        };
+        self.write(&Record::Unaccounted { path: &path, kind });
+    }
+
+    fn diagnostic(&mut self, d: Diagnostic) {
+        let key = diag_key(d.code, &d.path, &d.message);
+        if self.baseline.is_some_and(|b| b.diagnostics.contains(&key)) {
+            self.suppressed += 1;
+            return;
+        }
+        self.tally.add_diagnostic(&d);
+        let severity = match d.severity {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        self.write(&Record::Diagnostic {
+            code: d.code,
+            severity,
+            path: &d.path,
+            message: &d.message,
+            rule_lines: &d.rule_lines,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn diag(code: &'static str, severity: Severity, path: &str) -> Diagnostic {
+        Diagnostic {
+            code,
+            severity,
+            path: path.into(),
+            message: format!("{code}: {path}"),
+            rule_lines: vec![3],
+        }
+    }
+
+    fn entry(path: &Path, kind: EntryKind, status: Status) -> WalkEntry<'_> {
+        WalkEntry {
+            path,
+            kind,
+            status,
+            decided_by: None,
+        }
+    }
+
+    #[test]
+    fn writes_findings_then_a_summary() {
+        let mut buf = Vec::new();
+        let mut w = NdjsonWriter::new(&mut buf, None, ExitPolicy::default());
+        w.entry(&entry(
+            Path::new("src/a.rs"),
+            EntryKind::File,
+            Status::Allowed,
+        ));
+        w.diagnostic(diag("denied", Severity::Error, "junk/x.exe"));
+        w.entry(&entry(
+            Path::new("junk/x.exe"),
+            EntryKind::File,
+            Status::Unaccounted,
+        ));
+        w.entry(&entry(
+            Path::new("junk"),
+            EntryKind::Dir,
+            Status::Unaccounted,
+        ));
+        let (tally, suppressed) = w.finish().unwrap();
+        assert_eq!((tally.unaccounted, tally.errors(), suppressed), (2, 1, 0));
+
+        let lines: Vec<serde_json::Value> = String::from_utf8(buf)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["type"], "diagnostic");
+        assert_eq!(lines[0]["rule_lines"], serde_json::json!([3]));
+        assert_eq!(lines[1]["type"], "unaccounted");
+        assert_eq!(lines[1]["path"], "junk/x.exe");
+        assert_eq!(lines[2]["kind"], "dir");
+        let summary = &lines[3];
+        assert_eq!(summary["type"], "summary");
+        assert_eq!(summary["schema_version"], SCHEMA_VERSION_NDJSON);
+        assert_eq!(summary["ok"], false);
+        assert_eq!(summary["allowed_files"], 1);
+        assert_eq!(summary["unaccounted_count"], 2);
+        assert_eq!(summary["error_count"], 1);
+    }
+
+    #[test]
+    fn baselined_findings_are_counted_not_written() {
+        let baseline = Snapshot {
+            unaccounted: ["old.txt".to_string()].into(),
+            diagnostics: [("denied".to_string(), "old.exe".to_string())].into(),
+            ..Snapshot::default()
+        };
+        let mut buf = Vec::new();
+        let mut w = NdjsonWriter::new(&mut buf, Some(&baseline), ExitPolicy::default());
+        w.entry(&entry(
+            Path::new("old.txt"),
+            EntryKind::File,
+            Status::Unaccounted,
+        ));
+        w.diagnostic(diag("denied", Severity::Error, "old.exe"));
+        w.diagnostic(diag("ambiguous_match", Severity::Info, "new.txt"));
+        let (tally, suppressed) = w.finish().unwrap();
+        assert_eq!((tally.unaccounted, tally.errors(), suppressed), (0, 0, 2));
+
+        let out = String::from_utf8(buf).unwrap();
+        assert_eq!(out.lines().count(), 2);
+        assert!(out.contains("\"suppressed\":2"), "{out}");
+    }
+}
@@ -0,0 +1,232 @@
+use fspec_core::{Diagnostic, Error, Report, Rule, RuleKind, Severity};
+use std::collections::BTreeSet;
+
+/// No findings beyond what the exit policy tolerates.
+pub const EXIT_OK: u8 = 0;
+/// Findings the exit policy doesn't tolerate.
+pub const EXIT_FINDINGS: u8 = 1;
+/// The spec is missing or doesn't parse, or the input is otherwise invalid. Usage errors
+/// reported by the argument parser use this code too.
+pub const EXIT_SPEC_ERROR: u8 = 2;
+/// Reading the tree or a file, or writing output, failed.
+pub const EXIT_IO_ERROR: u8 = 3;
+
+const UNUSED_RULE: &str = "unused_rule";
+
+/// The exit code for an error from `fspec-core`.
+pub fn error_exit_code(e: &Error) -> u8 {
+    match e {
+        Error::Io { .. } => EXIT_IO_ERROR,
+        _ => EXIT_SPEC_ERROR,
+    }
+}
+
+/// What a check found, as far as the exit policy is concerned.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Tally {
+    pub unaccounted: usize,
+    /// Diagnostics per severity: info, warning, error. `unused_rule` isn't counted here.
+    pub by_severity: [usize; 3],
+    pub unused_rules: usize,
+}
+
+impl Tally {
+    pub fn from_report(report: &Report) -> Tally {
+        let mut tally = Tally {
+            unaccounted: report.unaccounted_paths().len(),
+            ..Tally::default()
+        };
+        for d in report.diagnostics() {
+            tally.add_diagnostic(d);
+        }
+        tally
+    }
+
+    pub fn add_diagnostic(&mut self, d: &Diagnostic) {
+        if d.code == UNUSED_RULE {
+            self.unused_rules += 1;
+        } else {
+            self.by_severity[d.severity as usize] += 1;
+        }
+    }
+
+    pub fn errors(&self) -> usize {
+        self.by_severity[Severity::Error as usize]
+    }
+
+    /// Diagnostics at or above `severity`.
+    fn at_least(&self, severity: Severity) -> usize {
+        self.by_severity[severity as usize..].iter().sum()
+    }
+}
+
+/// When a check fails: `--max-unaccounted`, `--fail-on` and `--fail-on-unused-rules`.
+#[derive(Debug, Clone, Copy)]
+pub struct ExitPolicy {
+    /// Unaccounted paths tolerated before failing.
+    pub max_unaccounted: usize,
+    /// Diagnostics at or above this severity fail the check.
+    pub fail_on: Severity,
+    /// Allow and ignore rules that decided no path fail the check.
+    pub fail_on_unused_rules: bool,
+}
+
+impl Default for ExitPolicy {
+    fn default() -> Self {
+        ExitPolicy {
+            max_unaccounted: 0,
+            fail_on: Severity::Error,
+            fail_on_unused_rules: false,
+        }
+    }
+}
+
+impl ExitPolicy {
+    /// Why a check with `tally` fails, or `None` if it passes.
+    pub fn failure(&self, tally: &Tally) -> Option<String> {
+        let mut reasons = Vec::new();
+        if tally.unaccounted > self.max_unaccounted {
+            reasons.push(format!(
+                "{} unaccounted path(s), {} tolerated",
+                tally.unaccounted, self.max_unaccounted
+            ));
+        }
+        let failing = tally.at_least(self.fail_on);
+        if failing > 0 {
+            let at = format!("{:?}", self.fail_on).to_lowercase();
+            reasons.push(format!("{failing} diagnostic(s) at {at} or above"));
+        }
+        if self.fail_on_unused_rules && tally.unused_rules > 0 {
+            reasons.push(format!("{} unused rule(s)", tally.unused_rules));
+        }
+        (!reasons.is_empty()).then(|| reasons.join("; "))
+    }
+
+    pub fn exit_code(&self, tally: &Tally) -> u8 {
+        match self.failure(tally) {
+            Some(_) => EXIT_FINDINGS,
+            None => EXIT_OK,
+        }
+    }
+}
+
+/// An `unused_rule` warning for each `allow` or `ignore` rule whose line isn't in `decided`
+/// (the rule lines that decided some path). `deny` rules are expected to match nothing, and
+/// rules from outside the spec file (presets) are left out as the user didn't write them.
+///
+/// The message names the rule but not its line, so a baseline still recognizes the finding
+/// after lines above it are added or removed.
+pub fn unused_rules(rules: &[Rule], decided: &BTreeSet<usize>) -> Vec<Diagnostic> {
+    rules
+        .iter()
+        .filter(|r| r.source.is_none() && r.kind != RuleKind::Deny && !decided.contains(&r.line))
+        .map(|r| Diagnostic {
+            code: UNUSED_RULE,
+            severity: Severity::Warning,
+            path: String::new(),
+            message: format!("rule decided no path: {}", r),
+            rule_lines: vec![r.line],
+        })
+        .collect()
+}
+
+/// The rule lines that decided some path in `report`.
+pub fn decided_rule_lines(report: &Report) -> BTreeSet<usize> {
+    report.entries().filter_map(|(_, e)| e.rule_line).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fspec_core::{MatchSettings, Spec, Status};
+
+    fn diag(code: &'static str, severity: Severity) -> Diagnostic {
+        Diagnostic {
+            code,
+            severity,
+            path: "a".into(),
+            message: String::new(),
+            rule_lines: vec![],
+        }
+    }
+
+    #[test]
+    fn default_policy_fails_on_unaccounted_paths_and_errors_only() {
+        let policy = ExitPolicy::default();
+        let mut tally = Tally::default();
+        tally.add_diagnostic(&diag("ambiguous_match", Severity::Warning));
+        tally.add_diagnostic(&diag(UNUSED_RULE, Severity::Warning));
+        assert_eq!(policy.exit_code(&tally), EXIT_OK);
+
+        tally.unaccounted = 1;
+        assert_eq!(
+            policy.failure(&tally).as_deref(),
+            Some("1 unaccounted path(s), 0 tolerated")
+        );
+
+        tally.unaccounted = 0;
+        tally.add_diagnostic(&diag("denied", Severity::Error));
+        assert_eq!(policy.exit_code(&tally), EXIT_FINDINGS);
+    }
+
+    #[test]
+    fn thresholds_tolerate_some_findings_and_tighten_others() {
+        let mut tally = Tally {
+            unaccounted: 3,
+            ..Tally::default()
+        };
+        tally.add_diagnostic(&diag("ambiguous_match", Severity::Warning));
+        tally.add_diagnostic(&diag(UNUSED_RULE, Severity::Warning));
+
+        let lenient = ExitPolicy {
+            max_unaccounted: 3,
+            ..ExitPolicy::default()
+        };
+        assert_eq!(lenient.exit_code(&tally), EXIT_OK);
+
+        let strict = ExitPolicy {
+            max_unaccounted: 3,
+            fail_on: Severity::Warning,
+            fail_on_unused_rules: true,
+        };
+        assert_eq!(
+            strict.failure(&tally).as_deref(),
+            Some("1 diagnostic(s) at warning or above; 1 unused rule(s)")
+        );
+    }
+
+    #[test]
+    fn errors_map_to_distinct_exit_codes() {
+        let io = Error::Io {
+            path: "x".into(),
+            source: std::io::Error::other("boom"),
+        };
+        let semantic = Error::Semantic { msg: "no".into() };
+        assert_eq!(error_exit_code(&io), EXIT_IO_ERROR);
+        assert_eq!(error_exit_code(&semantic), EXIT_SPEC_ERROR);
+    }
+
+    #[test]
+    fn unused_rules_skip_deny_rules_and_rules_that_decided_something() {
+        let spec = Spec::parse(
+            "allow ./src/*.rs\nignore ./target/\ndeny *.exe\nallow ./docs/\n",
+            &MatchSettings::default(),
+        )
+        .unwrap();
+        let mut report = Report::default();
+        report.set_entry(
+            "src/main.rs",
+            fspec_core::ReportEntry {
+                status: Status::Allowed,
+                kind: None,
+                rule_line: Some(1),
+                captures: Default::default(),
+            },
+        );
+
+        let unused = unused_rules(spec.rules(), &decided_rule_lines(&report));
+        let lines: Vec<_> = unused.iter().map(|d| d.rule_lines[0]).collect();
+        assert_eq!(lines, [2, 4]);
+        assert_eq!(unused[0].message, "rule decided no path: ignore ./target/");
+    }
+}
@@ -1,8 +1,11 @@
-use crate::args::OutputFormat;
-use fspec_core::{MatchSettings, Report, Severity};
+use crate::args::{OutputFormat, ReportVersion};
+use crate::policy::{ExitPolicy, Tally};
+use fspec_core::{Captures, Diagnostic, EntryKind, MatchSettings, Report, Severity, Status};
 use serde::Serialize;
+use std::path::Path;
 
 const SCHEMA_VERSION: &str = "fspec.report.v1";
+const SCHEMA_VERSION_V2: &str = "fspec.report.v2";
 const TOOL_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 // Until the report schema stabilizes, we probably don't want to directly deserialize the
@@ -33,6 +36,51 @@ struct JsonSummary {
     // you can add more later without breaking humans
 }
 
+#[derive(Serialize)]
+struct JsonOutV2<'a> {
+    schema_version: &'static str,
+    tool_version: &'static str,
+    ok: bool,
+    entries: Vec<JsonEntry<'a>>,
+    diagnostics: Vec<JsonDiag<'a>>,
+    summary: JsonSummaryV2,
+}
+
+#[derive(Serialize)]
+struct JsonEntry<'a> {
+    path: &'a str,
+    kind: Option<&'static str>,
+    status: &'static str,
+    rule_line: Option<usize>,
+    captures: &'a Captures,
+}
+
+#[derive(Serialize)]
+struct JsonSummaryV2 {
+    allowed_files: usize,
+    allowed_dirs: usize,
+    ignored_files: usize,
+    ignored_dirs: usize,
+    unaccounted_files: usize,
+    unaccounted_dirs: usize,
+    unaccounted_count: usize,
+}
+
+fn status_to_str(status: Status) -> &'static str {
+    match status {
+        Status::Allowed => "allowed",
+        Status::Ignored => "ignored",
+        Status::Unaccounted => "unaccounted",
+    }
+}
+
+fn kind_to_str(kind: EntryKind) -> &'static str {
+    match kind {
+        EntryKind::File => "file",
+        EntryKind::Dir => "dir",
+    }
+}
+
 fn severity_to_string(sev: Severity) -> String {
     match sev {
         Severity::Info => "info",
@@ -42,25 +90,16 @@ fn severity_to_string(sev: Severity) -> String {
     .to_string()
 }
 
-pub fn render_json(report: &Report, _settings: &MatchSettings) -> String {
+/// `ok` is whether the check passes under `policy`, as the exit code says.
+pub fn render_json(report: &Report, _settings: &MatchSettings, policy: &ExitPolicy) -> String {
     let un = report.unaccounted_paths();
-    let diags = report.diagnostics();
 
     let out = JsonOut {
         schema_version: SCHEMA_VERSION,
         tool_version: TOOL_VERSION,
-        ok: un.is_empty(),
+        ok: passes(report, policy),
         unaccounted: un.clone(),
-        diagnostics: diags
-            .iter()
-            .map(|d| JsonDiag {
-                code: d.code,
-                severity: severity_to_string(d.severity),
-                path: d.path.as_str(),
-                message: d.message.as_str(),
-                rule_lines: &d.rule_lines,
-            })
-            .collect(),
+        diagnostics: json_diagnostics(report),
         summary: JsonSummary {
             unaccounted_count: un.len(),
         },
@@ -70,6 +109,59 @@ pub fn render_json(report: &Report, _settings: &MatchSettings) -> String {
     serde_json::to_string_pretty(&out).unwrap_or_else(|_| "{\"ok\":false}".to_string())
 }
 
+/// Whether `report` passes `policy`: the `ok` of JSON reports.
+fn passes(report: &Report, policy: &ExitPolicy) -> bool {
+    policy.failure(&Tally::from_report(report)).is_none()
+}
+
+fn json_diagnostics(report: &Report) -> Vec<JsonDiag<'_>> {
+    report.diagnostics().iter().map(json_diag).collect()
+}
+
+fn json_diag(d: &Diagnostic) -> JsonDiag<'_> {
+    JsonDiag {
+        code: d.code,
+        severity: severity_to_string(d.severity),
+        path: d.path.as_str(),
+        message: d.message.as_str(),
+        rule_lines: &d.rule_lines,
+    }
+}
+
+/// `fspec.report.v2`: every walked path with its status, kind, deciding rule and captures.
+pub fn render_json_v2(report: &Report, _settings: &MatchSettings, policy: &ExitPolicy) -> String {
+    let summary = report.summary();
+    let unaccounted_count = report.unaccounted_paths().len();
+
+    let out = JsonOutV2 {
+        schema_version: SCHEMA_VERSION_V2,
+        tool_version: TOOL_VERSION,
+        ok: passes(report, policy),
+        entries: report
+            .entries()
+            .map(|(path, e)| JsonEntry {
+                path,
+                kind: e.kind.map(kind_to_str),
+                status: status_to_str(e.status),
+                rule_line: e.rule_line,
+                captures: &e.captures,
+            })
+            .collect(),
+        diagnostics: json_diagnostics(report),
+        summary: JsonSummaryV2 {
+            allowed_files: summary.allowed_files,
+            allowed_dirs: summary.allowed_dirs,
+            ignored_files: summary.ignored_files,
+            ignored_dirs: summary.ignored_dirs,
+            unaccounted_files: summary.unaccounted_files,
+            unaccounted_dirs: summary.unaccounted_dirs,
+            unaccounted_count,
+        },
+    };
+
+    serde_json::to_string_pretty(&out).unwrap_or_else(|_| "{\"ok\":false}".to_string())
+}
+
 pub fn render_human(
     report: &Report,
     settings: &MatchSettings,
@@ -95,23 +187,34 @@ pub fn render_human(
         return out;
     }
 
-    if un.is_empty() {
+    if un.is_empty() && report.diagnostics().is_empty() {
         out.push_str("OK: no unaccounted paths\n");
         return out;
     }
 
     // For now, all “findings” are printed using settings.default_severity,
     // because Report doesn’t yet attach severities per-path.
-    let sev_label = match settings.default_severity {
-        Severity::Info => "INFO",
-        Severity::Warning => "WARNING",
-        Severity::Error => "ERROR",
-    };
+    let sev_label = severity_label(settings.default_severity);
 
     for p in un {
         out.push_str(&format!("{sev_label} unaccounted: {p}\n"));
     }
 
+    for d in report.diagnostics() {
+        // A diagnostic that isn't about a path (`unused_rule`) is about a spec line.
+        let line = match d.rule_lines.first() {
+            Some(l) if d.path.is_empty() => format!(" (line {l})"),
+            _ => String::new(),
+        };
+        out.push_str(&format!(
+            "{} {}: {}{}\n",
+            severity_label(d.severity),
+            d.code,
+            d.message,
+            line
+        ));
+    }
+
     if verbosity > 0 {
         out.push_str(&format!(
             "summary: unaccounted={}\n",
@@ -122,15 +225,424 @@ pub fn render_human(
     out
 }
 
+/// `fspec lint` findings, one per line as `SPEC:LINE: SEVERITY code: message`. Informational
+/// findings are only shown with `-v`.
+pub fn render_lint_human(diags: &[Diagnostic], spec_path: &Path, verbosity: u8) -> String {
+    let mut out = String::new();
+    let shown: Vec<&Diagnostic> = diags
+        .iter()
+        .filter(|d| verbosity > 0 || d.severity > Severity::Info)
+        .collect();
+    if shown.is_empty() {
+        out.push_str(&format!(
+            "OK: no lint findings in {}\n",
+            spec_path.display()
+        ));
+        return out;
+    }
+    for d in shown {
+        let line = d
+            .rule_lines
+            .first()
+            .map(|l| format!(":{l}"))
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "{}{}: {} {}: {}\n",
+            spec_path.display(),
+            line,
+            severity_label(d.severity),
+            d.code,
+            d.message
+        ));
+    }
+    out
+}
+
+/// `fspec lint --format json`: `{"spec", "ok", "diagnostics"}`, with diagnostics shaped as in
+/// the report.
+pub fn render_lint_json(diags: &[Diagnostic], spec_path: &Path) -> String {
+    #[derive(Serialize)]
+    struct LintOut<'a> {
+        tool_version: &'static str,
+        spec: String,
+        ok: bool,
+        diagnostics: Vec<JsonDiag<'a>>,
+    }
+    let out = LintOut {
+        tool_version: TOOL_VERSION,
+        spec: spec_path.display().to_string(),
+        ok: diags.iter().all(|d| d.severity == Severity::Info),
+        diagnostics: diags.iter().map(json_diag).collect(),
+    };
+    serde_json::to_string_pretty(&out).unwrap_or_else(|_| "{\"ok\":false}".to_string())
+}
+
+fn severity_label(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Info => "INFO",
+        Severity::Warning => "WARNING",
+        Severity::Error => "ERROR",
+    }
+}
+
+/// A single reportable item, flattened for the CI-oriented renderers.
+///
+/// Unaccounted paths don't carry their own severity yet, so they use
+/// `settings.default_severity`; diagnostics keep the severity they were raised with.
+struct Finding<'a> {
+    code: &'a str,
+    severity: Severity,
+    path: &'a str,
+    message: String,
+}
+
+fn collect_findings<'a>(report: &'a Report, settings: &MatchSettings) -> Vec<Finding<'a>> {
+    let mut findings: Vec<Finding<'a>> = report
+        .unaccounted_paths()
+        .into_iter()
+        .map(|p| Finding {
+            code: "unaccounted",
+            severity: settings.default_severity,
+            path: p,
+            message: format!("unaccounted path: {p}"),
+        })
+        .collect();
+
+    findings.extend(report.diagnostics().iter().map(|d| Finding {
+        code: d.code,
+        severity: d.severity,
+        path: d.path.as_str(),
+        message: if d.rule_lines.is_empty() {
+            d.message.clone()
+        } else {
+            format!("{} (rule lines: {:?})", d.message, d.rule_lines)
+        },
+    }));
+
+    findings
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// JUnit XML: one `<testcase>` per finding.
+///
+/// Warnings and errors become `<failure>`s; info findings are recorded as passing
+/// test cases with the message in `<system-out>` so they stay visible without failing CI.
+pub fn render_junit(report: &Report, settings: &MatchSettings) -> String {
+    let findings = collect_findings(report, settings);
+    let failures = findings
+        .iter()
+        .filter(|f| f.severity >= Severity::Warning)
+        .count();
+    // An empty suite renders oddly in most CI UIs, so emit a single passing case instead.
+    let tests = findings.len().max(1);
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"fspec\" tests=\"{tests}\" failures=\"{failures}\" errors=\"0\">\n"
+    ));
+    out.push_str(&format!(
+        "  <testsuite name=\"fspec\" tests=\"{tests}\" failures=\"{failures}\" errors=\"0\" skipped=\"0\">\n"
+    ));
+
+    if findings.is_empty() {
+        out.push_str("    <testcase classname=\"fspec\" name=\"no unaccounted paths\"/>\n");
+    }
+
+    for f in &findings {
+        out.push_str(&format!(
+            "    <testcase classname=\"fspec.{}\" name=\"{}\">\n",
+            xml_escape(f.code),
+            xml_escape(f.path)
+        ));
+        if f.severity >= Severity::Warning {
+            out.push_str(&format!(
+                "      <failure type=\"{}\" message=\"{}\">{}: {}</failure>\n",
+                severity_to_string(f.severity),
+                xml_escape(&f.message),
+                severity_to_string(f.severity),
+                xml_escape(&f.message)
+            ));
+        } else {
+            out.push_str(&format!(
+                "      <system-out>{}: {}</system-out>\n",
+                severity_to_string(f.severity),
+                xml_escape(&f.message)
+            ));
+        }
+        out.push_str("    </testcase>\n");
+    }
+
+    out.push_str("  </testsuite>\n");
+    out.push_str("</testsuites>\n");
+    out
+}
+
+/// Escape the message part of a GitHub workflow command.
+fn github_escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a `key=value` property of a GitHub workflow command.
+fn github_escape_property(s: &str) -> String {
+    github_escape_data(s)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+/// GitHub Actions workflow commands, e.g. `::warning file=a/b.txt,title=fspec unaccounted::...`.
+pub fn render_github(report: &Report, settings: &MatchSettings) -> String {
+    let mut out = String::new();
+
+    for f in collect_findings(report, settings) {
+        let level = match f.severity {
+            Severity::Info => "notice",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        out.push_str(&format!(
+            "::{level} file={},title={}::{}\n",
+            github_escape_property(f.path),
+            github_escape_property(&format!("fspec {}", f.code)),
+            github_escape_data(&f.message)
+        ));
+    }
+
+    out
+}
+
+#[derive(Serialize)]
+struct CodeQualityIssue<'a> {
+    description: &'a str,
+    check_name: &'a str,
+    fingerprint: String,
+    severity: &'static str,
+    location: CodeQualityLocation<'a>,
+}
+
+#[derive(Serialize)]
+struct CodeQualityLocation<'a> {
+    path: &'a str,
+    lines: CodeQualityLines,
+}
+
+#[derive(Serialize)]
+struct CodeQualityLines {
+    begin: usize,
+}
+
+/// FNV-1a, used for GitLab fingerprints.
+///
+/// `DefaultHasher` is not guaranteed to be stable across Rust releases, and GitLab
+/// compares fingerprints between pipelines to decide which issues are new.
+fn fingerprint(parts: &[&str]) -> String {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for part in parts {
+        for b in part.bytes().chain(std::iter::once(0)) {
+            h ^= u64::from(b);
+            h = h.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    format!("{h:016x}")
+}
+
+/// GitLab Code Quality report: a JSON array of issues.
+pub fn render_gitlab_codequality(report: &Report, settings: &MatchSettings) -> String {
+    let findings = collect_findings(report, settings);
+
+    let issues: Vec<CodeQualityIssue<'_>> = findings
+        .iter()
+        .map(|f| CodeQualityIssue {
+            description: &f.message,
+            check_name: f.code,
+            fingerprint: fingerprint(&[f.code, f.path, &f.message]),
+            severity: match f.severity {
+                Severity::Info => "info",
+                Severity::Warning => "minor",
+                Severity::Error => "major",
+            },
+            location: CodeQualityLocation {
+                path: f.path,
+                lines: CodeQualityLines { begin: 1 },
+            },
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&issues).unwrap_or_else(|_| "[]".to_string())
+}
+
 pub fn render(
     report: &Report,
     settings: &MatchSettings,
     format: OutputFormat,
+    report_version: ReportVersion,
+    policy: &ExitPolicy,
     verbosity: u8,
     quiet: bool,
 ) -> String {
     match format {
         OutputFormat::Human => render_human(report, settings, verbosity, quiet),
-        OutputFormat::Json => render_json(report, settings),
+        OutputFormat::Json => match report_version {
+            ReportVersion::V1 => render_json(report, settings, policy),
+            ReportVersion::V2 => render_json_v2(report, settings, policy),
+        },
+        OutputFormat::Junit => render_junit(report, settings),
+        OutputFormat::Github => render_github(report, settings),
+        OutputFormat::GitlabCodequality => render_gitlab_codequality(report, settings),
+        OutputFormat::Ndjson => unreachable!("ndjson is streamed during the walk, see ndjson.rs"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fspec_core::{Diagnostic, ReportEntry};
+
+    fn sample_report() -> Report {
+        let mut report = Report::default();
+        report.set_status("src/main.rs", Status::Allowed);
+        report.set_status("junk/a&b.txt", Status::Unaccounted);
+        report.push_diagnostic(Diagnostic {
+            code: "ambiguous_match",
+            severity: Severity::Info,
+            path: "src/main.rs".into(),
+            message: "matched by several rules".into(),
+            rule_lines: vec![2, 3],
+        });
+        report
+    }
+
+    #[test]
+    fn json_v2_lists_every_entry_with_counts() {
+        let mut report = sample_report();
+        report.set_entry(
+            "movies/1999/a_1999.mkv",
+            ReportEntry {
+                status: Status::Allowed,
+                kind: Some(EntryKind::File),
+                rule_line: Some(4),
+                captures: [("year".to_string(), "1999".to_string())].into(),
+            },
+        );
+
+        let out = render_json_v2(&report, &MatchSettings::default(), &ExitPolicy::default());
+        let v: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(v["schema_version"], "fspec.report.v2");
+        assert_eq!(v["ok"], false);
+
+        let entries = v["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 3);
+        let movie = entries
+            .iter()
+            .find(|e| e["path"] == "movies/1999/a_1999.mkv")
+            .unwrap();
+        assert_eq!(movie["kind"], "file");
+        assert_eq!(movie["status"], "allowed");
+        assert_eq!(movie["rule_line"], 4);
+        assert_eq!(movie["captures"]["year"], "1999");
+
+        assert_eq!(v["summary"]["allowed_files"], 1);
+        assert_eq!(v["summary"]["unaccounted_count"], 1);
+    }
+
+    #[test]
+    fn human_lists_diagnostics_after_unaccounted_paths() {
+        let out = render_human(&sample_report(), &MatchSettings::default(), 0, false);
+        assert_eq!(
+            out,
+            "WARNING unaccounted: junk/a&b.txt\nINFO ambiguous_match: matched by several rules\n"
+        );
+    }
+
+    #[test]
+    fn lint_human_prefixes_spec_and_line_and_hides_info() {
+        let diag = |code, severity, line| Diagnostic {
+            code,
+            severity,
+            path: String::new(),
+            message: "m".to_string(),
+            rule_lines: vec![line],
+        };
+        let diags = [
+            diag("shadowed_rule", Severity::Warning, 3),
+            diag("single_use_placeholder", Severity::Info, 4),
+        ];
+        let spec = Path::new("a.fspec");
+        assert_eq!(
+            render_lint_human(&diags, spec, 0),
+            "a.fspec:3: WARNING shadowed_rule: m\n"
+        );
+        assert_eq!(render_lint_human(&diags, spec, 1).lines().count(), 2);
+        assert!(render_lint_human(&diags[1..], spec, 0).starts_with("OK:"));
+    }
+
+    #[test]
+    fn junit_has_one_failure_per_unaccounted_path() {
+        let out = render_junit(&sample_report(), &MatchSettings::default());
+        assert!(out.starts_with("<?xml"));
+        assert!(out.contains("tests=\"2\" failures=\"1\""));
+        assert!(out.contains("name=\"junk/a&amp;b.txt\""));
+        assert!(out.contains("<failure type=\"warning\""));
+        assert!(out.contains("<system-out>info: matched by several rules"));
+    }
+
+    #[test]
+    fn junit_without_findings_emits_a_passing_case() {
+        let out = render_junit(&Report::default(), &MatchSettings::default());
+        assert!(out.contains("tests=\"1\" failures=\"0\""));
+        assert!(!out.contains("<failure"));
+    }
+
+    #[test]
+    fn github_annotations_use_severity_levels() {
+        let settings = MatchSettings {
+            default_severity: Severity::Error,
+            ..MatchSettings::default()
+        };
+        let out = render_github(&sample_report(), &settings);
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(
+            lines[0],
+            "::error file=junk/a&b.txt,title=fspec unaccounted::unaccounted path: junk/a&b.txt"
+        );
+        assert!(lines[1].starts_with("::notice file=src/main.rs,title=fspec ambiguous_match::"));
+        assert!(lines[1].contains("matched by several rules (rule lines: [2, 3])"));
+    }
+
+    #[test]
+    fn github_escapes_properties_and_data() {
+        assert_eq!(github_escape_property("a,b:c%"), "a%2Cb%3Ac%25");
+        assert_eq!(github_escape_data("line1\nline2"), "line1%0Aline2");
+    }
+
+    #[test]
+    fn gitlab_codequality_is_a_json_array_with_stable_fingerprints() {
+        let out = render_gitlab_codequality(&sample_report(), &MatchSettings::default());
+        let v: serde_json::Value = serde_json::from_str(&out).unwrap();
+        let issues = v.as_array().unwrap();
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0]["check_name"], "unaccounted");
+        assert_eq!(issues[0]["severity"], "minor");
+        assert_eq!(issues[0]["location"]["path"], "junk/a&b.txt");
+        assert_eq!(issues[1]["severity"], "info");
+
+        let again = render_gitlab_codequality(&sample_report(), &MatchSettings::default());
+        assert_eq!(out, again);
     }
 }
@@ -0,0 +1,150 @@
+use crate::args::ReportVersion;
+
+// The schema documents live next to the crate so they can be published and
+// pinned by downstream consumers; the binary embeds them for `fspec schema`.
+const REPORT_V1: &str = include_str!("../schemas/report.v1.schema.json");
+const REPORT_V2: &str = include_str!("../schemas/report.v2.schema.json");
+const NDJSON_V1: &str = include_str!("../schemas/ndjson.v1.schema.json");
+
+/// JSON Schema document for a `--format json` report version.
+pub fn report_schema(version: ReportVersion) -> &'static str {
+    match version {
+        ReportVersion::V1 => REPORT_V1,
+        ReportVersion::V2 => REPORT_V2,
+    }
+}
+
+/// JSON Schema document for each line of `--format ndjson` output.
+pub fn ndjson_schema() -> &'static str {
+    NDJSON_V1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::ExitPolicy;
+    use crate::render::{render_json, render_json_v2};
+    use fspec_core::{Diagnostic, EntryKind, MatchSettings, Report, ReportEntry, Severity, Status};
+    use serde_json::Value;
+
+    fn sample_report() -> Report {
+        let mut report = Report::default();
+        report.set_entry(
+            "movies/1999/a_1999.mkv",
+            ReportEntry {
+                status: Status::Allowed,
+                kind: Some(EntryKind::File),
+                rule_line: Some(2),
+                captures: [("year".to_string(), "1999".to_string())].into(),
+            },
+        );
+        report.set_entry(
+            "movies/1999",
+            ReportEntry {
+                status: Status::Allowed,
+                kind: Some(EntryKind::Dir),
+                rule_line: None,
+                captures: Default::default(),
+            },
+        );
+        report.set_status("stray.txt", Status::Unaccounted);
+        report.push_diagnostic(Diagnostic {
+            code: "ambiguous_match",
+            severity: Severity::Warning,
+            path: "movies/1999/a_1999.mkv".into(),
+            message: "matched by several rules".into(),
+            rule_lines: vec![2, 5],
+        });
+        report
+    }
+
+    fn assert_valid(schema: &str, rendered: &str) {
+        let schema: Value = serde_json::from_str(schema).unwrap();
+        let instance: Value = serde_json::from_str(rendered).unwrap();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        let errors: Vec<String> = validator
+            .iter_errors(&instance)
+            .map(|e| format!("{} at {}", e, e.instance_path))
+            .collect();
+        assert!(errors.is_empty(), "schema violations: {errors:#?}");
+    }
+
+    #[test]
+    fn schemas_are_valid_json_with_matching_versions() {
+        for (version, id) in [
+            (ReportVersion::V1, "fspec.report.v1"),
+            (ReportVersion::V2, "fspec.report.v2"),
+        ] {
+            let v: Value = serde_json::from_str(report_schema(version)).unwrap();
+            assert_eq!(v["properties"]["schema_version"]["const"], id);
+        }
+    }
+
+    #[test]
+    fn v1_output_matches_schema() {
+        let settings = MatchSettings::default();
+        let policy = ExitPolicy::default();
+        assert_valid(
+            report_schema(ReportVersion::V1),
+            &render_json(&sample_report(), &settings, &policy),
+        );
+        assert_valid(
+            report_schema(ReportVersion::V1),
+            &render_json(&Report::default(), &settings, &policy),
+        );
+    }
+
+    #[test]
+    fn v2_output_matches_schema() {
+        let settings = MatchSettings::default();
+        let policy = ExitPolicy::default();
+        assert_valid(
+            report_schema(ReportVersion::V2),
+            &render_json_v2(&sample_report(), &settings, &policy),
+        );
+        assert_valid(
+            report_schema(ReportVersion::V2),
+            &render_json_v2(&Report::default(), &settings, &policy),
+        );
+    }
+
+    #[test]
+    fn ndjson_lines_match_schema() {
+        use crate::ndjson::{NdjsonWriter, SCHEMA_VERSION_NDJSON};
+        use fspec_core::{WalkEntry, WalkVisitor};
+        use std::path::Path;
+
+        let v: Value = serde_json::from_str(ndjson_schema()).unwrap();
+        let summary = &v["$defs"]["summary"]["properties"]["schema_version"]["const"];
+        assert_eq!(summary, SCHEMA_VERSION_NDJSON);
+
+        let mut buf = Vec::new();
+        let mut w = NdjsonWriter::new(&mut buf, None, ExitPolicy::default());
+        for d in sample_report().diagnostics() {
+            w.diagnostic(d.clone());
+        }
+        w.entry(&WalkEntry {
+            path: Path::new("stray.txt"),
+            kind: EntryKind::File,
+            status: Status::Unaccounted,
+            decided_by: None,
+        });
+        w.finish().unwrap();
+
+        let out = String::from_utf8(buf).unwrap();
+        assert_eq!(out.lines().count(), 3);
+        for line in out.lines() {
+            assert_valid(ndjson_schema(), line);
+        }
+    }
+
+    #[test]
+    fn schemas_reject_the_other_version() {
+        let settings = MatchSettings::default();
+        let policy = ExitPolicy::default();
+        let schema: Value = serde_json::from_str(report_schema(ReportVersion::V1)).unwrap();
+        let v2: Value =
+            serde_json::from_str(&render_json_v2(&sample_report(), &settings, &policy)).unwrap();
+        assert!(!jsonschema::is_valid(&schema, &v2));
+    }
+}
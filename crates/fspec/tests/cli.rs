@@ -0,0 +1,142 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn temp_tree(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("fspec-cli-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    for (path, contents) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    dir
+}
+
+fn fspec(root: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_fspec"))
+        .arg(root)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn preset_rules_are_not_reported_as_the_users_rules() {
+    let root = temp_tree(
+        "presets",
+        &[
+            (".fspec", "allow ./a.txt\n"),
+            ("a.txt", ""),
+            (".git/HEAD", ""),
+        ],
+    );
+
+    let out = fspec(
+        &root,
+        &[
+            "--no-config",
+            "--ignore-preset",
+            "vcs",
+            "--fail-on-unused-rules",
+            "--format",
+            "json",
+            "--report-version",
+            "v2",
+        ],
+    );
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(out.status.code(), Some(0), "{stdout}");
+    assert!(!stdout.contains("unused_rule"), "{stdout}");
+
+    let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let git = report["entries"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|e| e["path"] == ".git")
+        .unwrap();
+    assert_eq!(git["status"], "ignored");
+    assert_eq!(git["rule_line"], serde_json::Value::Null);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn the_loaded_config_file_is_not_part_of_the_tree() {
+    let root = temp_tree(
+        "config",
+        &[
+            (".fspec", "allow ./a.txt\n"),
+            ("a.txt", ""),
+            ("fspec.toml", "[exit]\nmax-unaccounted = 0\n"),
+            ("sub/fspec.toml", ""),
+        ],
+    );
+
+    let out = fspec(&root, &["--max-unaccounted", "2"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(out.status.code(), Some(0), "{stdout}");
+    assert!(!stdout.contains("unaccounted: fspec.toml"), "{stdout}");
+    // only the file that was loaded is left out
+    assert!(stdout.contains("sub/fspec.toml"), "{stdout}");
+
+    // without a config, it's a path like any other
+    let out = fspec(&root, &["--no-config", "--max-unaccounted", "3"]);
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("unaccounted: fspec.toml"), "{stdout}");
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn a_directory_named_like_a_subcommand_is_scanned_when_written_as_a_path() {
+    let root = temp_tree(
+        "subcommand-dir",
+        &[("lint/.fspec", "allow ./a.txt\n"), ("lint/a.txt", "")],
+    );
+
+    let out = Command::new(env!("CARGO_BIN_EXE_fspec"))
+        .current_dir(&root)
+        .args(["./lint", "--no-config", "--format", "json"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(out.status.code(), Some(0), "{stdout}");
+    let report: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(report["ok"], true);
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn json_ok_follows_the_exit_policy() {
+    let root = temp_tree(
+        "ok",
+        &[
+            (".fspec", "allow ./a.txt\nallow ./missing.txt\n"),
+            ("a.txt", ""),
+            ("b.txt", ""),
+            ("c.txt", ""),
+        ],
+    );
+
+    for (args, code) in [
+        (&["--max-unaccounted", "5"][..], 0),
+        (&["--max-unaccounted", "1"][..], 1),
+        (&["--max-unaccounted", "5", "--fail-on-unused-rules"][..], 1),
+    ] {
+        for format in ["json", "ndjson"] {
+            let out = fspec(
+                &root,
+                &[&["--no-config", "--format", format], args].concat(),
+            );
+            let stdout = String::from_utf8_lossy(&out.stdout);
+            assert_eq!(out.status.code(), Some(code), "{format} {args:?}: {stdout}");
+            let record = match format {
+                "json" => stdout.to_string(),
+                _ => stdout.lines().last().unwrap().to_string(),
+            };
+            let report: serde_json::Value = serde_json::from_str(&record).unwrap();
+            assert_eq!(report["ok"], code == 0, "{format} {args:?}: {stdout}");
+        }
+    }
+    fs::remove_dir_all(&root).unwrap();
+}